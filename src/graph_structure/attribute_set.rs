@@ -0,0 +1,310 @@
+use anyhow::Result;
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Topological operation creating new elements from existing ones
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AttributeEvent {
+    /// Elements created from scratch (no parent)
+    Creation,
+    /// Elements created by flipping an edge (parents are the two flipped elements)
+    Flip,
+    /// Elements created by splitting an element (parent is the split element)
+    Split,
+}
+
+/// Rule computing value of a new element, from event and parent values
+pub type AttributeRule<T> = Arc<dyn Fn(AttributeEvent, &[T]) -> T + Send + Sync>;
+
+/// Type erased storage of one attribute (one value per element)
+trait AttributeStorage: Send + Sync {
+    /// Resizes storage, new elements get the default value
+    fn resize(&mut self, nb_elements: usize);
+
+    /// Resets element value to default
+    fn reset_element(&mut self, ind_element: usize);
+
+    /// Copies value of an element to another one
+    fn move_element(&mut self, ind_from: usize, ind_to: usize);
+
+    /// Moves and creates elements during a topological operation
+    ///
+    /// All values are read before being written, so parents and moved elements
+    /// can be overwritten by the same operation
+    fn update_elements(
+        &mut self,
+        event: AttributeEvent,
+        parents: &[usize],
+        moved: &[(usize, usize)],
+        created: &[usize],
+    );
+
+    fn clone_box(&self) -> Box<dyn AttributeStorage>;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// Values of one attribute, with its default value and optional rule
+struct AttributeValues<T> {
+    default: T,
+    rule: Option<AttributeRule<T>>,
+    values: Vec<T>,
+}
+
+impl<T: Clone + Send + Sync + 'static> AttributeStorage for AttributeValues<T> {
+    fn resize(&mut self, nb_elements: usize) {
+        self.values.resize(nb_elements, self.default.clone());
+    }
+
+    fn reset_element(&mut self, ind_element: usize) {
+        self.values[ind_element] = self.default.clone();
+    }
+
+    fn move_element(&mut self, ind_from: usize, ind_to: usize) {
+        self.values[ind_to] = self.values[ind_from].clone();
+    }
+
+    fn update_elements(
+        &mut self,
+        event: AttributeEvent,
+        parents: &[usize],
+        moved: &[(usize, usize)],
+        created: &[usize],
+    ) {
+        let value_created = if let Some(rule) = &self.rule {
+            let parent_values: Vec<T> = parents
+                .iter()
+                .map(|&ind| self.values[ind].clone())
+                .collect();
+            rule(event, &parent_values)
+        } else {
+            self.default.clone()
+        };
+        let moved_values: Vec<T> = moved
+            .iter()
+            .map(|&(ind_from, _)| self.values[ind_from].clone())
+            .collect();
+
+        for (&(_, ind_to), value) in moved.iter().zip(moved_values) {
+            self.values[ind_to] = value;
+        }
+        for &ind in created.iter() {
+            self.values[ind] = value_created.clone();
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn AttributeStorage> {
+        Box::new(AttributeValues {
+            default: self.default.clone(),
+            rule: self.rule.clone(),
+            values: self.values.clone(),
+        })
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Attribute set: stores user values attributed to simplicial elements (nodes, tetrahedra, ...)
+///
+/// Storage is kept in sync with the simplicial structure: elements are resized, moved and
+/// reset by the same methods that modify the topology.
+/// Elements created by a topological operation get either the default value,
+/// or the value computed by the attribute rule.
+pub struct AttributeSet {
+    /// Attribute values, by name
+    attributes: HashMap<String, Box<dyn AttributeStorage>>,
+
+    /// Number of elements
+    nb_elements: usize,
+}
+
+impl AttributeSet {
+    /// Attribute set constructor
+    pub fn new(nb_elements: usize) -> AttributeSet {
+        AttributeSet {
+            attributes: HashMap::new(),
+            nb_elements,
+        }
+    }
+
+    /// Gets number of elements
+    pub fn get_nb_elements(&self) -> usize {
+        self.nb_elements
+    }
+
+    /// Checks if an attribute exists
+    pub fn has_attribute(&self, attribute_name: &str) -> bool {
+        self.attributes.contains_key(attribute_name)
+    }
+
+    /// Adds an attribute with a default value (replaces existing attribute with same name)
+    pub fn add_attribute<T: Clone + Send + Sync + 'static>(
+        &mut self,
+        attribute_name: String,
+        attribute_default: T,
+    ) {
+        let values = vec![attribute_default.clone(); self.nb_elements];
+        self.attributes.insert(
+            attribute_name,
+            Box::new(AttributeValues {
+                default: attribute_default,
+                rule: None,
+                values,
+            }),
+        );
+    }
+
+    /// Adds an attribute with a default value, and a rule describing how values carry over
+    /// when elements are created, flipped or split (replaces existing attribute with same name)
+    ///
+    /// The rule gets the operation and the values of the parent elements
+    /// (empty for created elements without parent)
+    pub fn add_attribute_with_rule<T, F>(
+        &mut self,
+        attribute_name: String,
+        attribute_default: T,
+        attribute_rule: F,
+    ) where
+        T: Clone + Send + Sync + 'static,
+        F: Fn(AttributeEvent, &[T]) -> T + Send + Sync + 'static,
+    {
+        let values = vec![attribute_default.clone(); self.nb_elements];
+        self.attributes.insert(
+            attribute_name,
+            Box::new(AttributeValues {
+                default: attribute_default,
+                rule: Some(Arc::new(attribute_rule)),
+                values,
+            }),
+        );
+    }
+
+    /// Removes an attribute
+    pub fn remove_attribute(&mut self, attribute_name: &str) -> Result<()> {
+        self.attributes
+            .remove(attribute_name)
+            .ok_or(anyhow::Error::msg(
+                "No attribute ".to_owned() + attribute_name + " in attribute set",
+            ))?;
+        Ok(())
+    }
+
+    fn get_values<T: 'static>(&self, attribute_name: &str) -> Result<&AttributeValues<T>> {
+        self.attributes
+            .get(attribute_name)
+            .ok_or(anyhow::Error::msg(
+                "No attribute ".to_owned() + attribute_name + " in attribute set",
+            ))?
+            .as_any()
+            .downcast_ref::<AttributeValues<T>>()
+            .ok_or(anyhow::Error::msg(
+                "Attribute ".to_owned() + attribute_name + " is not of requested type",
+            ))
+    }
+
+    fn get_values_mut<T: 'static>(
+        &mut self,
+        attribute_name: &str,
+    ) -> Result<&mut AttributeValues<T>> {
+        self.attributes
+            .get_mut(attribute_name)
+            .ok_or(anyhow::Error::msg(
+                "No attribute ".to_owned() + attribute_name + " in attribute set",
+            ))?
+            .as_any_mut()
+            .downcast_mut::<AttributeValues<T>>()
+            .ok_or(anyhow::Error::msg(
+                "Attribute ".to_owned() + attribute_name + " is not of requested type",
+            ))
+    }
+
+    /// Sets an attribute value
+    pub fn set_attribute_value<T: 'static>(
+        &mut self,
+        ind_element: usize,
+        attribute_name: &str,
+        attribute_value: T,
+    ) -> Result<()> {
+        if ind_element >= self.nb_elements {
+            return Err(anyhow::Error::msg("Index out of bounds"));
+        }
+        self.get_values_mut::<T>(attribute_name)?.values[ind_element] = attribute_value;
+        Ok(())
+    }
+
+    /// Gets an attribute value
+    pub fn get_attribute_value<T: 'static>(
+        &self,
+        ind_element: usize,
+        attribute_name: &str,
+    ) -> Result<&T> {
+        if ind_element >= self.nb_elements {
+            return Err(anyhow::Error::msg("Index out of bounds"));
+        }
+        Ok(&self.get_values::<T>(attribute_name)?.values[ind_element])
+    }
+
+    /// Gets all values of an attribute
+    pub fn get_attribute_values<T: 'static>(&self, attribute_name: &str) -> Result<&[T]> {
+        Ok(&self.get_values::<T>(attribute_name)?.values)
+    }
+
+    /// Resizes all attributes, new elements get default values
+    pub(crate) fn resize(&mut self, nb_elements: usize) {
+        self.nb_elements = nb_elements;
+        for (_, storage) in self.attributes.iter_mut() {
+            storage.resize(nb_elements);
+        }
+    }
+
+    /// Resets all attributes of an element to their default values
+    pub(crate) fn reset_element(&mut self, ind_element: usize) {
+        for (_, storage) in self.attributes.iter_mut() {
+            storage.reset_element(ind_element);
+        }
+    }
+
+    /// Copies all attributes of an element to another one
+    pub(crate) fn move_element(&mut self, ind_from: usize, ind_to: usize) {
+        for (_, storage) in self.attributes.iter_mut() {
+            storage.move_element(ind_from, ind_to);
+        }
+    }
+
+    /// Applies a topological operation to all attributes:
+    /// moved elements keep their values, created elements get the rule value
+    pub(crate) fn update_elements(
+        &mut self,
+        event: AttributeEvent,
+        parents: &[usize],
+        moved: &[(usize, usize)],
+        created: &[usize],
+    ) {
+        for (_, storage) in self.attributes.iter_mut() {
+            storage.update_elements(event, parents, moved, created);
+        }
+    }
+}
+
+impl Clone for AttributeSet {
+    fn clone(&self) -> AttributeSet {
+        AttributeSet {
+            attributes: self
+                .attributes
+                .iter()
+                .map(|(key, storage)| (key.clone(), storage.clone_box()))
+                .collect(),
+            nb_elements: self.nb_elements,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use anyhow::Result;
+use nalgebra::base::*;
+use std::io::{Read, Write};
+
+/// Current version of simplicial binary files
+pub(crate) const BINARY_FORMAT_VERSION: u32 = 1;
+
+pub(crate) fn write_header(writer: &mut impl Write, magic: &[u8; 4]) -> Result<()> {
+    writer.write_all(magic)?;
+    writer.write_all(&BINARY_FORMAT_VERSION.to_le_bytes())?;
+    Ok(())
+}
+
+pub(crate) fn read_header(reader: &mut impl Read, magic: &[u8; 4]) -> Result<()> {
+    let mut magic_read = [0u8; 4];
+    reader.read_exact(&mut magic_read)?;
+    if &magic_read != magic {
+        return Err(anyhow::Error::msg("Wrong file type"));
+    }
+    let mut version = [0u8; 4];
+    reader.read_exact(&mut version)?;
+    if u32::from_le_bytes(version) != BINARY_FORMAT_VERSION {
+        return Err(anyhow::Error::msg("Unsupported file version"));
+    }
+    Ok(())
+}
+
+pub(crate) fn write_u64(writer: &mut impl Write, value: usize) -> Result<()> {
+    writer.write_all(&(value as u64).to_le_bytes())?;
+    Ok(())
+}
+
+pub(crate) fn read_u64(reader: &mut impl Read) -> Result<usize> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes) as usize)
+}
+
+pub(crate) fn write_u64_vec(writer: &mut impl Write, values: &[usize]) -> Result<()> {
+    for &value in values.iter() {
+        write_u64(writer, value)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn read_u64_vec(reader: &mut impl Read, nb_values: usize) -> Result<Vec<usize>> {
+    let mut values = Vec::new();
+    for _ in 0..nb_values {
+        values.push(read_u64(reader)?);
+    }
+    Ok(values)
+}
+
+pub(crate) fn write_u8_vec(writer: &mut impl Write, values: &[usize]) -> Result<()> {
+    let bytes: Vec<u8> = values.iter().map(|&v| v as u8).collect();
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+pub(crate) fn read_u8_vec(reader: &mut impl Read, nb_values: usize) -> Result<Vec<usize>> {
+    let mut bytes = Vec::new();
+    reader.take(nb_values as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != nb_values {
+        return Err(anyhow::Error::msg("Unexpected end of file"));
+    }
+    Ok(bytes.iter().map(|&v| v as usize).collect())
+}
+
+/// Writes optional node coordinates (flag, dimension, number of nodes, coordinates)
+pub(crate) fn write_coords<const D: usize>(
+    writer: &mut impl Write,
+    node_coords: Option<&Vec<SVector<f64, D>>>,
+) -> Result<()> {
+    if let Some(coords) = node_coords {
+        writer.write_all(&[1u8])?;
+        write_u64(writer, D)?;
+        write_u64(writer, coords.len())?;
+        for coord in coords.iter() {
+            for i in 0..D {
+                writer.write_all(&coord[i].to_le_bytes())?;
+            }
+        }
+    } else {
+        writer.write_all(&[0u8])?;
+    }
+    Ok(())
+}
+
+/// Reads optional node coordinates
+pub(crate) fn read_coords<const D: usize>(
+    reader: &mut impl Read,
+) -> Result<Option<Vec<SVector<f64, D>>>> {
+    let mut flag = [0u8; 1];
+    reader.read_exact(&mut flag)?;
+    if flag[0] == 0 {
+        return Ok(None);
+    }
+    let dim = read_u64(reader)?;
+    if dim != D {
+        return Err(anyhow::Error::msg(format!(
+            "Node coordinates dimension is {}, expected {}",
+            dim, D
+        )));
+    }
+    let nb_nodes = read_u64(reader)?;
+    let mut coords = Vec::new();
+    let mut bytes = [0u8; 8];
+    for _ in 0..nb_nodes {
+        let mut coord = SVector::<f64, D>::zeros();
+        for i in 0..D {
+            reader.read_exact(&mut bytes)?;
+            coord[i] = f64::from_le_bytes(bytes);
+        }
+        coords.push(coord);
+    }
+    Ok(Some(coords))
+}
@@ -3,3 +3,12 @@ pub mod simplicial2;
 
 /// Simplicial 3D object and operations
 pub mod simplicial3;
+
+/// Attribute set attached to simplicial elements
+pub mod attribute_set;
+
+/// Binary helpers shared by simplicial input/output functions
+mod binary_io;
+
+/// Union-find helper shared by topological functions
+mod union_find;
@@ -22,37 +22,70 @@ impl<'a> IterDiagHalfEdge2<'a> {
     }
 
     /// Get opposite diagram halfedge
+    ///
+    /// Panics if dual halfedge is on a boundary, see [`try_opposite`](Self::try_opposite)
     pub fn opposite(&self) -> IterDiagHalfEdge2<'a> {
         self.dual().opposite().dual()
     }
 
+    /// Get opposite diagram halfedge (None if dual halfedge is on a boundary)
+    pub fn try_opposite(&self) -> Option<IterDiagHalfEdge2<'a>> {
+        self.dual().try_opposite().map(|he| he.dual())
+    }
+
     /// Get first node of the diagram halfedge
     pub fn first_node(&self) -> IterDiagNode2<'a> {
         self.dual().triangle().dual()
     }
 
     /// Get last node of the diagram halfedge
+    ///
+    /// Panics if dual halfedge is on a boundary, see [`try_last_node`](Self::try_last_node)
     pub fn last_node(&self) -> IterDiagNode2<'a> {
         self.dual().opposite().triangle().dual()
     }
 
+    /// Get last node of the diagram halfedge (None if dual halfedge is on a boundary)
+    pub fn try_last_node(&self) -> Option<IterDiagNode2<'a>> {
+        self.dual().try_opposite().map(|he| he.triangle().dual())
+    }
+
     /// Next diagram edge
+    ///
+    /// Panics if dual halfedge is on a boundary, see [`try_next`](Self::try_next)
     pub fn next(&self) -> IterDiagHalfEdge2<'a> {
         self.dual().opposite().next().dual()
     }
 
+    /// Next diagram edge (None if dual halfedge is on a boundary)
+    pub fn try_next(&self) -> Option<IterDiagHalfEdge2<'a>> {
+        self.dual().try_opposite().map(|he| he.next().dual())
+    }
+
     /// Previous diagram edge
+    ///
+    /// Panics if previous dual halfedge is on a boundary, see
+    /// [`try_previous`](Self::try_previous)
     pub fn previous(&self) -> IterDiagHalfEdge2<'a> {
         self.dual().previous().opposite().dual()
     }
 
+    /// Previous diagram edge (None if previous dual halfedge is on a boundary)
+    pub fn try_previous(&self) -> Option<IterDiagHalfEdge2<'a>> {
+        self.dual().previous().try_opposite().map(|he| he.dual())
+    }
+
     /// Halfedge to string
     pub fn to_string(&self) -> String {
-        format!(
-            "Diagram Edge {} -> {}",
-            self.first_node().index(),
-            self.last_node().index()
-        )
+        if let Some(last_node) = self.try_last_node() {
+            format!(
+                "Diagram Edge {} -> {}",
+                self.first_node().index(),
+                last_node.index()
+            )
+        } else {
+            format!("Diagram Edge {} -> boundary", self.first_node().index())
+        }
     }
 
     /// Print halfedge string
@@ -48,11 +48,32 @@ impl<'a> IterHalfEdge2<'a> {
     }
 
     /// Opposite halfedge: Same vertices in opposite order (on neighbor triangle)
+    ///
+    /// Panics for boundary halfedges, see [`try_opposite`](IterHalfEdge2::try_opposite)
     pub fn opposite(&self) -> IterHalfEdge2<'a> {
         let ind_opp = self.simplicial.halfedge_opposite_index(self.ind_halfedge);
         IterHalfEdge2::new(self.simplicial, ind_opp)
     }
 
+    /// Opposite halfedge, None for boundary halfedges
+    pub fn try_opposite(&self) -> Option<IterHalfEdge2<'a>> {
+        self.simplicial
+            .try_halfedge_opposite_index(self.ind_halfedge)
+            .map(|ind_opp| IterHalfEdge2::new(self.simplicial, ind_opp))
+    }
+
+    /// Checks if halfedge is on a boundary (no opposite halfedge)
+    pub fn is_boundary(&self) -> bool {
+        self.simplicial.halfedge_is_boundary(self.ind_halfedge)
+    }
+
+    /// Next boundary halfedge, starting at last node (None if halfedge is not on a boundary)
+    pub fn next_boundary(&self) -> Option<IterHalfEdge2<'a>> {
+        self.simplicial
+            .halfedge_next_boundary_index(self.ind_halfedge)
+            .map(|ind_he| IterHalfEdge2::new(self.simplicial, ind_he))
+    }
+
     /// Triangle containing halfedge
     pub fn triangle(&self) -> IterTriangle2<'a> {
         let ind_triangle = self.simplicial.halfedge_triangle_index(self.ind_halfedge);
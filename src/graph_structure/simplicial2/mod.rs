@@ -32,4 +32,10 @@ pub mod simplicial_2_build;
 /// Set of functions to test 2D simmplicial
 pub mod simplicial_2_quality;
 
+/// Binary input/output functions
+pub mod simplicial_2_io;
+
+/// Topological invariants
+pub mod simplicial_2_topology;
+
 mod unit_tests;
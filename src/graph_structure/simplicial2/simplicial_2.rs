@@ -1,11 +1,18 @@
 use anyhow::Result;
 
+use crate::graph_structure::attribute_set::AttributeSet;
+
 use super::IterHalfEdge2;
 use super::IterNode2;
 use super::IterTriangle2;
 
+/// Opposite value of boundary halfedges (halfedges with no opposite)
+pub(super) const NO_OPPOSITE: usize = usize::MAX;
+
 /// 2D Simplicial structure
-/// Always valid (all methods have to let simplicial with no holes, no triple edges)
+/// Always valid (all methods have to let simplicial with no triple edges)
+///
+/// Surfaces may have boundaries: boundary halfedges have no opposite halfedge.
 #[derive(Clone)]
 pub struct Simplicial2 {
     // i   : he1 \
@@ -16,12 +23,18 @@ pub struct Simplicial2 {
     // such that he1 = next(he3)
     // only first node is stored (last node is the next one, taking account of the %3)
     pub(super) halfedge_first_node: Vec<usize>,
+    // opposite halfedge, NO_OPPOSITE for boundary halfedges
     pub(super) halfedge_opposite: Vec<usize>,
 
     // optional attribute, containing indices of halfedges starting at given node
     pub(super) node_halfedges: Option<Vec<Vec<usize>>>,
 
     pub(super) nb_triangles: usize,
+
+    /// User attributes on triangles (indexed by triangle index)
+    pub(super) triangle_attributes: AttributeSet,
+    /// User attributes on halfedges (indexed by halfedge index)
+    pub(super) halfedge_attributes: AttributeSet,
 }
 
 impl Simplicial2 {
@@ -37,6 +50,8 @@ impl Simplicial2 {
             halfedge_opposite: Vec::new(),
             node_halfedges,
             nb_triangles: 0,
+            triangle_attributes: AttributeSet::new(0),
+            halfedge_attributes: AttributeSet::new(0),
         }
     }
 
@@ -85,9 +100,11 @@ impl Simplicial2 {
             if self.halfedge_last_node_value(ind_he_next) == node2 {
                 return Some(self.halfedge_triangle_index(ind_he));
             }
-            let ind_he_opp_next = self.halfedge_next_index(self.halfedge_opposite_index(ind_he));
-            if self.halfedge_last_node_value(ind_he_opp_next) == node2 {
-                return Some(self.halfedge_triangle_index(ind_he_opp_next));
+            if let Some(ind_he_opp) = self.try_halfedge_opposite_index(ind_he) {
+                let ind_he_opp_next = self.halfedge_next_index(ind_he_opp);
+                if self.halfedge_last_node_value(ind_he_opp_next) == node2 {
+                    return Some(self.halfedge_triangle_index(ind_he_opp_next));
+                }
             }
         }
 
@@ -115,6 +132,9 @@ impl Simplicial2 {
     }
 
     /// Gets node halfedges indices
+    ///
+    /// Without node registration, halfedges are found by turning around the node,
+    /// in both directions if the node is on a boundary.
     pub fn node_halfedge_indices(&self, ind_node: usize) -> Vec<usize> {
         if let Some(vec) = &self.node_halfedges {
             let nod_val = self.halfedge_first_node[ind_node];
@@ -124,10 +144,23 @@ impl Simplicial2 {
             let ind_he = ind_node;
 
             vec_he.push(ind_he);
-            let mut ind_he_cur = self.halfedge_next_index(self.halfedge_opposite_index(ind_he));
-            while ind_he_cur != ind_he {
+            // forward turn
+            let mut ind_he_cur = ind_he;
+            while let Some(ind_he_opp) = self.try_halfedge_opposite_index(ind_he_cur) {
+                ind_he_cur = self.halfedge_next_index(ind_he_opp);
+                if ind_he_cur == ind_he {
+                    return vec_he;
+                }
                 vec_he.push(ind_he_cur);
-                ind_he_cur = self.halfedge_next_index(self.halfedge_opposite_index(ind_he_cur));
+            }
+
+            // boundary reached, backward turn from starting halfedge
+            ind_he_cur = ind_he;
+            while let Some(ind_he_opp) =
+                self.try_halfedge_opposite_index(self.halfedge_previous_index(ind_he_cur))
+            {
+                ind_he_cur = ind_he_opp;
+                vec_he.insert(0, ind_he_cur);
             }
 
             vec_he
@@ -168,8 +201,62 @@ impl Simplicial2 {
     }
 
     /// Gets halfedge opposite halfedge index
+    ///
+    /// Panics for boundary halfedges, see
+    /// [`try_halfedge_opposite_index`](Simplicial2::try_halfedge_opposite_index)
     pub fn halfedge_opposite_index(&self, ind_he: usize) -> usize {
-        self.halfedge_opposite[ind_he]
+        self.try_halfedge_opposite_index(ind_he)
+            .expect("Boundary halfedge has no opposite")
+    }
+
+    /// Gets halfedge opposite halfedge index (None for boundary halfedges)
+    pub fn try_halfedge_opposite_index(&self, ind_he: usize) -> Option<usize> {
+        let ind_he_opp = self.halfedge_opposite[ind_he];
+        if ind_he_opp == NO_OPPOSITE {
+            None
+        } else {
+            Some(ind_he_opp)
+        }
+    }
+
+    /// Checks if halfedge is on a boundary (no opposite halfedge)
+    pub fn halfedge_is_boundary(&self, ind_he: usize) -> bool {
+        self.halfedge_opposite[ind_he] == NO_OPPOSITE
+    }
+
+    /// Gets next boundary halfedge index, starting at last node of given boundary halfedge
+    ///
+    /// Returns None if halfedge is not on a boundary
+    pub fn halfedge_next_boundary_index(&self, ind_he: usize) -> Option<usize> {
+        if !self.halfedge_is_boundary(ind_he) {
+            return None;
+        }
+        let mut ind_he_cur = self.halfedge_next_index(ind_he);
+        while let Some(ind_he_opp) = self.try_halfedge_opposite_index(ind_he_cur) {
+            ind_he_cur = self.halfedge_next_index(ind_he_opp);
+        }
+        Some(ind_he_cur)
+    }
+
+    /// Gets boundary loops, as lists of boundary halfedges indices
+    pub fn boundary_loop_indices(&self) -> Vec<Vec<usize>> {
+        let mut visited = vec![false; self.get_nb_halfedges()];
+        let mut loops = Vec::new();
+        for ind_he in 0..self.get_nb_halfedges() {
+            if visited[ind_he] || !self.halfedge_is_boundary(ind_he) {
+                continue;
+            }
+            let mut boundary_loop = Vec::new();
+            let mut ind_he_cur = ind_he;
+            while !visited[ind_he_cur] {
+                visited[ind_he_cur] = true;
+                boundary_loop.push(ind_he_cur);
+                // boundary halfedge always has a next boundary halfedge
+                ind_he_cur = self.halfedge_next_boundary_index(ind_he_cur).unwrap();
+            }
+            loops.push(boundary_loop);
+        }
+        loops
     }
 
     /// Gets halfedge triangle index
@@ -221,6 +308,19 @@ impl Simplicial2 {
             .collect()
     }
 
+    /// Gets boundary loops, as lists of boundary halfedge iterators
+    pub fn get_boundary_loops(&self) -> Vec<Vec<IterHalfEdge2<'_>>> {
+        self.boundary_loop_indices()
+            .iter()
+            .map(|boundary_loop| {
+                boundary_loop
+                    .iter()
+                    .map(|&ind_he| IterHalfEdge2::new(self, ind_he))
+                    .collect()
+            })
+            .collect()
+    }
+
     /// Checks if a node is in the simplicial
     ///
     /// Returns halfedge iterator if found
@@ -253,6 +353,26 @@ impl Simplicial2 {
         }
     }
 
+    /// Gets triangle attributes (indexed by triangle index)
+    pub fn get_triangle_attributes(&self) -> &AttributeSet {
+        &self.triangle_attributes
+    }
+
+    /// Gets mutable triangle attributes (indexed by triangle index)
+    pub fn get_triangle_attributes_mut(&mut self) -> &mut AttributeSet {
+        &mut self.triangle_attributes
+    }
+
+    /// Gets halfedge attributes (indexed by halfedge index)
+    pub fn get_halfedge_attributes(&self) -> &AttributeSet {
+        &self.halfedge_attributes
+    }
+
+    /// Gets mutable halfedge attributes (indexed by halfedge index)
+    pub fn get_halfedge_attributes_mut(&mut self) -> &mut AttributeSet {
+        &mut self.halfedge_attributes
+    }
+
     /// Println each triangle of the graph
     pub fn println(&self) -> () {
         for ind_tri in 0..self.nb_triangles {
@@ -1,7 +1,12 @@
 use anyhow::Result;
 
+use super::simplicial_2::NO_OPPOSITE;
 use super::Simplicial2;
+use crate::graph_structure::attribute_set::AttributeEvent;
+use crate::graph_structure::union_find::UnionFind;
 use std::cmp::max;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 /////////////////////////////
 /// Private build methods ///
@@ -17,6 +22,9 @@ fn add_empty_triangle(simpl: &mut Simplicial2) -> usize {
 
     simpl.nb_triangles = simpl.nb_triangles + 1;
 
+    simpl.triangle_attributes.resize(simpl.nb_triangles);
+    simpl.halfedge_attributes.resize(simpl.nb_triangles * 3);
+
     simpl.nb_triangles - 1
 }
 
@@ -59,9 +67,69 @@ fn unset_triangle(simpl: &mut Simplicial2, ind_tri: usize) -> usize {
     ind_tri
 }
 
+/// Removes triangle, replacing it by last triangle
+///
+/// Halfedges of removed triangle must not be referenced as opposite anymore.
+/// Returns former index of moved triangle, if any.
+fn remove_triangle(simpl: &mut Simplicial2, ind_tri: usize) -> Option<usize> {
+    unset_triangle(simpl, ind_tri);
+    let ind_tri_last = simpl.nb_triangles - 1;
+
+    let moved = if ind_tri != ind_tri_last {
+        let [nod1, nod2, nod3] = simpl.triangle_node_values(ind_tri_last);
+        let ind_he_last = simpl.triangle_halfedge_indices(ind_tri_last);
+        let ind_he_opp = ind_he_last.map(|ind_he| simpl.halfedge_opposite[ind_he]);
+
+        unset_triangle(simpl, ind_tri_last);
+        let ind_he_new = set_triangle(simpl, ind_tri, nod1, nod2, nod3);
+        for i in 0..3 {
+            oppose_halfedges(simpl, ind_he_new[i], ind_he_opp[i]);
+            simpl
+                .halfedge_attributes
+                .move_element(ind_he_last[i], ind_he_new[i]);
+        }
+        simpl
+            .triangle_attributes
+            .move_element(ind_tri_last, ind_tri);
+        Some(ind_tri_last)
+    } else {
+        None
+    };
+
+    simpl.halfedge_first_node.truncate(ind_tri_last * 3);
+    simpl.halfedge_opposite.truncate(ind_tri_last * 3);
+    simpl.nb_triangles = ind_tri_last;
+
+    simpl.triangle_attributes.resize(simpl.nb_triangles);
+    simpl.halfedge_attributes.resize(simpl.nb_triangles * 3);
+
+    moved
+}
+
+/// Gets nodes linked to the first node of a halfedge
+///
+/// Boundary is represented by NO_OPPOSITE value (as a virtual node linked to boundary nodes)
+fn node_link(simpl: &Simplicial2, ind_he: usize) -> HashSet<usize> {
+    let mut link = HashSet::new();
+    for ind_he_out in simpl.node_halfedge_indices(ind_he) {
+        let ind_he_in = simpl.halfedge_previous_index(ind_he_out);
+        link.insert(simpl.halfedge_last_node_value(ind_he_out));
+        link.insert(simpl.halfedge_first_node_value(ind_he_in));
+        if simpl.halfedge_is_boundary(ind_he_out) || simpl.halfedge_is_boundary(ind_he_in) {
+            link.insert(NO_OPPOSITE);
+        }
+    }
+    link
+}
+
+/// Opposes two halfedges (any of them can be NO_OPPOSITE, to set a boundary)
 fn oppose_halfedges(simpl: &mut Simplicial2, he0: usize, he1: usize) {
-    simpl.halfedge_opposite[he0] = he1;
-    simpl.halfedge_opposite[he1] = he0;
+    if he0 != NO_OPPOSITE {
+        simpl.halfedge_opposite[he0] = he1;
+    }
+    if he1 != NO_OPPOSITE {
+        simpl.halfedge_opposite[he1] = he0;
+    }
 }
 
 ////////////////////////////////
@@ -125,6 +193,19 @@ pub fn insert_first_triangle(simpl: &mut Simplicial2, nodes: [usize; 3]) -> Resu
     oppose_halfedges(simpl, h12, h21);
     oppose_halfedges(simpl, h20, h02);
 
+    simpl.triangle_attributes.update_elements(
+        AttributeEvent::Creation,
+        &[],
+        &[],
+        &[ind_tri0, ind_tri1],
+    );
+    simpl.halfedge_attributes.update_elements(
+        AttributeEvent::Creation,
+        &[],
+        &[],
+        &[h01, h12, h20, h02, h21, h10],
+    );
+
     Ok([ind_tri0, ind_tri1])
 }
 
@@ -135,10 +216,10 @@ pub fn insert_node_within_triangle(
     ind_tri: usize,
 ) -> Result<[usize; 3]> {
     let [n0, n1, n2] = simpl.triangle_node_values(ind_tri);
-    let [h01, h12, h20] = simpl.triangle_halfedge_indices(ind_tri);
-    let h10 = simpl.halfedge_opposite_index(h01);
-    let h21 = simpl.halfedge_opposite_index(h12);
-    let h02 = simpl.halfedge_opposite_index(h20);
+    let [h01_old, h12_old, h20_old] = simpl.triangle_halfedge_indices(ind_tri);
+    let h10 = simpl.halfedge_opposite[h01_old];
+    let h21 = simpl.halfedge_opposite[h12_old];
+    let h02 = simpl.halfedge_opposite[h20_old];
 
     let ind_tri0 = unset_triangle(simpl, ind_tri);
     let ind_tri1 = add_empty_triangle(simpl);
@@ -156,12 +237,27 @@ pub fn insert_node_within_triangle(
     oppose_halfedges(simpl, h1n, hn1);
     oppose_halfedges(simpl, h2n, hn2);
 
+    simpl.triangle_attributes.update_elements(
+        AttributeEvent::Split,
+        &[ind_tri],
+        &[],
+        &[ind_tri0, ind_tri1, ind_tri2],
+    );
+    simpl.halfedge_attributes.update_elements(
+        AttributeEvent::Split,
+        &[],
+        &[(h01_old, h01), (h12_old, h12), (h20_old, h20)],
+        &[h1n, hn0, h2n, hn1, h0n, hn2],
+    );
+
     Ok([ind_tri, simpl.nb_triangles - 2, simpl.nb_triangles - 1])
 }
 
-/// Flips halfedge
+/// Flips halfedge (boundary halfedges cannot be flipped)
 pub fn flip_halfedge(simpl: &mut Simplicial2, ind_he: usize) -> Result<[usize; 2]> {
-    let ind_he_opp = simpl.halfedge_opposite_index(ind_he);
+    let ind_he_opp = simpl
+        .try_halfedge_opposite_index(ind_he)
+        .ok_or(anyhow::Error::msg("Cannot flip boundary halfedge"))?;
 
     // ind_he is ca
     let hab = simpl.halfedge_next_index(ind_he);
@@ -176,14 +272,18 @@ pub fn flip_halfedge(simpl: &mut Simplicial2, ind_he: usize) -> Result<[usize; 2
     let nc = simpl.halfedge_first_node_value(hcd);
     let nd = simpl.halfedge_first_node_value(hda);
 
-    let hba = simpl.halfedge_opposite_index(hab);
-    let hcb = simpl.halfedge_opposite_index(hbc);
-    let hdc = simpl.halfedge_opposite_index(hcd);
-    let had = simpl.halfedge_opposite_index(hda);
+    let hba = simpl.halfedge_opposite[hab];
+    let hcb = simpl.halfedge_opposite[hbc];
+    let hdc = simpl.halfedge_opposite[hcd];
+    let had = simpl.halfedge_opposite[hda];
 
     let ind_tri1 = simpl.halfedge_triangle_index(ind_he);
     let ind_tri2 = simpl.halfedge_triangle_index(ind_he_opp);
 
+    let (hab_old, hbc_old, hcd_old, hda_old) = (hab, hbc, hcd, hda);
+
+    unset_triangle(simpl, ind_tri1);
+    unset_triangle(simpl, ind_tri2);
     let [hbc, hcd, hdb] = set_triangle(simpl, ind_tri1, nb, nc, nd);
     let [hda, hab, hbd] = set_triangle(simpl, ind_tri2, nd, na, nb);
 
@@ -194,43 +294,357 @@ pub fn flip_halfedge(simpl: &mut Simplicial2, ind_he: usize) -> Result<[usize; 2
 
     oppose_halfedges(simpl, hbd, hdb);
 
+    simpl.triangle_attributes.update_elements(
+        AttributeEvent::Flip,
+        &[ind_tri1, ind_tri2],
+        &[],
+        &[ind_tri1, ind_tri2],
+    );
+    simpl.halfedge_attributes.update_elements(
+        AttributeEvent::Flip,
+        &[ind_he, ind_he_opp],
+        &[
+            (hab_old, hab),
+            (hbc_old, hbc),
+            (hcd_old, hcd),
+            (hda_old, hda),
+        ],
+        &[hbd, hdb],
+    );
+
     Ok([hbd, hdb])
 }
 
+/// Triangles modified by a collapse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollapsedTriangles {
+    /// Indices (before collapse) of removed triangles
+    pub removed: Vec<usize>,
+    /// Relocated triangles, as (former index, new index) pairs
+    pub relocated: Vec<(usize, usize)>,
+}
+
+/// Collapses halfedge: its last node is merged into its first node
+///
+/// The collapse is rejected if it does not satisfy the link condition (common neighbours
+/// of the two nodes are the opposite nodes of the edge, boundary being seen as a virtual
+/// node), since it would change the topology of the surface.
+/// Removed triangles are replaced by last triangles of the simplicial.
+pub fn collapse_halfedge(simpl: &mut Simplicial2, ind_he: usize) -> Result<CollapsedTriangles> {
+    if ind_he >= simpl.get_nb_halfedges() {
+        return Err(anyhow::Error::msg("Halfedge index out of bounds"));
+    }
+    let ind_he_opp = simpl.halfedge_opposite[ind_he];
+    let ind_he_bc = simpl.halfedge_next_index(ind_he);
+    let ind_he_ca = simpl.halfedge_previous_index(ind_he);
+    let node_a = simpl.halfedge_first_node_value(ind_he);
+    let node_b = simpl.halfedge_last_node_value(ind_he);
+    let node_c = simpl.halfedge_first_node_value(ind_he_ca);
+    let node_d = if ind_he_opp != NO_OPPOSITE {
+        simpl.halfedge_first_node_value(simpl.halfedge_previous_index(ind_he_opp))
+    } else {
+        NO_OPPOSITE
+    };
+    if node_c == node_d {
+        return Err(anyhow::Error::msg("Collapse would remove whole surface"));
+    }
+
+    // link condition
+    let edge_link = HashSet::from([node_c, node_d]);
+    let link_a = node_link(simpl, ind_he);
+    let link_b = node_link(simpl, ind_he_bc);
+    if link_a
+        .intersection(&link_b)
+        .copied()
+        .collect::<HashSet<usize>>()
+        != edge_link
+    {
+        return Err(anyhow::Error::msg(
+            "Collapse does not satisfy link condition",
+        ));
+    }
+    if ind_he_opp == NO_OPPOSITE
+        && simpl.halfedge_is_boundary(ind_he_bc)
+        && simpl.halfedge_is_boundary(ind_he_ca)
+    {
+        return Err(anyhow::Error::msg("Collapse would leave a dangling edge"));
+    }
+
+    let halfedges_b = simpl.node_halfedge_indices(ind_he_bc);
+
+    // patch opposite links around removed triangles
+    let mut removed = vec![simpl.halfedge_triangle_index(ind_he)];
+    oppose_halfedges(
+        simpl,
+        simpl.halfedge_opposite[ind_he_bc],
+        simpl.halfedge_opposite[ind_he_ca],
+    );
+    if ind_he_opp != NO_OPPOSITE {
+        removed.push(simpl.halfedge_triangle_index(ind_he_opp));
+        let ind_he_ad = simpl.halfedge_next_index(ind_he_opp);
+        let ind_he_db = simpl.halfedge_previous_index(ind_he_opp);
+        oppose_halfedges(
+            simpl,
+            simpl.halfedge_opposite[ind_he_ad],
+            simpl.halfedge_opposite[ind_he_db],
+        );
+    }
+
+    // merge nodes
+    for &ind_he_b in halfedges_b.iter() {
+        simpl.halfedge_first_node[ind_he_b] = node_a;
+    }
+    if let Some(vec) = simpl.node_halfedges.as_mut() {
+        vec[node_b].clear();
+        vec[node_a].extend(halfedges_b.iter());
+    }
+
+    // remove triangles, from last to first
+    let mut to_remove = removed.clone();
+    to_remove.sort();
+    let mut relocated: Vec<(usize, usize)> = Vec::new();
+    for &ind_tri in to_remove.iter().rev() {
+        // a triangle can be relocated twice
+        if let Some(ind_tri_last) = remove_triangle(simpl, ind_tri) {
+            if let Some(reloc) = relocated.iter_mut().find(|reloc| reloc.1 == ind_tri_last) {
+                reloc.1 = ind_tri;
+            } else {
+                relocated.push((ind_tri_last, ind_tri));
+            }
+        }
+    }
+
+    Ok(CollapsedTriangles { removed, relocated })
+}
+
+/// Edge of a triangle list shared by more than two triangles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonManifoldEdge {
+    /// Edge nodes (sorted)
+    pub nodes: [usize; 2],
+    /// Indices (within given list) of triangles containing the edge
+    pub triangles: Vec<usize>,
+}
+
+/// Error returned when a triangle list does not form a closed manifold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonManifoldEdgesError {
+    /// Edges belonging to only one triangle (oriented as in their triangle)
+    pub boundary_edges: Vec<[usize; 2]>,
+    /// Edges shared by more than two triangles
+    pub non_manifold_edges: Vec<NonManifoldEdge>,
+    /// Indices (within given list) of triangles sharing an edge with same orientation
+    pub inconsistent_faces: Vec<usize>,
+}
+
+impl fmt::Display for NonManifoldEdgesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            concat!(
+                "Given faces do not form a manifold simplicial ",
+                "({} boundary edges, {} non manifold edges, {} inconsistent faces)"
+            ),
+            self.boundary_edges.len(),
+            self.non_manifold_edges.len(),
+            self.inconsistent_faces.len()
+        )?;
+        for edge in self.boundary_edges.iter() {
+            write!(f, "\n  boundary edge {:?}", edge)?;
+        }
+        for edge in self.non_manifold_edges.iter() {
+            write!(
+                f,
+                "\n  edge {:?} in triangles {:?}",
+                edge.nodes, edge.triangles
+            )?;
+        }
+        if !self.inconsistent_faces.is_empty() {
+            write!(f, "\n  inconsistent faces {:?}", self.inconsistent_faces)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for NonManifoldEdgesError {}
+
 /// Builds full simplicial from set of triangles
+///
+/// Triangles have to be consistently oriented, and each edge has to be shared by exactly
+/// two triangles. Otherwise, returns a [`NonManifoldEdgesError`] listing wrong edges and faces.
+/// Triangles around each node have to form a single fan (no pinched node).
 pub fn build_from_triangle_list(
     triangles: Vec<[usize; 3]>,
     register_node_halfedges: bool,
+) -> Result<Simplicial2> {
+    build_from_triangles(triangles, register_node_halfedges, false)
+}
+
+/// Builds simplicial with boundaries from set of triangles
+///
+/// Triangles have to be consistently oriented, and each edge has to be shared by one
+/// (boundary edge) or two triangles. Otherwise, returns a [`NonManifoldEdgesError`]
+/// listing wrong edges and faces (with no boundary edge).
+/// Each node can be on one boundary loop at most, and triangles around each node have to
+/// form a single fan (no pinched node).
+pub fn build_from_triangle_list_with_boundary(
+    triangles: Vec<[usize; 3]>,
+    register_node_halfedges: bool,
+) -> Result<Simplicial2> {
+    build_from_triangles(triangles, register_node_halfedges, true)
+}
+
+fn build_from_triangles(
+    triangles: Vec<[usize; 3]>,
+    register_node_halfedges: bool,
+    with_boundary: bool,
 ) -> Result<Simplicial2> {
     let mut simpl = Simplicial2::new(register_node_halfedges);
 
     for &[nod0, nod1, nod2] in triangles.iter() {
+        if nod0 == nod1 || nod0 == nod2 || nod1 == nod2 {
+            return Err(anyhow::Error::msg("Degenerated triangle in list"));
+        }
         let ind_tri = add_empty_triangle(&mut simpl);
         set_triangle(&mut simpl, ind_tri, nod0, nod1, nod2);
     }
 
-    let mut to_attribute: Vec<usize> = (0..simpl.get_nb_halfedges()).collect();
-    while let Some(ind_he) = to_attribute.pop() {
+    // group halfedges by edge
+    let mut edge_halfedges: HashMap<[usize; 2], Vec<usize>> = HashMap::new();
+    for ind_he in 0..simpl.get_nb_halfedges() {
         let n0 = simpl.halfedge_first_node_value(ind_he);
         let n1 = simpl.halfedge_last_node_value(ind_he);
-        let mut found = false;
-        for i in 0..to_attribute.len() {
-            let ind_he_opp = to_attribute[i];
-            let n0o = simpl.halfedge_first_node_value(ind_he_opp);
-            let n1o = simpl.halfedge_last_node_value(ind_he_opp);
-            if n0 == n1o && n1 == n0o {
-                oppose_halfedges(&mut simpl, ind_he, ind_he_opp);
-                to_attribute.remove(i);
-                found = true;
-                break;
+        let key = if n0 < n1 { [n0, n1] } else { [n1, n0] };
+        edge_halfedges.entry(key).or_default().push(ind_he);
+    }
+
+    let mut error = NonManifoldEdgesError {
+        boundary_edges: Vec::new(),
+        non_manifold_edges: Vec::new(),
+        inconsistent_faces: Vec::new(),
+    };
+    let mut to_oppose = Vec::new();
+    let mut boundary_halfedges = Vec::new();
+    for (nodes, vec_he) in edge_halfedges.into_iter() {
+        match vec_he[..] {
+            [ind_he] => {
+                if with_boundary {
+                    boundary_halfedges.push(ind_he);
+                } else {
+                    error.boundary_edges.push([
+                        simpl.halfedge_first_node_value(ind_he),
+                        simpl.halfedge_last_node_value(ind_he),
+                    ]);
+                }
+            }
+            [ind_he0, ind_he1] => {
+                if simpl.halfedge_first_node_value(ind_he0)
+                    == simpl.halfedge_first_node_value(ind_he1)
+                {
+                    error.inconsistent_faces.push(ind_he0 / 3);
+                    error.inconsistent_faces.push(ind_he1 / 3);
+                } else {
+                    to_oppose.push((ind_he0, ind_he1));
+                }
             }
+            _ => error.non_manifold_edges.push(NonManifoldEdge {
+                nodes,
+                triangles: vec_he.iter().map(|&ind_he| ind_he / 3).collect(),
+            }),
         }
-        if !found {
-            return Err(anyhow::Error::msg(
-                "Given faces do not form a manifold simpl",
-            ));
+    }
+
+    if !error.boundary_edges.is_empty()
+        || !error.non_manifold_edges.is_empty()
+        || !error.inconsistent_faces.is_empty()
+    {
+        error.boundary_edges.sort();
+        error.non_manifold_edges.sort_by_key(|edge| edge.nodes);
+        error.inconsistent_faces.sort();
+        error.inconsistent_faces.dedup();
+        return Err(anyhow::Error::new(error));
+    }
+
+    // a node starting two boundary halfedges is pinched
+    let mut boundary_nodes = HashMap::new();
+    for &ind_he in boundary_halfedges.iter() {
+        let node = simpl.halfedge_first_node_value(ind_he);
+        if boundary_nodes.insert(node, ind_he).is_some() {
+            return Err(anyhow::Error::msg(format!(
+                "Node {} is on several boundary loops",
+                node
+            )));
         }
     }
 
+    for (ind_he0, ind_he1) in to_oppose {
+        oppose_halfedges(&mut simpl, ind_he0, ind_he1);
+    }
+    for ind_he in boundary_halfedges {
+        oppose_halfedges(&mut simpl, ind_he, NO_OPPOSITE);
+    }
+
+    // halfedges starting at a node have to form a single fan of triangles (no pinched node)
+    let mut fans = UnionFind::new(simpl.get_nb_halfedges());
+    for ind_he in 0..simpl.get_nb_halfedges() {
+        if let Some(ind_he_opp) = simpl.try_halfedge_opposite_index(ind_he) {
+            fans.union(ind_he, simpl.halfedge_next_index(ind_he_opp));
+        }
+    }
+    let mut node_fans = HashMap::new();
+    for ind_he in 0..simpl.get_nb_halfedges() {
+        let node = simpl.halfedge_first_node_value(ind_he);
+        let fan = fans.find(ind_he);
+        if *node_fans.entry(node).or_insert(fan) != fan {
+            return Err(anyhow::Error::msg(format!(
+                "Node {} is pinched (shared by several fans of triangles)",
+                node
+            )));
+        }
+    }
+
+    Ok(simpl)
+}
+
+/// Builds simplicial from raw halfedge arrays (first node and opposite of each halfedge,
+/// NO_OPPOSITE for boundary halfedges)
+///
+/// Node values have to be lower than nb_nodes. Only checks array consistency, topological
+/// validity has to be checked by caller
+pub(crate) fn build_from_halfedge_arrays(
+    halfedge_first_node: Vec<usize>,
+    halfedge_opposite: Vec<usize>,
+    nb_nodes: usize,
+    register_node_halfedges: bool,
+) -> Result<Simplicial2> {
+    let nb_halfedges = halfedge_first_node.len();
+    if !nb_halfedges.is_multiple_of(3) || halfedge_opposite.len() != nb_halfedges {
+        return Err(anyhow::Error::msg("Inconsistent halfedge arrays"));
+    }
+    if halfedge_first_node.iter().any(|&node| node >= nb_nodes) {
+        return Err(anyhow::Error::msg("Node value out of bounds"));
+    }
+    for (ind_he, &ind_he_opp) in halfedge_opposite.iter().enumerate() {
+        if ind_he_opp == NO_OPPOSITE {
+            continue;
+        }
+        if ind_he_opp >= nb_halfedges || halfedge_opposite[ind_he_opp] != ind_he {
+            return Err(anyhow::Error::msg("Inconsistent halfedge opposite array"));
+        }
+    }
+
+    let mut simpl = Simplicial2::new(register_node_halfedges);
+    for ind_first in (0..nb_halfedges).step_by(3) {
+        let ind_tri = add_empty_triangle(&mut simpl);
+        set_triangle(
+            &mut simpl,
+            ind_tri,
+            halfedge_first_node[ind_first],
+            halfedge_first_node[ind_first + 1],
+            halfedge_first_node[ind_first + 2],
+        );
+    }
+    simpl.halfedge_opposite = halfedge_opposite;
+
     Ok(simpl)
 }
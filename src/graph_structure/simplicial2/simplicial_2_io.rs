@@ -0,0 +1,73 @@
+use anyhow::Result;
+use nalgebra::base::*;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+
+use super::simplicial_2_build::build_from_halfedge_arrays;
+use super::simplicial_2_quality::simplicial2_is_valid;
+use super::Simplicial2;
+use crate::graph_structure::binary_io::{
+    read_coords, read_header, read_u64, read_u64_vec, write_coords, write_header, write_u64,
+    write_u64_vec,
+};
+
+const SIMPLICIAL2_MAGIC: &[u8; 4] = b"SKS2";
+
+/// Saves simplicial as binary file, with optional node coordinates (indexed by node value)
+///
+/// Layout (little endian): magic, version, number of triangles,
+/// halfedge first nodes, halfedge opposites, node coordinates
+pub fn save_simplicial2_bin<const D: usize>(
+    filename: &str,
+    simplicial: &Simplicial2,
+    node_coords: Option<&Vec<SVector<f64, D>>>,
+) -> Result<()> {
+    let file = File::create(filename)?;
+    let mut writer = BufWriter::new(file);
+
+    write_header(&mut writer, SIMPLICIAL2_MAGIC)?;
+    write_u64(&mut writer, simplicial.get_nb_triangles())?;
+    write_u64_vec(&mut writer, &simplicial.halfedge_first_node)?;
+    write_u64_vec(&mut writer, &simplicial.halfedge_opposite)?;
+    write_coords(&mut writer, node_coords)?;
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Loads simplicial from binary file, with optional node coordinates
+///
+/// Node values have to be lower than the number of coordinates if there are some, than the
+/// number of halfedges otherwise. Node halfedges are rebuilt if requested, and simplicial
+/// validity is checked
+pub fn load_simplicial2_bin<const D: usize>(
+    filename: &str,
+    register_node_halfedges: bool,
+) -> Result<(Simplicial2, Option<Vec<SVector<f64, D>>>)> {
+    let file = File::open(filename)?;
+    let mut reader = BufReader::new(file);
+
+    read_header(&mut reader, SIMPLICIAL2_MAGIC)?;
+    let nb_halfedges = read_u64(&mut reader)?
+        .checked_mul(3)
+        .ok_or(anyhow::Error::msg("Wrong number of triangles"))?;
+    let halfedge_first_node = read_u64_vec(&mut reader, nb_halfedges)?;
+    let halfedge_opposite = read_u64_vec(&mut reader, nb_halfedges)?;
+    let node_coords = read_coords::<D>(&mut reader)?;
+
+    let nb_nodes = node_coords
+        .as_ref()
+        .map_or(nb_halfedges, |coords| coords.len());
+    let simplicial = build_from_halfedge_arrays(
+        halfedge_first_node,
+        halfedge_opposite,
+        nb_nodes,
+        register_node_halfedges,
+    )?;
+
+    if !simplicial2_is_valid(&simplicial)? {
+        return Err(anyhow::Error::msg("Loaded simplicial is not valid"));
+    }
+
+    Ok((simplicial, node_coords))
+}
@@ -1,45 +1,156 @@
 use anyhow::Result;
+use std::fmt;
 
 pub use super::IterHalfEdge2;
 pub use super::Simplicial2;
 
-/// Checks halfedge validity
-pub fn halfedge2_is_valid(halfedge: &IterHalfEdge2) -> bool {
+/// Violation of 2D simplicial structure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Simplicial2Violation {
+    /// Next halfedge does not start at last node of halfedge
+    WrongNext {
+        /// Halfedge index
+        halfedge: usize,
+    },
+    /// Previous halfedge does not end at first node of halfedge
+    WrongPrevious {
+        /// Halfedge index
+        halfedge: usize,
+    },
+    /// Opposite halfedge does not go through the same nodes in reverse direction
+    WrongOpposite {
+        /// Halfedge index
+        halfedge: usize,
+    },
+    /// Opposite of opposite halfedge is not the halfedge
+    WrongOppositeOfOpposite {
+        /// Halfedge index
+        halfedge: usize,
+    },
+    /// Registered halfedges of a node differ from halfedges starting at the node
+    StaleNodeRegistry {
+        /// Node value
+        node: usize,
+    },
+}
+
+impl fmt::Display for Simplicial2Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Simplicial2Violation::WrongNext { halfedge } => {
+                write!(f, "Halfedge {}: wrong next halfedge", halfedge)
+            }
+            Simplicial2Violation::WrongPrevious { halfedge } => {
+                write!(f, "Halfedge {}: wrong previous halfedge", halfedge)
+            }
+            Simplicial2Violation::WrongOpposite { halfedge } => {
+                write!(f, "Halfedge {}: wrong opposite halfedge", halfedge)
+            }
+            Simplicial2Violation::WrongOppositeOfOpposite { halfedge } => {
+                write!(
+                    f,
+                    "Halfedge {}: opposite of opposite halfedge differs",
+                    halfedge
+                )
+            }
+            Simplicial2Violation::StaleNodeRegistry { node } => {
+                write!(f, "Node {}: registered halfedges differ", node)
+            }
+        }
+    }
+}
+
+/// Logs violations, and checks if there is none
+fn log_violations(violations: &[Simplicial2Violation]) -> bool {
+    for violation in violations.iter() {
+        log::error!("{}", violation);
+    }
+    violations.is_empty()
+}
+
+/// Gets violations of halfedge structure
+pub fn halfedge2_violations(halfedge: &IterHalfEdge2) -> Vec<Simplicial2Violation> {
     let first_node = halfedge.first_node();
     let last_node = halfedge.last_node();
 
     let he_next = halfedge.next();
     let he_prev = halfedge.previous();
-    let he_opp = halfedge.opposite();
+    let he_opp = halfedge.try_opposite();
 
-    let mut valid = true;
+    let mut violations = Vec::new();
+    let ind_he = halfedge.index();
 
     if he_next.first_node().value() != last_node.value() {
-        log::error!("{}: Wrong next halfedge", halfedge.to_string());
-        valid = false;
+        violations.push(Simplicial2Violation::WrongNext { halfedge: ind_he });
     }
     if he_prev.last_node().value() != first_node.value() {
-        log::error!("{}: Wrong previous halfedge", halfedge.to_string());
-        valid = false;
+        violations.push(Simplicial2Violation::WrongPrevious { halfedge: ind_he });
     }
 
-    if he_opp.first_node().value() != last_node.value()
-        || he_opp.last_node().value() != first_node.value()
-    {
-        log::error!("{}: Wrong opposite halfedge", halfedge.to_string());
-        valid = false;
+    if let Some(he_opp) = he_opp {
+        if he_opp.first_node().value() != last_node.value()
+            || he_opp.last_node().value() != first_node.value()
+        {
+            violations.push(Simplicial2Violation::WrongOpposite { halfedge: ind_he });
+        }
+        if he_opp.try_opposite().map(|he| he.index()) != Some(ind_he) {
+            violations.push(Simplicial2Violation::WrongOppositeOfOpposite { halfedge: ind_he });
+        }
     }
 
-    valid
+    violations
 }
 
-/// Checks validity of simplicial graph
-pub fn simplicial2_is_valid(simplicial: &Simplicial2) -> Result<bool> {
-    let mut valid = true;
+/// Checks halfedge validity
+pub fn halfedge2_is_valid(halfedge: &IterHalfEdge2) -> bool {
+    log_violations(&halfedge2_violations(halfedge))
+}
 
-    for he in simplicial.get_all_halfedges().iter() {
-        valid = valid && halfedge2_is_valid(&he);
+/// Gets violations of node halfedges registry (none if nodes are not registered)
+pub fn node_halfedges_violations(simplicial: &Simplicial2) -> Vec<Simplicial2Violation> {
+    let mut violations = Vec::new();
+    let Some(registry) = &simplicial.node_halfedges else {
+        return violations;
+    };
+
+    let mut node_halfedges: Vec<Vec<usize>> = Vec::new();
+    for ind_he in 0..simplicial.get_nb_halfedges() {
+        let node = simplicial.halfedge_first_node_value(ind_he);
+        if node_halfedges.len() <= node {
+            node_halfedges.resize(node + 1, Vec::new());
+        }
+        node_halfedges[node].push(ind_he);
     }
 
-    Ok(valid)
+    for node in 0..std::cmp::max(node_halfedges.len(), registry.len()) {
+        let mut registered = registry.get(node).cloned().unwrap_or_default();
+        registered.sort();
+        let expected = node_halfedges.get(node).cloned().unwrap_or_default();
+        if registered != expected {
+            violations.push(Simplicial2Violation::StaleNodeRegistry { node });
+        }
+    }
+
+    violations
+}
+
+/// Checks node halfedges registry within simplicial
+pub fn check_node_halfedges(simplicial: &Simplicial2) -> bool {
+    log_violations(&node_halfedges_violations(simplicial))
+}
+
+/// Gets all violations of simplicial graph
+pub fn simplicial2_violations(simplicial: &Simplicial2) -> Vec<Simplicial2Violation> {
+    let mut violations: Vec<Simplicial2Violation> = simplicial
+        .get_all_halfedges()
+        .iter()
+        .flat_map(halfedge2_violations)
+        .collect();
+    violations.extend(node_halfedges_violations(simplicial));
+    violations
+}
+
+/// Checks validity of simplicial graph
+pub fn simplicial2_is_valid(simplicial: &Simplicial2) -> Result<bool> {
+    Ok(log_violations(&simplicial2_violations(simplicial)))
 }
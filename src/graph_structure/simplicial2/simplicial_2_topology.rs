@@ -0,0 +1,137 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::Simplicial2;
+use crate::graph_structure::union_find::UnionFind;
+
+/// Gets number of nodes of the simplicial
+pub fn nb_nodes(simpl: &Simplicial2) -> usize {
+    (0..simpl.get_nb_halfedges())
+        .map(|ind_he| simpl.halfedge_first_node_value(ind_he))
+        .collect::<HashSet<usize>>()
+        .len()
+}
+
+/// Gets number of edges of the simplicial (boundary edges included)
+pub fn nb_edges(simpl: &Simplicial2) -> usize {
+    let nb_boundary = (0..simpl.get_nb_halfedges())
+        .filter(|&ind_he| simpl.halfedge_is_boundary(ind_he))
+        .count();
+    (simpl.get_nb_halfedges() - nb_boundary) / 2 + nb_boundary
+}
+
+/// Computes Euler characteristic (nodes - edges + triangles)
+pub fn euler_characteristic(simpl: &Simplicial2) -> i64 {
+    nb_nodes(simpl) as i64 - nb_edges(simpl) as i64 + simpl.get_nb_triangles() as i64
+}
+
+/// Gets connected components, as lists of triangle indices
+///
+/// Triangles are connected through edges
+pub fn connected_components(simpl: &Simplicial2) -> Vec<Vec<usize>> {
+    let mut union_find = UnionFind::new(simpl.get_nb_triangles());
+    for ind_he in 0..simpl.get_nb_halfedges() {
+        if let Some(ind_he_opp) = simpl.try_halfedge_opposite_index(ind_he) {
+            union_find.union(
+                simpl.halfedge_triangle_index(ind_he),
+                simpl.halfedge_triangle_index(ind_he_opp),
+            );
+        }
+    }
+
+    let mut component_index = vec![usize::MAX; simpl.get_nb_triangles()];
+    let mut components: Vec<Vec<usize>> = Vec::new();
+    for ind_tri in 0..simpl.get_nb_triangles() {
+        let root = union_find.find(ind_tri);
+        if component_index[root] == usize::MAX {
+            component_index[root] = components.len();
+            components.push(Vec::new());
+        }
+        components[component_index[root]].push(ind_tri);
+    }
+    components
+}
+
+/// Gets number of connected components
+pub fn nb_connected_components(simpl: &Simplicial2) -> usize {
+    connected_components(simpl).len()
+}
+
+/// Checks if a triangle list describes an orientable surface
+///
+/// Simplicial2 is orientable by construction (its builders reject triangles sharing an edge
+/// with the same orientation), so orientability is checked on raw triangles, whose
+/// orientation may be flipped. Orientation is propagated through edges shared by two
+/// triangles, fails if an edge is shared by more than two triangles.
+pub fn triangle_list_is_orientable(triangles: &[[usize; 3]]) -> Result<bool> {
+    // triangles of each edge, with edge direction within triangle
+    let mut edge_triangles: HashMap<[usize; 2], Vec<(usize, bool)>> = HashMap::new();
+    for (ind_tri, triangle) in triangles.iter().enumerate() {
+        for i in 0..3 {
+            let (n0, n1) = (triangle[i], triangle[(i + 1) % 3]);
+            edge_triangles
+                .entry([n0.min(n1), n0.max(n1)])
+                .or_default()
+                .push((ind_tri, n0 < n1));
+        }
+    }
+    let mut neighbors = vec![Vec::new(); triangles.len()];
+    for (edge, tris) in edge_triangles.iter() {
+        match tris[..] {
+            [(tri0, forward0), (tri1, forward1)] => {
+                // flipped relative orientation if edge is traversed in the same direction
+                neighbors[tri0].push((tri1, forward0 == forward1));
+                neighbors[tri1].push((tri0, forward0 == forward1));
+            }
+            [_] => (),
+            _ => {
+                return Err(anyhow::Error::msg(format!(
+                    "Edge {:?} shared by more than two triangles",
+                    edge
+                )))
+            }
+        }
+    }
+
+    // breadth first propagation of orientation flips
+    let mut flipped: Vec<Option<bool>> = vec![None; triangles.len()];
+    for ind_start in 0..triangles.len() {
+        if flipped[ind_start].is_some() {
+            continue;
+        }
+        flipped[ind_start] = Some(false);
+        let mut queue = VecDeque::from([ind_start]);
+        while let Some(ind_tri) = queue.pop_front() {
+            let flip = flipped[ind_tri].unwrap_or(false);
+            for &(ind_nei, relative_flip) in neighbors[ind_tri].iter() {
+                let flip_nei = flip != relative_flip;
+                match flipped[ind_nei] {
+                    None => {
+                        flipped[ind_nei] = Some(flip_nei);
+                        queue.push_back(ind_nei);
+                    }
+                    Some(flip_cur) if flip_cur != flip_nei => return Ok(false),
+                    Some(_) => (),
+                }
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// Computes genus of the surface (sum of genera of connected components)
+///
+/// Uses Euler characteristic, number of connected components and number of boundary loops:
+/// chi = 2 * components - 2 * genus - boundary loops (simplicial is orientable by
+/// construction)
+pub fn genus(simpl: &Simplicial2) -> Result<usize> {
+    let twice_genus = 2 * nb_connected_components(simpl) as i64
+        - simpl.boundary_loop_indices().len() as i64
+        - euler_characteristic(simpl);
+    if twice_genus < 0 || twice_genus % 2 != 0 {
+        return Err(anyhow::Error::msg(
+            "Inconsistent Euler characteristic, simplicial is not a manifold surface",
+        ));
+    }
+    Ok((twice_genus / 2) as usize)
+}
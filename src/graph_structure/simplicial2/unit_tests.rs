@@ -1,8 +1,16 @@
 #[cfg(test)]
 mod simplicial2_test {
     use anyhow::Result;
-
-    use crate::graph_structure::simplicial2::simplicial_2_quality::simplicial2_is_valid;
+    use nalgebra::base::*;
+
+    use crate::graph_structure::attribute_set::AttributeEvent;
+    use crate::graph_structure::simplicial2::simplicial_2_io::{
+        load_simplicial2_bin, save_simplicial2_bin,
+    };
+    use crate::graph_structure::simplicial2::simplicial_2_quality::{
+        simplicial2_is_valid, simplicial2_violations, Simplicial2Violation,
+    };
+    use crate::graph_structure::simplicial2::simplicial_2_topology;
     use crate::graph_structure::simplicial2::{simplicial_2_build, Simplicial2};
 
     #[test]
@@ -118,4 +126,503 @@ mod simplicial2_test {
 
         Ok(())
     }
+
+    #[test]
+    fn flip_node_registry_test() -> Result<()> {
+        let mut simpl = Simplicial2::new(true);
+        let [_, ind_tri1] = simplicial_2_build::insert_first_triangle(&mut simpl, [0, 1, 2])?;
+        simplicial_2_build::insert_node_within_triangle(&mut simpl, 3, ind_tri1)?;
+        let ind_he = simpl
+            .find_halfedge_index(0, 1)
+            .ok_or(anyhow::Error::msg("could not find edge [0; 1]"))?;
+        simplicial_2_build::flip_halfedge(&mut simpl, ind_he)?;
+
+        // registered halfedges of each node are exactly the halfedges starting at it
+        assert!(simpl.find_halfedge_index(0, 1).is_none());
+        for node in 0..4 {
+            let mut registered: Vec<usize> = simpl
+                .find_node(node)
+                .ok_or(anyhow::Error::msg("could not find node"))?
+                .halfedges()
+                .iter()
+                .map(|he| he.index())
+                .collect();
+            registered.sort();
+            let expected: Vec<usize> = (0..simpl.get_nb_halfedges())
+                .filter(|&ind_he| simpl.halfedge_first_node_value(ind_he) == node)
+                .collect();
+            assert!(registered == expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn attributes_test() -> Result<()> {
+        let mut simpl = Simplicial2::new(true);
+
+        // label: copied on split, smallest parent on flip
+        simpl.get_triangle_attributes_mut().add_attribute_with_rule(
+            "label".to_string(),
+            0usize,
+            |event, parents| match event {
+                AttributeEvent::Creation => 1,
+                AttributeEvent::Split => parents[0] * 10,
+                AttributeEvent::Flip => *parents.iter().min().unwrap(),
+            },
+        );
+        // constraint: never inherited by new halfedges
+        simpl
+            .get_halfedge_attributes_mut()
+            .add_attribute("constraint".to_string(), false);
+
+        let [ind_tri0, ind_tri1] =
+            simplicial_2_build::insert_first_triangle(&mut simpl, [0, 1, 2])?;
+        assert!(
+            *simpl
+                .get_triangle_attributes()
+                .get_attribute_value::<usize>(ind_tri0, "label")?
+                == 1
+        );
+
+        simpl
+            .get_triangle_attributes_mut()
+            .set_attribute_value(ind_tri1, "label", 2usize)?;
+        let ind_he = simpl
+            .find_halfedge_index(2, 1)
+            .ok_or(anyhow::Error::msg("could not find edge [2; 1]"))?;
+        simpl
+            .get_halfedge_attributes_mut()
+            .set_attribute_value(ind_he, "constraint", true)?;
+
+        let new_tris = simplicial_2_build::insert_node_within_triangle(&mut simpl, 3, ind_tri1)?;
+        for ind_tri in new_tris {
+            assert!(
+                *simpl
+                    .get_triangle_attributes()
+                    .get_attribute_value::<usize>(ind_tri, "label")?
+                    == 20
+            );
+        }
+        assert!(simpl.get_triangle_attributes().get_nb_elements() == 4);
+        assert!(simpl.get_halfedge_attributes().get_nb_elements() == 12);
+
+        let ind_he = simpl
+            .find_halfedge_index(0, 1)
+            .ok_or(anyhow::Error::msg("could not find edge [0; 1]"))?;
+        let [hbd, hdb] = simplicial_2_build::flip_halfedge(&mut simpl, ind_he)?;
+        assert!(simplicial2_is_valid(&simpl)?);
+        assert!(
+            *simpl
+                .get_triangle_attributes()
+                .get_attribute_value::<usize>(simpl.halfedge_triangle_index(hbd), "label")?
+                == 1
+        );
+        assert!(
+            *simpl
+                .get_triangle_attributes()
+                .get_attribute_value::<usize>(simpl.halfedge_triangle_index(hdb), "label")?
+                == 1
+        );
+
+        for he in simpl.get_all_halfedges() {
+            let [n0, n1] = [he.first_node().value(), he.last_node().value()];
+            let constraint = *simpl
+                .get_halfedge_attributes()
+                .get_attribute_value::<bool>(he.index(), "constraint")?;
+            assert!(constraint == (n0 == 2 && n1 == 1));
+        }
+
+        let simpl_clone = simpl.clone();
+        assert!(
+            simpl_clone
+                .get_halfedge_attributes()
+                .get_attribute_values::<bool>("constraint")?
+                == simpl
+                    .get_halfedge_attributes()
+                    .get_attribute_values::<bool>("constraint")?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_io_test() -> Result<()> {
+        let mut simpl = Simplicial2::new(false);
+        let [_, ind_tri1] = simplicial_2_build::insert_first_triangle(&mut simpl, [0, 1, 2])?;
+        simplicial_2_build::insert_node_within_triangle(&mut simpl, 3, ind_tri1)?;
+        let coords = vec![
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(0.0, 1.0),
+            Vector2::new(0.2, 0.2),
+        ];
+
+        let filename = std::env::temp_dir().join("skeletal_structures_simplicial2.bin");
+        let filename = filename.to_str().unwrap();
+
+        save_simplicial2_bin(filename, &simpl, Some(&coords))?;
+        let (simpl_loaded, coords_loaded) = load_simplicial2_bin::<2>(filename, true)?;
+        assert!(simpl_loaded.get_nb_triangles() == simpl.get_nb_triangles());
+        for ind_tri in 0..simpl.get_nb_triangles() {
+            assert!(
+                simpl_loaded.triangle_node_values(ind_tri) == simpl.triangle_node_values(ind_tri)
+            );
+        }
+        assert!(coords_loaded.as_ref() == Some(&coords));
+        assert!(simpl_loaded.find_triangle(1, 2, 3).is_some());
+        assert!(load_simplicial2_bin::<3>(filename, true).is_err());
+
+        save_simplicial2_bin::<2>(filename, &simpl, None)?;
+        let (_, coords_loaded) = load_simplicial2_bin::<2>(filename, false)?;
+        assert!(coords_loaded.is_none());
+
+        // node values beyond coordinates, or huge without coordinates
+        for (coords, node) in [(Some(&coords), 4), (None, 1 << 40), (None, u64::MAX)] {
+            save_simplicial2_bin(filename, &simpl, coords)?;
+            let mut bytes = std::fs::read(filename)?;
+            bytes[16..24].copy_from_slice(&node.to_le_bytes());
+            std::fs::write(filename, &bytes)?;
+            assert!(load_simplicial2_bin::<2>(filename, true)
+                .is_err_and(|err| err.to_string() == "Node value out of bounds"));
+        }
+
+        std::fs::remove_file(filename)?;
+        Ok(())
+    }
+
+    #[test]
+    fn build_from_triangle_list_test() -> Result<()> {
+        // octahedron
+        let triangles = vec![
+            [0, 2, 4],
+            [2, 1, 4],
+            [1, 3, 4],
+            [3, 0, 4],
+            [2, 0, 5],
+            [1, 2, 5],
+            [3, 1, 5],
+            [0, 3, 5],
+        ];
+        for register_node_halfedges in [true, false] {
+            let simpl = simplicial_2_build::build_from_triangle_list(
+                triangles.clone(),
+                register_node_halfedges,
+            )?;
+            assert!(simpl.get_nb_triangles() == 8);
+            assert!(simplicial2_is_valid(&simpl)?);
+        }
+
+        // open, non manifold and inconsistent list
+        let triangles = vec![[0, 1, 2], [1, 0, 3], [0, 1, 4], [5, 6, 7], [5, 6, 8]];
+        let err = simplicial_2_build::build_from_triangle_list(triangles, false)
+            .err()
+            .ok_or(anyhow::Error::msg("Wrong list should not be built"))?;
+        let err = err
+            .downcast_ref::<simplicial_2_build::NonManifoldEdgesError>()
+            .ok_or(anyhow::Error::msg("Wrong error type"))?;
+        assert!(err.non_manifold_edges.len() == 1);
+        assert!(err.non_manifold_edges[0].nodes == [0, 1]);
+        assert!(err.non_manifold_edges[0].triangles == vec![0, 1, 2]);
+        assert!(err.inconsistent_faces == vec![3, 4]);
+        assert!(err.boundary_edges.contains(&[2, 0]));
+        assert!(err.boundary_edges.contains(&[8, 5]));
+        assert!(err.boundary_edges.len() == 10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn boundary_test() -> Result<()> {
+        // square split in 4 triangles around node 4
+        let triangles = vec![[0, 1, 4], [1, 2, 4], [2, 3, 4], [3, 0, 4]];
+        assert!(simplicial_2_build::build_from_triangle_list(triangles.clone(), false).is_err());
+
+        for register_node_halfedges in [true, false] {
+            let mut simpl = simplicial_2_build::build_from_triangle_list_with_boundary(
+                triangles.clone(),
+                register_node_halfedges,
+            )?;
+            assert!(simplicial2_is_valid(&simpl)?);
+
+            let he01 = simpl
+                .find_halfedge(0, 1)
+                .ok_or(anyhow::Error::msg("could not find edge [0; 1]"))?;
+            assert!(he01.is_boundary());
+            assert!(he01.try_opposite().is_none());
+            assert!(he01.next_boundary().map(|he| he.last_node().value()) == Some(2));
+            assert!(!he01.next().is_boundary());
+            assert!(he01.dual().try_last_node().is_none());
+            assert!(he01.next().dual().try_last_node().is_some());
+
+            let loops = simpl.get_boundary_loops();
+            assert!(loops.len() == 1);
+            let loop_nodes: Vec<usize> =
+                loops[0].iter().map(|he| he.first_node().value()).collect();
+            assert!(loop_nodes.len() == 4);
+            for (i, &nod) in loop_nodes.iter().enumerate() {
+                assert!(loop_nodes[(i + 1) % 4] == (nod + 1) % 4);
+            }
+
+            // fan around boundary and inner nodes
+            let node0 = simpl
+                .find_node(0)
+                .ok_or(anyhow::Error::msg("could not find node 0"))?;
+            let mut last_nodes: Vec<usize> = node0
+                .halfedges()
+                .iter()
+                .map(|he| he.last_node().value())
+                .collect();
+            last_nodes.sort();
+            assert!(last_nodes == vec![1, 4]);
+            let node4 = simpl
+                .find_node(4)
+                .ok_or(anyhow::Error::msg("could not find node 4"))?;
+            assert!(node4.halfedges().len() == 4);
+
+            // modifications keep boundary
+            let ind_he01 = he01.index();
+            let ind_he14 = simpl
+                .find_halfedge_index(1, 4)
+                .ok_or(anyhow::Error::msg("could not find edge [1; 4]"))?;
+            assert!(simplicial_2_build::flip_halfedge(&mut simpl, ind_he01).is_err());
+            simplicial_2_build::flip_halfedge(&mut simpl, ind_he14)?;
+            assert!(simplicial2_is_valid(&simpl)?);
+            assert!(simpl.find_halfedge(0, 2).is_some());
+            let ind_tri = simpl
+                .find_triangle_index(0, 1, 2)
+                .ok_or(anyhow::Error::msg("could not find triangle [0; 1; 2]"))?;
+            simplicial_2_build::insert_node_within_triangle(&mut simpl, 5, ind_tri)?;
+            assert!(simplicial2_is_valid(&simpl)?);
+            assert!(simpl.get_boundary_loops()[0].len() == 4);
+            assert!(simpl.find_halfedge(0, 1).map(|he| he.is_boundary()) == Some(true));
+        }
+
+        // pinched node
+        let triangles = vec![[0, 1, 2], [0, 3, 4]];
+        assert!(
+            simplicial_2_build::build_from_triangle_list_with_boundary(triangles, false).is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn topology_test() -> Result<()> {
+        // octahedron
+        let octahedron = |offset: usize| {
+            (0..4)
+                .flat_map(|i| {
+                    let (a, b) = (offset + i, offset + (i + 1) % 4);
+                    [[a, b, offset + 4], [b, a, offset + 5]]
+                })
+                .collect::<Vec<_>>()
+        };
+        let simpl = simplicial_2_build::build_from_triangle_list(octahedron(0), false)?;
+        assert!(simplicial_2_topology::nb_nodes(&simpl) == 6);
+        assert!(simplicial_2_topology::nb_edges(&simpl) == 12);
+        assert!(simplicial_2_topology::euler_characteristic(&simpl) == 2);
+        assert!(simplicial_2_topology::nb_connected_components(&simpl) == 1);
+        assert!(simplicial_2_topology::genus(&simpl)? == 0);
+
+        // two disjoint octahedra
+        let mut triangles = octahedron(0);
+        triangles.extend(octahedron(6));
+        let simpl = simplicial_2_build::build_from_triangle_list(triangles, false)?;
+        let components = simplicial_2_topology::connected_components(&simpl);
+        assert!(components.len() == 2);
+        assert!(components.iter().all(|comp| comp.len() == 8));
+        assert!(simplicial_2_topology::euler_characteristic(&simpl) == 4);
+        assert!(simplicial_2_topology::genus(&simpl)? == 0);
+
+        // disc
+        let triangles = vec![[0, 1, 4], [1, 2, 4], [2, 3, 4], [3, 0, 4]];
+        let simpl = simplicial_2_build::build_from_triangle_list_with_boundary(triangles, false)?;
+        assert!(simplicial_2_topology::nb_edges(&simpl) == 8);
+        assert!(simplicial_2_topology::euler_characteristic(&simpl) == 1);
+        assert!(simplicial_2_topology::genus(&simpl)? == 0);
+
+        // torus from 4x4 periodic grid
+        let node = |i: usize, j: usize| 4 * (i % 4) + j % 4;
+        let triangles = (0..4)
+            .flat_map(|i| (0..4).map(move |j| (i, j)))
+            .flat_map(|(i, j)| {
+                [
+                    [node(i, j), node(i + 1, j), node(i + 1, j + 1)],
+                    [node(i, j), node(i + 1, j + 1), node(i, j + 1)],
+                ]
+            })
+            .collect::<Vec<_>>();
+        let simpl = simplicial_2_build::build_from_triangle_list(triangles.clone(), false)?;
+        assert!(simplicial_2_topology::euler_characteristic(&simpl) == 0);
+        assert!(simplicial_2_topology::nb_connected_components(&simpl) == 1);
+        assert!(simplicial_2_topology::genus(&simpl)? == 1);
+
+        // orientability of raw triangles: torus with flipped triangles, and Moebius strip
+        let mut triangles = triangles;
+        for triangle in triangles.iter_mut().step_by(3) {
+            triangle.swap(0, 1);
+        }
+        assert!(simplicial_2_topology::triangle_list_is_orientable(
+            &triangles
+        )?);
+        assert!(simplicial_2_build::build_from_triangle_list(triangles.clone(), false).is_err());
+        let moebius = (0..5)
+            .map(|i| [i, (i + 1) % 5, (i + 2) % 5])
+            .collect::<Vec<[usize; 3]>>();
+        assert!(!simplicial_2_topology::triangle_list_is_orientable(
+            &moebius
+        )?);
+        assert!(simplicial_2_topology::triangle_list_is_orientable(
+            &octahedron(0)
+        )?);
+
+        // octahedra sharing a single node
+        let mut triangles = octahedron(0);
+        triangles.extend(octahedron(5));
+        assert!(simplicial_2_build::build_from_triangle_list(triangles.clone(), false).is_err());
+        assert!(
+            simplicial_2_build::build_from_triangle_list_with_boundary(triangles, false).is_err()
+        );
+        assert!(simplicial_2_topology::triangle_list_is_orientable(&[
+            [0, 1, 2],
+            [0, 1, 3],
+            [1, 0, 4]
+        ])
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn collapse_test() -> Result<()> {
+        let octahedron = (0..4)
+            .flat_map(|i| [[i, (i + 1) % 4, 4], [(i + 1) % 4, i, 5]])
+            .collect::<Vec<_>>();
+
+        for register_node_halfedges in [true, false] {
+            let mut simpl = simplicial_2_build::build_from_triangle_list(
+                octahedron.clone(),
+                register_node_halfedges,
+            )?;
+            simpl
+                .get_triangle_attributes_mut()
+                .add_attribute::<usize>("id".to_string(), 0);
+            for ind_tri in 0..simpl.get_nb_triangles() {
+                simpl
+                    .get_triangle_attributes_mut()
+                    .set_attribute_value(ind_tri, "id", ind_tri)?;
+            }
+
+            // node 1 merged into node 0, triangles [0; 1; 4] and [1; 0; 5] removed
+            let ind_he01 = simpl
+                .find_halfedge_index(0, 1)
+                .ok_or(anyhow::Error::msg("could not find edge [0; 1]"))?;
+            let simplicial_2_build::CollapsedTriangles { removed, relocated } =
+                simplicial_2_build::collapse_halfedge(&mut simpl, ind_he01)?;
+            assert!(removed == vec![0, 1]);
+            assert!(relocated.len() == 2);
+            assert!(simplicial2_is_valid(&simpl)?);
+            assert!(simpl.get_nb_triangles() == 6);
+            assert!(simpl.find_node(1).is_none());
+            assert!(simpl.find_triangle(0, 2, 4).is_some());
+            assert!(simpl.find_triangle(2, 0, 5).is_some());
+            assert!(simplicial_2_topology::euler_characteristic(&simpl) == 2);
+            let ids = simpl
+                .get_triangle_attributes()
+                .get_attribute_values::<usize>("id")?;
+            for &(ind_from, ind_to) in relocated.iter() {
+                assert!(ids[ind_to] == ind_from);
+            }
+            for ind_tri in 0..simpl.get_nb_triangles() {
+                assert!(!removed.contains(&ids[ind_tri]));
+            }
+
+            // triangular bipyramid: [0; 2] has common neighbour 3 out of its link
+            let ind_he02 = simpl
+                .find_halfedge_index(0, 2)
+                .ok_or(anyhow::Error::msg("could not find edge [0; 2]"))?;
+            assert!(simplicial_2_build::collapse_halfedge(&mut simpl, ind_he02).is_err());
+            assert!(simpl.get_nb_triangles() == 6);
+        }
+
+        // square disc
+        let triangles = vec![[0, 1, 4], [1, 2, 4], [2, 3, 4], [3, 0, 4]];
+        for register_node_halfedges in [true, false] {
+            let mut simpl = simplicial_2_build::build_from_triangle_list_with_boundary(
+                triangles.clone(),
+                register_node_halfedges,
+            )?;
+            // inner node merged into boundary node
+            let ind_he04 = simpl
+                .find_halfedge_index(0, 4)
+                .ok_or(anyhow::Error::msg("could not find edge [0; 4]"))?;
+            let removed = simplicial_2_build::collapse_halfedge(&mut simpl, ind_he04)?.removed;
+            assert!(removed.len() == 2);
+            assert!(simplicial2_is_valid(&simpl)?);
+            assert!(simpl.get_nb_triangles() == 2);
+            assert!(simplicial_2_topology::euler_characteristic(&simpl) == 1);
+            assert!(simpl.get_boundary_loops()[0].len() == 4);
+
+            // inner edge between boundary nodes would pinch the disc
+            let ind_he02 = simpl
+                .find_halfedge_index(0, 2)
+                .ok_or(anyhow::Error::msg("could not find edge [0; 2]"))?;
+            assert!(simplicial_2_build::collapse_halfedge(&mut simpl, ind_he02).is_err());
+
+            // boundary edge
+            let ind_he01 = simpl
+                .find_halfedge_index(0, 1)
+                .ok_or(anyhow::Error::msg("could not find edge [0; 1]"))?;
+            let removed = simplicial_2_build::collapse_halfedge(&mut simpl, ind_he01)?.removed;
+            assert!(removed.len() == 1);
+            assert!(simplicial2_is_valid(&simpl)?);
+            assert!(simpl.get_nb_triangles() == 1);
+            assert!(simpl.get_boundary_loops()[0].len() == 3);
+
+            // single triangle cannot be collapsed
+            assert!(simplicial_2_build::collapse_halfedge(&mut simpl, 0).is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn violations_test() -> Result<()> {
+        let octahedron = (0..4)
+            .flat_map(|i| [[i, (i + 1) % 4, 4], [(i + 1) % 4, i, 5]])
+            .collect::<Vec<_>>();
+        let simpl = simplicial_2_build::build_from_triangle_list(octahedron, true)?;
+        assert!(simplicial2_violations(&simpl).is_empty());
+
+        // swapped opposites
+        let mut simpl_wrong = simpl.clone();
+        let ind_he01 = simpl
+            .find_halfedge_index(0, 1)
+            .ok_or(anyhow::Error::msg("could not find edge [0; 1]"))?;
+        let ind_he12 = simpl
+            .find_halfedge_index(1, 2)
+            .ok_or(anyhow::Error::msg("could not find edge [1; 2]"))?;
+        simpl_wrong.halfedge_opposite[ind_he01] = simpl.halfedge_opposite[ind_he12];
+        simpl_wrong.halfedge_opposite[ind_he12] = simpl.halfedge_opposite[ind_he01];
+        assert!(!simplicial2_is_valid(&simpl_wrong)?);
+        let violations = simplicial2_violations(&simpl_wrong);
+        assert!(violations.contains(&Simplicial2Violation::WrongOpposite { halfedge: ind_he01 }));
+        assert!(violations.contains(&Simplicial2Violation::WrongOpposite { halfedge: ind_he12 }));
+        assert!(
+            violations.contains(&Simplicial2Violation::WrongOppositeOfOpposite {
+                halfedge: simpl.halfedge_opposite[ind_he01]
+            })
+        );
+
+        // stale registry
+        let mut simpl_wrong = simpl.clone();
+        if let Some(registry) = simpl_wrong.node_halfedges.as_mut() {
+            registry[3].push(ind_he01);
+        }
+        let violations = simplicial2_violations(&simpl_wrong);
+        assert!(violations == vec![Simplicial2Violation::StaleNodeRegistry { node: 3 }]);
+
+        Ok(())
+    }
 }
@@ -1,3 +1,15 @@
 /// Set of functions to test 2D simmplicial
 mod simplicial_3_quality_check;
 pub use simplicial_3_quality_check::*;
+
+/// VTK output functions
+mod simplicial_3_vtk;
+pub use simplicial_3_vtk::*;
+
+/// Topological invariants
+mod simplicial_3_topology;
+pub use simplicial_3_topology::*;
+
+/// Extraction of region boundaries
+mod simplicial_3_boundary;
+pub use simplicial_3_boundary::*;
@@ -0,0 +1,93 @@
+use anyhow::Result;
+use nalgebra::base::*;
+
+use super::super::{IterHalfTriangle3, IterTetrahedron3, Simplicial3};
+use crate::graph_structure::simplicial2::simplicial_2_build::build_from_triangle_list;
+use crate::graph_structure::simplicial2::Simplicial2;
+use crate::mesh_structure::mesh3d::Mesh3D;
+
+/// Gets halftriangles separating selected tetrahedra from other ones
+///
+/// Returned halftriangles belong to selected tetrahedra.
+pub fn region_boundary_halftriangles<'a, F>(
+    simpl: &'a Simplicial3,
+    is_inside: F,
+) -> Vec<IterHalfTriangle3<'a>>
+where
+    F: Fn(&IterTetrahedron3) -> bool,
+{
+    let inside: Vec<bool> = simpl.get_all_tetrahedra().iter().map(&is_inside).collect();
+
+    simpl
+        .get_all_halftriangles()
+        .into_iter()
+        .filter(|htri| {
+            inside[htri.tetrahedron().ind()] && !inside[htri.opposite().tetrahedron().ind()]
+        })
+        .collect()
+}
+
+/// Gets triangles separating selected tetrahedra from other ones
+///
+/// Triangles are oriented outward the selected region (for positively oriented tetrahedra),
+/// in the same order as [`region_boundary_halftriangles`].
+pub fn region_boundary_triangles<F>(simpl: &Simplicial3, is_inside: F) -> Vec<[usize; 3]>
+where
+    F: Fn(&IterTetrahedron3) -> bool,
+{
+    region_boundary_halftriangles(simpl, is_inside)
+        .iter()
+        .map(|htri| {
+            let [a, b, c] = htri.node_values();
+            [a, c, b]
+        })
+        .collect()
+}
+
+/// Extracts boundary of selected tetrahedra as a closed 2D simplicial
+///
+/// Triangle i of the simplicial comes from halftriangle i of
+/// [`region_boundary_halftriangles`].
+/// Fails if the boundary is not a manifold surface (selected tetrahedra sharing only an
+/// edge or a node).
+pub fn extract_region_boundary<F>(
+    simpl: &Simplicial3,
+    is_inside: F,
+    register_node_halfedges: bool,
+) -> Result<Simplicial2>
+where
+    F: Fn(&IterTetrahedron3) -> bool,
+{
+    build_from_triangle_list(
+        region_boundary_triangles(simpl, is_inside),
+        register_node_halfedges,
+    )
+}
+
+/// Extracts boundary of selected tetrahedra as a mesh
+///
+/// All coordinates are kept as mesh vertices, so that vertex indices are node values.
+/// Faces are ordered as [`region_boundary_halftriangles`].
+pub fn extract_region_boundary_mesh<F>(
+    simpl: &Simplicial3,
+    node_coords: &[Vector3<f64>],
+    is_inside: F,
+) -> Result<Mesh3D>
+where
+    F: Fn(&IterTetrahedron3) -> bool,
+{
+    let triangles = region_boundary_triangles(simpl, is_inside);
+    if triangles
+        .iter()
+        .any(|tri| tri.iter().any(|&node| node >= node_coords.len()))
+    {
+        return Err(anyhow::Error::msg(
+            "Boundary node has no coordinates (infinite node within region?)",
+        ));
+    }
+
+    Ok(Mesh3D::create(
+        node_coords.to_vec(),
+        triangles.into_iter().map(|tri| tri.to_vec()).collect(),
+    ))
+}
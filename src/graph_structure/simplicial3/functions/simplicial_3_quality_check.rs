@@ -1,10 +1,131 @@
 use anyhow::Result;
+use std::fmt;
 
 pub use super::super::IterHalfEdge3;
+pub use super::super::IterHalfTriangle3;
 pub use super::super::Simplicial3;
 
-/// Checks halfedge validity
-pub fn halfedge3_is_valid(halfedge: &IterHalfEdge3) -> bool {
+/// Halfedge location, as halftriangle index and node values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HalfEdge3Location {
+    /// Index of halftriangle containing halfedge
+    pub halftriangle: usize,
+    /// First and last node values
+    pub nodes: [usize; 2],
+}
+
+impl HalfEdge3Location {
+    fn new(halfedge: &IterHalfEdge3) -> HalfEdge3Location {
+        HalfEdge3Location {
+            halftriangle: halfedge.halftriangle().ind(),
+            nodes: halfedge.node_values(),
+        }
+    }
+}
+
+/// Violation of 3D simplicial structure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Simplicial3Violation {
+    /// Next halfedge does not start at last node of halfedge
+    WrongNext(HalfEdge3Location),
+    /// Next halfedge is not on the same halftriangle
+    WrongNextHalftriangle(HalfEdge3Location),
+    /// Next halfedge is not on the same tetrahedron
+    WrongNextTetrahedron(HalfEdge3Location),
+    /// Previous halfedge does not end at first node of halfedge
+    WrongPrevious(HalfEdge3Location),
+    /// Previous halfedge is not on the same halftriangle
+    WrongPreviousHalftriangle(HalfEdge3Location),
+    /// Previous halfedge is not on the same tetrahedron
+    WrongPreviousTetrahedron(HalfEdge3Location),
+    /// Opposite halfedge does not go through the same nodes in reverse direction
+    WrongOpposite(HalfEdge3Location),
+    /// Opposite halfedge is on the same tetrahedron
+    WrongOppositeTetrahedron(HalfEdge3Location),
+    /// Neighbor halfedge does not go through the same nodes in reverse direction
+    WrongNeighbor(HalfEdge3Location),
+    /// Neighbor halfedge is on the same halftriangle
+    WrongNeighborHalftriangle(HalfEdge3Location),
+    /// Neighbor halfedge is not on the same tetrahedron
+    WrongNeighborTetrahedron(HalfEdge3Location),
+    /// Opposite of opposite halftriangle is not the halftriangle,
+    /// or opposite halftriangle has different nodes
+    WrongOppositeHalftriangle {
+        /// Halftriangle index
+        halftriangle: usize,
+    },
+    /// Halfedges of opposite halftriangles are not matched in reverse direction
+    WrongShift {
+        /// Halftriangle index
+        halftriangle: usize,
+    },
+    /// Registered halfedges of a node differ from halfedges starting at the node
+    StaleNodeRegistry {
+        /// Node value
+        node: usize,
+    },
+}
+
+impl fmt::Display for Simplicial3Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (location, message) = match self {
+            Simplicial3Violation::WrongNext(loc) => (loc, "wrong next halfedge"),
+            Simplicial3Violation::WrongNextHalftriangle(loc) => {
+                (loc, "wrong halftriangle for next halfedge")
+            }
+            Simplicial3Violation::WrongNextTetrahedron(loc) => {
+                (loc, "wrong tetrahedron for next halfedge")
+            }
+            Simplicial3Violation::WrongPrevious(loc) => (loc, "wrong previous halfedge"),
+            Simplicial3Violation::WrongPreviousHalftriangle(loc) => {
+                (loc, "wrong halftriangle for previous halfedge")
+            }
+            Simplicial3Violation::WrongPreviousTetrahedron(loc) => {
+                (loc, "wrong tetrahedron for previous halfedge")
+            }
+            Simplicial3Violation::WrongOpposite(loc) => (loc, "wrong opposite halfedge"),
+            Simplicial3Violation::WrongOppositeTetrahedron(loc) => {
+                (loc, "wrong tetrahedron for opposite halfedge")
+            }
+            Simplicial3Violation::WrongNeighbor(loc) => (loc, "wrong neighbor halfedge"),
+            Simplicial3Violation::WrongNeighborHalftriangle(loc) => {
+                (loc, "wrong halftriangle for neighbor halfedge")
+            }
+            Simplicial3Violation::WrongNeighborTetrahedron(loc) => {
+                (loc, "wrong tetrahedron for neighbor halfedge")
+            }
+            Simplicial3Violation::WrongOppositeHalftriangle { halftriangle } => {
+                return write!(
+                    f,
+                    "Halftriangle {}: wrong opposite halftriangle",
+                    halftriangle
+                )
+            }
+            Simplicial3Violation::WrongShift { halftriangle } => {
+                return write!(f, "Halftriangle {}: wrong shift", halftriangle)
+            }
+            Simplicial3Violation::StaleNodeRegistry { node } => {
+                return write!(f, "Node {}: registered halfedges differ", node)
+            }
+        };
+        write!(
+            f,
+            "Halfedge {:?} of halftriangle {}: {}",
+            location.nodes, location.halftriangle, message
+        )
+    }
+}
+
+/// Logs violations, and checks if there is none
+fn log_violations(violations: &[Simplicial3Violation]) -> bool {
+    for violation in violations.iter() {
+        log::error!("{}", violation);
+    }
+    violations.is_empty()
+}
+
+/// Gets violations of halfedge structure
+pub fn halfedge3_violations(halfedge: &IterHalfEdge3) -> Vec<Simplicial3Violation> {
     let first_node = halfedge.first_node();
     let last_node = halfedge.last_node();
 
@@ -13,87 +134,87 @@ pub fn halfedge3_is_valid(halfedge: &IterHalfEdge3) -> bool {
     let he_opp = halfedge.opposite();
     let he_nei = halfedge.neighbor();
 
-    let mut valid = true;
+    let mut violations = Vec::new();
+    let loc = HalfEdge3Location::new(halfedge);
 
     if he_next.first_node().value() != last_node.value() {
-        log::error!("{}: Wrong next halfedge", halfedge.to_string());
-        valid = false;
+        violations.push(Simplicial3Violation::WrongNext(loc));
     }
     if he_next.halftriangle().node_values() != halfedge.halftriangle().node_values() {
-        log::error!(
-            "{}: Wrong halftriangle for next hafledge",
-            halfedge.to_string()
-        );
-        valid = false;
+        violations.push(Simplicial3Violation::WrongNextHalftriangle(loc));
     }
     if he_next.tetrahedron().node_values() != halfedge.tetrahedron().node_values() {
-        log::error!(
-            "{}: Wrong tetrahedron for next hafledge",
-            halfedge.to_string()
-        );
-        valid = false;
+        violations.push(Simplicial3Violation::WrongNextTetrahedron(loc));
     }
 
     if he_prev.last_node().value() != first_node.value() {
-        log::error!("{}: Wrong previous halfedge", halfedge.to_string());
-        valid = false;
+        violations.push(Simplicial3Violation::WrongPrevious(loc));
     }
     if he_prev.halftriangle().node_values() != halfedge.halftriangle().node_values() {
-        log::error!(
-            "{}: Wrong halftriangle for prev hafledge",
-            halfedge.to_string()
-        );
-        valid = false;
+        violations.push(Simplicial3Violation::WrongPreviousHalftriangle(loc));
     }
     if he_prev.tetrahedron().node_values() != halfedge.tetrahedron().node_values() {
-        log::error!(
-            "{}: Wrong tetrahedron for prev hafledge",
-            halfedge.to_string()
-        );
-        valid = false;
+        violations.push(Simplicial3Violation::WrongPreviousTetrahedron(loc));
     }
 
     if he_opp.first_node().value() != last_node.value()
         || he_opp.last_node().value() != first_node.value()
     {
-        log::error!("{}: Wrong opposite halfedge", halfedge.to_string());
-        valid = false;
+        violations.push(Simplicial3Violation::WrongOpposite(loc));
     }
     if he_opp.tetrahedron().node_values() == halfedge.tetrahedron().node_values() {
-        log::error!(
-            "{}: Wrong tetrahedron for oppoite hafledge",
-            halfedge.to_string()
-        );
-        valid = false;
+        violations.push(Simplicial3Violation::WrongOppositeTetrahedron(loc));
     }
 
     if he_nei.first_node().value() != last_node.value()
         || he_nei.last_node().value() != first_node.value()
     {
-        log::error!("{}: Wrong neighbor halfedge", halfedge.to_string());
-        valid = false;
+        violations.push(Simplicial3Violation::WrongNeighbor(loc));
     }
     if he_nei.halftriangle().node_values() == halfedge.halftriangle().node_values() {
-        log::error!(
-            "{}: Wrong halftriangle for neighbor hafledge",
-            halfedge.to_string()
-        );
-        valid = false;
+        violations.push(Simplicial3Violation::WrongNeighborHalftriangle(loc));
     }
     if he_nei.tetrahedron().node_values() != halfedge.tetrahedron().node_values() {
-        log::error!(
-            "{}: Wrong tetrahedron for neighbor hafledge",
-            halfedge.to_string()
-        );
-        valid = false;
+        violations.push(Simplicial3Violation::WrongNeighborTetrahedron(loc));
     }
 
-    valid
+    violations
 }
 
-/// Checks node indices within simplicial
-pub fn check_node_indices(simplicial: &Simplicial3) -> bool {
-    let mut valid = true;
+/// Checks halfedge validity
+pub fn halfedge3_is_valid(halfedge: &IterHalfEdge3) -> bool {
+    log_violations(&halfedge3_violations(halfedge))
+}
+
+/// Gets violations of halftriangle opposite and shift
+pub fn halftriangle3_violations(halftriangle: &IterHalfTriangle3) -> Vec<Simplicial3Violation> {
+    let mut violations = Vec::new();
+    let ind_htri = halftriangle.ind();
+    let htri_opp = halftriangle.opposite();
+
+    let mut nodes = halftriangle.node_values();
+    let mut nodes_opp = htri_opp.node_values();
+    nodes.sort();
+    nodes_opp.sort();
+    if htri_opp.opposite().ind() != ind_htri || nodes != nodes_opp {
+        violations.push(Simplicial3Violation::WrongOppositeHalftriangle {
+            halftriangle: ind_htri,
+        });
+    } else if halftriangle.halfedges().iter().any(|he| {
+        let [n0, n1] = he.node_values();
+        he.opposite().node_values() != [n1, n0]
+    }) {
+        violations.push(Simplicial3Violation::WrongShift {
+            halftriangle: ind_htri,
+        });
+    }
+
+    violations
+}
+
+/// Gets violations of node indices within simplicial
+pub fn node_indices_violations(simplicial: &Simplicial3) -> Vec<Simplicial3Violation> {
+    let mut violations = Vec::new();
     let mut nod_indices = Vec::new();
 
     for tetra in simplicial.get_all_tetrahedra().iter() {
@@ -104,7 +225,7 @@ pub fn check_node_indices(simplicial: &Simplicial3) -> bool {
             nod_indices.resize(nod_max + 1, Vec::new());
         }
 
-        nod_indices[n0].push((ind_tet << 2) + 0);
+        nod_indices[n0].push(ind_tet << 2);
         nod_indices[n1].push((ind_tet << 2) + 1);
         nod_indices[n2].push((ind_tet << 2) + 2);
         nod_indices[n3].push((ind_tet << 2) + 3);
@@ -112,13 +233,11 @@ pub fn check_node_indices(simplicial: &Simplicial3) -> bool {
 
     for (node_value, vec) in nod_indices.iter().enumerate() {
         let nod_opt = simplicial.find_node(node_value);
-        if vec.len() == 0 {
+        if vec.is_empty() {
             if nod_opt.is_some() {
-                valid = false;
-                break;
-            } else {
-                continue;
+                violations.push(Simplicial3Violation::StaleNodeRegistry { node: node_value });
             }
+            continue;
         }
 
         if let Some(nod) = nod_opt {
@@ -133,27 +252,39 @@ pub fn check_node_indices(simplicial: &Simplicial3) -> bool {
             vec_clone.sort();
             vec_clone.dedup();
             if ind_he != vec_clone {
-                valid = false;
-                break;
+                violations.push(Simplicial3Violation::StaleNodeRegistry { node: node_value });
             }
         } else {
-            valid = false;
-            break;
+            violations.push(Simplicial3Violation::StaleNodeRegistry { node: node_value });
         }
     }
 
-    valid
+    violations
 }
 
-/// Checks validity of simplicial graph
-pub fn simplicial3_is_valid(simplicial: &Simplicial3) -> Result<bool> {
-    let mut valid = true;
-
-    for he in simplicial.get_all_halfedges().iter() {
-        valid = valid && halfedge3_is_valid(&he);
-    }
+/// Checks node indices within simplicial
+pub fn check_node_indices(simplicial: &Simplicial3) -> bool {
+    log_violations(&node_indices_violations(simplicial))
+}
 
-    valid = valid && check_node_indices(simplicial);
+/// Gets all violations of simplicial graph
+pub fn simplicial3_violations(simplicial: &Simplicial3) -> Vec<Simplicial3Violation> {
+    let mut violations: Vec<Simplicial3Violation> = simplicial
+        .get_all_halfedges()
+        .iter()
+        .flat_map(halfedge3_violations)
+        .collect();
+    violations.extend(
+        simplicial
+            .get_all_halftriangles()
+            .iter()
+            .flat_map(halftriangle3_violations),
+    );
+    violations.extend(node_indices_violations(simplicial));
+    violations
+}
 
-    Ok(valid)
+/// Checks validity of simplicial graph
+pub fn simplicial3_is_valid(simplicial: &Simplicial3) -> Result<bool> {
+    Ok(log_violations(&simplicial3_violations(simplicial)))
 }
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use super::super::{IterTetrahedron3, Simplicial3};
+use crate::graph_structure::union_find::UnionFind;
+
+/// Gets index of a simplex, inserting it if new
+fn simplex_index<const N: usize>(
+    indices: &mut HashMap<[usize; N], usize>,
+    nodes: [usize; N],
+) -> usize {
+    let nb = indices.len();
+    *indices.entry(nodes).or_insert(nb)
+}
+
+/// Computes Z2 rank of a boundary matrix, given as lists of row indices for each column
+///
+/// Columns are reduced from left to right, using their largest row index as pivot
+fn z2_rank(columns: Vec<Vec<usize>>) -> usize {
+    let mut pivots: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut rank = 0;
+    for mut column in columns {
+        column.sort();
+        while let Some(&low) = column.last() {
+            if let Some(pivot_column) = pivots.get(&low) {
+                // symmetric difference of sorted columns
+                let mut reduced = Vec::with_capacity(column.len() + pivot_column.len());
+                let (mut i, mut j) = (0, 0);
+                while i < column.len() || j < pivot_column.len() {
+                    if j == pivot_column.len() || (i < column.len() && column[i] < pivot_column[j])
+                    {
+                        reduced.push(column[i]);
+                        i += 1;
+                    } else if i == column.len() || pivot_column[j] < column[i] {
+                        reduced.push(pivot_column[j]);
+                        j += 1;
+                    } else {
+                        i += 1;
+                        j += 1;
+                    }
+                }
+                column = reduced;
+            } else {
+                pivots.insert(low, column);
+                rank += 1;
+                break;
+            }
+        }
+    }
+    rank
+}
+
+/// Computes Betti numbers (with Z2 coefficients) of the subcomplex made of selected
+/// tetrahedra and all their faces
+///
+/// Returns [b0, b1, b2, b3]: numbers of connected components, tunnels, cavities and
+/// closed volumes.
+pub fn subcomplex_betti_numbers<F>(simpl: &Simplicial3, is_selected: F) -> [usize; 4]
+where
+    F: Fn(&IterTetrahedron3) -> bool,
+{
+    let tetrahedra: Vec<IterTetrahedron3> = simpl
+        .get_all_tetrahedra()
+        .into_iter()
+        .filter(|tetra| is_selected(tetra))
+        .collect();
+    if tetrahedra.is_empty() {
+        return [0, 0, 0, 0];
+    }
+
+    // faces of subcomplex
+    let mut nodes = HashMap::new();
+    let mut edges = HashMap::new();
+    let mut triangles = HashMap::new();
+    let mut triangle_edges = Vec::new();
+    for tetra in tetrahedra.iter() {
+        let mut tetra_nodes = tetra.node_values();
+        tetra_nodes.sort();
+        let [n0, n1, n2, n3] = tetra_nodes;
+        for node in tetra_nodes {
+            simplex_index(&mut nodes, [node]);
+        }
+        for triangle in [[n0, n1, n2], [n0, n1, n3], [n0, n2, n3], [n1, n2, n3]] {
+            if triangles.contains_key(&triangle) {
+                continue;
+            }
+            simplex_index(&mut triangles, triangle);
+            let [a, b, c] = triangle;
+            triangle_edges.push(vec![
+                simplex_index(&mut edges, [a, b]),
+                simplex_index(&mut edges, [a, c]),
+                simplex_index(&mut edges, [b, c]),
+            ]);
+        }
+    }
+    let nb_nodes = nodes.len();
+    let nb_edges = edges.len();
+    let nb_triangles = triangles.len();
+    let nb_tetrahedra = tetrahedra.len();
+
+    // b0 from edge connectivity, rank of node boundary matrix is nb_nodes - b0
+    let mut union_find = UnionFind::new(nb_nodes);
+    let mut nb_merges = 0;
+    for &[a, b] in edges.keys() {
+        if union_find.union(nodes[&[a]], nodes[&[b]]) {
+            nb_merges += 1;
+        }
+    }
+    let b0 = nb_nodes - nb_merges;
+    let rank1 = nb_merges;
+
+    // b3 counts face-connected components of selected tetrahedra with no free face
+    let mut tetra_index = vec![usize::MAX; simpl.get_nb_tetrahedra()];
+    for (ind, tetra) in tetrahedra.iter().enumerate() {
+        tetra_index[tetra.ind()] = ind;
+    }
+    let mut union_find = UnionFind::new(nb_tetrahedra);
+    let mut has_free_face = vec![false; nb_tetrahedra];
+    for (ind, tetra) in tetrahedra.iter().enumerate() {
+        for htri in tetra.halftriangles() {
+            let ind_nei = tetra_index[htri.opposite().tetrahedron().ind()];
+            if ind_nei == usize::MAX {
+                has_free_face[ind] = true;
+            } else {
+                union_find.union(ind, ind_nei);
+            }
+        }
+    }
+    let mut component_free = HashMap::new();
+    for (ind, &free) in has_free_face.iter().enumerate() {
+        let root = union_find.find(ind);
+        *component_free.entry(root).or_insert(false) |= free;
+    }
+    let b3 = component_free.values().filter(|&&free| !free).count();
+    let rank3 = nb_tetrahedra - b3;
+
+    let rank2 = z2_rank(triangle_edges);
+
+    let b1 = nb_edges - rank1 - rank2;
+    let b2 = nb_triangles - rank2 - rank3;
+    [b0, b1, b2, b3]
+}
+
+/// Computes Betti numbers (with Z2 coefficients) of the whole simplicial
+///
+/// Returns [b0, b1, b2, b3]
+pub fn betti_numbers(simpl: &Simplicial3) -> [usize; 4] {
+    subcomplex_betti_numbers(simpl, |_| true)
+}
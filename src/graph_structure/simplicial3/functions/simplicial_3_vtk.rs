@@ -0,0 +1,273 @@
+use anyhow::Result;
+use nalgebra::base::*;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use ply_rs::ply::{PropertyType, ScalarType};
+
+use super::super::Simplicial3;
+use crate::mesh_structure::property_set::PropertySet;
+
+/// VTK cell type of tetrahedra
+const VTK_TETRA: usize = 10;
+
+/// Scalar data array (point or cell data)
+struct DataArray {
+    name: String,
+    scalar_type: ScalarType,
+    values: Vec<f64>,
+}
+
+/// Gets tetrahedra to write (tetrahedra touching infinite node are skipped)
+fn kept_tetrahedra(
+    simpl: &Simplicial3,
+    node_coords: &[Vector3<f64>],
+    infinite_node: Option<usize>,
+) -> Result<Vec<(usize, [usize; 4])>> {
+    let mut tetrahedra = Vec::new();
+    for tetra in simpl.get_all_tetrahedra() {
+        let nodes = tetra.node_values();
+        if infinite_node.is_some_and(|inf| nodes.contains(&inf)) {
+            continue;
+        }
+        if nodes.iter().any(|&nod| nod >= node_coords.len()) {
+            return Err(anyhow::Error::msg(
+                "Node value out of node coordinates bounds",
+            ));
+        }
+        tetrahedra.push((tetra.ind(), nodes));
+    }
+    Ok(tetrahedra)
+}
+
+/// Gets scalar properties of given elements (list properties are skipped)
+fn scalar_arrays(
+    properties: Option<&PropertySet>,
+    nb_elements: usize,
+    elements: &[usize],
+) -> Result<Vec<DataArray>> {
+    let mut arrays = Vec::new();
+    if let Some(properties) = properties {
+        if properties.get_nb_elements() != nb_elements {
+            return Err(anyhow::Error::msg(
+                "Number of property elements differs from number of simplicial elements",
+            ));
+        }
+        for name in properties.get_property_names() {
+            if let PropertyType::Scalar(scalar_type) = properties.get_property_type(&name)? {
+                let values = elements
+                    .iter()
+                    .map(|&ind| properties.get_property_value_f64(ind, &name))
+                    .collect::<Result<Vec<f64>>>()?;
+                arrays.push(DataArray {
+                    name,
+                    scalar_type,
+                    values,
+                });
+            }
+        }
+    }
+    Ok(arrays)
+}
+
+fn legacy_type_name(scalar_type: &ScalarType) -> &'static str {
+    match scalar_type {
+        ScalarType::Char => "char",
+        ScalarType::UChar => "unsigned_char",
+        ScalarType::Short => "short",
+        ScalarType::UShort => "unsigned_short",
+        ScalarType::Int => "int",
+        ScalarType::UInt => "unsigned_int",
+        ScalarType::Float => "float",
+        ScalarType::Double => "double",
+    }
+}
+
+fn xml_type_name(scalar_type: &ScalarType) -> &'static str {
+    match scalar_type {
+        ScalarType::Char => "Int8",
+        ScalarType::UChar => "UInt8",
+        ScalarType::Short => "Int16",
+        ScalarType::UShort => "UInt16",
+        ScalarType::Int => "Int32",
+        ScalarType::UInt => "UInt32",
+        ScalarType::Float => "Float32",
+        ScalarType::Double => "Float64",
+    }
+}
+
+fn xml_escape(name: &str) -> String {
+    name.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn write_legacy_arrays(file: &mut impl Write, arrays: &[DataArray]) -> Result<()> {
+    for array in arrays.iter() {
+        writeln!(
+            file,
+            "SCALARS {} {} 1",
+            array.name.replace(char::is_whitespace, "_"),
+            legacy_type_name(&array.scalar_type)
+        )?;
+        writeln!(file, "LOOKUP_TABLE default")?;
+        for value in array.values.iter() {
+            writeln!(file, "{}", value)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_xml_arrays(file: &mut impl Write, arrays: &[DataArray]) -> Result<()> {
+    for array in arrays.iter() {
+        writeln!(
+            file,
+            "        <DataArray type=\"{}\" Name=\"{}\" format=\"ascii\">",
+            xml_type_name(&array.scalar_type),
+            xml_escape(&array.name)
+        )?;
+        for value in array.values.iter() {
+            writeln!(file, "          {}", value)?;
+        }
+        writeln!(file, "        </DataArray>")?;
+    }
+    Ok(())
+}
+
+/// Saves 3D simplicial as legacy VTK file (unstructured grid of tetrahedra)
+///
+/// Node values are indices of node coordinates, all coordinates are written as points.
+/// Scalar properties of nodes (indexed by node value) and of tetrahedra (indexed by
+/// tetrahedron index) are written as point and cell data.
+/// Tetrahedra touching the infinite node, if given, are skipped.
+pub fn save_simplicial3_vtk(
+    filename: &str,
+    simpl: &Simplicial3,
+    node_coords: &[Vector3<f64>],
+    node_properties: Option<&PropertySet>,
+    tetrahedron_properties: Option<&PropertySet>,
+    infinite_node: Option<usize>,
+) -> Result<()> {
+    let tetrahedra = kept_tetrahedra(simpl, node_coords, infinite_node)?;
+    let nodes: Vec<usize> = (0..node_coords.len()).collect();
+    let point_arrays = scalar_arrays(node_properties, node_coords.len(), &nodes)?;
+    let cells: Vec<usize> = tetrahedra.iter().map(|&(ind, _)| ind).collect();
+    let cell_arrays = scalar_arrays(tetrahedron_properties, simpl.get_nb_tetrahedra(), &cells)?;
+
+    let mut file = BufWriter::new(File::create(filename)?);
+    writeln!(file, "# vtk DataFile Version 3.0")?;
+    writeln!(file, "Simplicial3")?;
+    writeln!(file, "ASCII")?;
+    writeln!(file, "DATASET UNSTRUCTURED_GRID")?;
+
+    writeln!(file, "POINTS {} double", node_coords.len())?;
+    for coord in node_coords.iter() {
+        writeln!(file, "{} {} {}", coord[0], coord[1], coord[2])?;
+    }
+
+    writeln!(file, "CELLS {} {}", tetrahedra.len(), tetrahedra.len() * 5)?;
+    for (_, [n0, n1, n2, n3]) in tetrahedra.iter() {
+        writeln!(file, "4 {} {} {} {}", n0, n1, n2, n3)?;
+    }
+    writeln!(file, "CELL_TYPES {}", tetrahedra.len())?;
+    for _ in tetrahedra.iter() {
+        writeln!(file, "{}", VTK_TETRA)?;
+    }
+
+    if !point_arrays.is_empty() {
+        writeln!(file, "POINT_DATA {}", node_coords.len())?;
+        write_legacy_arrays(&mut file, &point_arrays)?;
+    }
+    if !cell_arrays.is_empty() {
+        writeln!(file, "CELL_DATA {}", tetrahedra.len())?;
+        write_legacy_arrays(&mut file, &cell_arrays)?;
+    }
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Saves 3D simplicial as XML VTK unstructured grid file (.vtu)
+///
+/// Same content as [`save_simplicial3_vtk`].
+pub fn save_simplicial3_vtu(
+    filename: &str,
+    simpl: &Simplicial3,
+    node_coords: &[Vector3<f64>],
+    node_properties: Option<&PropertySet>,
+    tetrahedron_properties: Option<&PropertySet>,
+    infinite_node: Option<usize>,
+) -> Result<()> {
+    let tetrahedra = kept_tetrahedra(simpl, node_coords, infinite_node)?;
+    let nodes: Vec<usize> = (0..node_coords.len()).collect();
+    let point_arrays = scalar_arrays(node_properties, node_coords.len(), &nodes)?;
+    let cells: Vec<usize> = tetrahedra.iter().map(|&(ind, _)| ind).collect();
+    let cell_arrays = scalar_arrays(tetrahedron_properties, simpl.get_nb_tetrahedra(), &cells)?;
+
+    let mut file = BufWriter::new(File::create(filename)?);
+    writeln!(file, "<?xml version=\"1.0\"?>")?;
+    writeln!(
+        file,
+        "<VTKFile type=\"UnstructuredGrid\" version=\"0.1\" byte_order=\"LittleEndian\">"
+    )?;
+    writeln!(file, "  <UnstructuredGrid>")?;
+    writeln!(
+        file,
+        "    <Piece NumberOfPoints=\"{}\" NumberOfCells=\"{}\">",
+        node_coords.len(),
+        tetrahedra.len()
+    )?;
+
+    writeln!(file, "      <PointData>")?;
+    write_xml_arrays(&mut file, &point_arrays)?;
+    writeln!(file, "      </PointData>")?;
+    writeln!(file, "      <CellData>")?;
+    write_xml_arrays(&mut file, &cell_arrays)?;
+    writeln!(file, "      </CellData>")?;
+
+    writeln!(file, "      <Points>")?;
+    writeln!(
+        file,
+        "        <DataArray type=\"Float64\" NumberOfComponents=\"3\" format=\"ascii\">"
+    )?;
+    for coord in node_coords.iter() {
+        writeln!(file, "          {} {} {}", coord[0], coord[1], coord[2])?;
+    }
+    writeln!(file, "        </DataArray>")?;
+    writeln!(file, "      </Points>")?;
+
+    writeln!(file, "      <Cells>")?;
+    writeln!(
+        file,
+        "        <DataArray type=\"Int64\" Name=\"connectivity\" format=\"ascii\">"
+    )?;
+    for (_, [n0, n1, n2, n3]) in tetrahedra.iter() {
+        writeln!(file, "          {} {} {} {}", n0, n1, n2, n3)?;
+    }
+    writeln!(file, "        </DataArray>")?;
+    writeln!(
+        file,
+        "        <DataArray type=\"Int64\" Name=\"offsets\" format=\"ascii\">"
+    )?;
+    for ind in 0..tetrahedra.len() {
+        writeln!(file, "          {}", 4 * (ind + 1))?;
+    }
+    writeln!(file, "        </DataArray>")?;
+    writeln!(
+        file,
+        "        <DataArray type=\"UInt8\" Name=\"types\" format=\"ascii\">"
+    )?;
+    for _ in tetrahedra.iter() {
+        writeln!(file, "          {}", VTK_TETRA)?;
+    }
+    writeln!(file, "        </DataArray>")?;
+    writeln!(file, "      </Cells>")?;
+
+    writeln!(file, "    </Piece>")?;
+    writeln!(file, "  </UnstructuredGrid>")?;
+    writeln!(file, "</VTKFile>")?;
+    file.flush()?;
+
+    Ok(())
+}
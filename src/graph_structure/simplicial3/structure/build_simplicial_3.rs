@@ -1,5 +1,7 @@
 use anyhow::Result;
 use std::cmp::max;
+use std::collections::HashMap;
+use std::fmt;
 
 use super::{
     simplicial_3::ABC2ACB, simplicial_3::ABC2BAC, simplicial_3::ABC2CBA, IterHalfTriangle3,
@@ -21,6 +23,11 @@ pub(super) fn add_empty_tetrahedron(simpl3: &mut Simplicial3) -> usize {
 
     simpl3.nb_tetrahedra = simpl3.nb_tetrahedra + 1;
 
+    simpl3.tetrahedron_attributes.resize(simpl3.nb_tetrahedra);
+    simpl3
+        .halftriangle_attributes
+        .resize(simpl3.nb_tetrahedra << 2);
+
     simpl3.nb_tetrahedra - 1
 }
 
@@ -47,6 +54,12 @@ pub(super) fn unset_tetrahedron(simpl3: &mut Simplicial3, ind_tet: usize) {
     simpl3.halftriangle_shift[ind_first + 2] = 3;
     simpl3.halftriangle_shift[ind_first + 3] = 3;
 
+    simpl3.tetrahedron_attributes.reset_element(ind_tet);
+    simpl3.halftriangle_attributes.reset_element(ind_first);
+    simpl3.halftriangle_attributes.reset_element(ind_first + 1);
+    simpl3.halftriangle_attributes.reset_element(ind_first + 2);
+    simpl3.halftriangle_attributes.reset_element(ind_first + 3);
+
     if let Some(vec) = simpl3.node_positions.as_mut() {
         vec[nod0].retain(|&x| x >> 2 != ind_tet);
         vec[nod1].retain(|&x| x >> 2 != ind_tet);
@@ -69,8 +82,12 @@ pub(super) fn set_tetrahedron(
     simpl3.tet_nodes[ind_first + 2] = nod3;
     simpl3.tet_nodes[ind_first + 3] = nod4;
 
+    let max_nod = max(max(nod1, nod2), max(nod3, nod4));
+    if simpl3.node_attributes.get_nb_elements() <= max_nod {
+        simpl3.node_attributes.resize(max_nod + 1);
+    }
+
     if let Some(vec) = simpl3.node_positions.as_mut() {
-        let max_nod = max(max(nod1, nod2), max(nod3, nod4));
         if vec.len() <= max_nod {
             vec.resize(max_nod + 1, Vec::new());
         }
@@ -118,6 +135,16 @@ pub(super) fn remove_tetrahedron(simpl3: &mut Simplicial3, ind_tetra: usize) ->
         simpl3.halftriangle_opposite[ind_tri_opp2] = ind_tri2;
         simpl3.halftriangle_opposite[ind_tri_opp3] = ind_tri3;
         simpl3.halftriangle_opposite[ind_tri_opp4] = ind_tri4;
+
+        let ind_tetra_last = simpl3.nb_tetrahedra - 1;
+        simpl3
+            .tetrahedron_attributes
+            .move_element(ind_tetra_last, ind_tetra);
+        for i in 0..4 {
+            simpl3
+                .halftriangle_attributes
+                .move_element((ind_tetra_last << 2) + i, (ind_tetra << 2) + i);
+        }
     }
 
     simpl3.tet_nodes.pop();
@@ -137,6 +164,11 @@ pub(super) fn remove_tetrahedron(simpl3: &mut Simplicial3, ind_tetra: usize) ->
 
     simpl3.nb_tetrahedra = simpl3.nb_tetrahedra - 1;
 
+    simpl3.tetrahedron_attributes.resize(simpl3.nb_tetrahedra);
+    simpl3
+        .halftriangle_attributes
+        .resize(simpl3.nb_tetrahedra << 2);
+
     Ok(())
 }
 
@@ -178,6 +210,42 @@ pub(super) fn oppose_halftriangles_auto(
     Ok(())
 }
 
+/// Face of a tetrahedron list that cannot be matched with exactly one opposite face
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonManifoldFace {
+    /// Face nodes (sorted)
+    pub nodes: [usize; 3],
+    /// Indices (within given list) of tetrahedra containing the face
+    pub tetrahedra: Vec<usize>,
+}
+
+/// Error returned when a tetrahedron list does not form a closed manifold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonManifoldFacesError {
+    /// Faces shared by a number of tetrahedra different from 2, or with same orientation
+    pub faces: Vec<NonManifoldFace>,
+}
+
+impl fmt::Display for NonManifoldFacesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Given tetrahedra do not form a manifold simplicial ({} non manifold faces)",
+            self.faces.len()
+        )?;
+        for face in self.faces.iter() {
+            write!(
+                f,
+                "\n  face {:?} in tetrahedra {:?}",
+                face.nodes, face.tetrahedra
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for NonManifoldFacesError {}
+
 ////////////////////////////////
 /// Public modifying methods ///
 ////////////////////////////////
@@ -202,3 +270,154 @@ pub fn first_tetrahedron(simpl3: &mut Simplicial3, nodes: [usize; 4]) -> Result<
 
     Ok([ind_tet0, ind_tet1])
 }
+
+/// Builds full simplicial from set of tetrahedra
+///
+/// Tetrahedra have to be consistently oriented, and each face has to be shared by exactly
+/// two tetrahedra. Otherwise, returns a [`NonManifoldFacesError`] listing wrong faces.
+pub fn build_from_tetrahedron_list(
+    tetrahedra: Vec<[usize; 4]>,
+    register_node_halfedges: bool,
+) -> Result<Simplicial3> {
+    let mut simpl3 = Simplicial3::new(register_node_halfedges);
+
+    for &[nod0, nod1, nod2, nod3] in tetrahedra.iter() {
+        if nod0 == nod1
+            || nod0 == nod2
+            || nod0 == nod3
+            || nod1 == nod2
+            || nod1 == nod3
+            || nod2 == nod3
+        {
+            return Err(anyhow::Error::msg("Degenerated tetrahedron in list"));
+        }
+        let ind_tet = add_empty_tetrahedron(&mut simpl3);
+        set_tetrahedron(&mut simpl3, ind_tet, nod0, nod1, nod2, nod3);
+    }
+
+    // group halftriangles by face
+    let mut face_halftriangles: HashMap<[usize; 3], Vec<usize>> = HashMap::new();
+    for ind_htri in 0..simpl3.get_nb_tetrahedra() << 2 {
+        let mut nodes = IterHalfTriangle3::new(&simpl3, ind_htri).node_values();
+        nodes.sort();
+        face_halftriangles.entry(nodes).or_default().push(ind_htri);
+    }
+
+    let mut faces = Vec::new();
+    let mut to_oppose = Vec::new();
+    for (nodes, vec_htri) in face_halftriangles.into_iter() {
+        let opposed = vec_htri.len() == 2 && {
+            let [a, b, c] = IterHalfTriangle3::new(&simpl3, vec_htri[0]).node_values();
+            let nodes1 = IterHalfTriangle3::new(&simpl3, vec_htri[1]).node_values();
+            nodes1 == [a, c, b] || nodes1 == [c, b, a] || nodes1 == [b, a, c]
+        };
+        if opposed {
+            to_oppose.push((vec_htri[0], vec_htri[1]));
+        } else {
+            faces.push(NonManifoldFace {
+                nodes,
+                tetrahedra: vec_htri.iter().map(|&ind_htri| ind_htri >> 2).collect(),
+            });
+        }
+    }
+
+    if !faces.is_empty() {
+        faces.sort_by_key(|face| face.nodes);
+        return Err(anyhow::Error::new(NonManifoldFacesError { faces }));
+    }
+
+    for (htri0, htri1) in to_oppose {
+        oppose_halftriangles_auto(&mut simpl3, htri0, htri1)?;
+    }
+
+    Ok(simpl3)
+}
+
+/// Closes a set of tetrahedra with an infinite node
+///
+/// Each boundary face (face belonging to only one tetrahedron) is linked to the infinite node
+/// by a new tetrahedron, so that the returned list can be given to
+/// [`build_from_tetrahedron_list`]. Added tetrahedra are appended after the given ones.
+pub fn close_tetrahedron_list(
+    tetrahedra: &[[usize; 4]],
+    infinite_node: usize,
+) -> Result<Vec<[usize; 4]>> {
+    let mut face_count: HashMap<[usize; 3], usize> = HashMap::new();
+    let mut oriented_faces = Vec::with_capacity(tetrahedra.len() << 2);
+    for &[n0, n1, n2, n3] in tetrahedra.iter() {
+        if [n0, n1, n2, n3].contains(&infinite_node) {
+            return Err(anyhow::Error::msg(
+                "Infinite node already in tetrahedron list",
+            ));
+        }
+        for face in [[n3, n2, n1], [n2, n3, n0], [n1, n0, n3], [n0, n1, n2]] {
+            let mut key = face;
+            key.sort();
+            *face_count.entry(key).or_insert(0) += 1;
+            oriented_faces.push(face);
+        }
+    }
+
+    let mut closed = tetrahedra.to_vec();
+    for [a, b, c] in oriented_faces {
+        let mut key = [a, b, c];
+        key.sort();
+        if face_count[&key] == 1 {
+            closed.push([a, c, b, infinite_node]);
+        }
+    }
+
+    Ok(closed)
+}
+
+/// Builds simplicial from raw halftriangle arrays
+/// (tetrahedra nodes, opposite and shift of each halftriangle)
+///
+/// Node values have to be lower than nb_nodes. Only checks array consistency, topological
+/// validity has to be checked by caller
+pub(crate) fn build_from_halftriangle_arrays(
+    tet_nodes: Vec<usize>,
+    halftriangle_opposite: Vec<usize>,
+    halftriangle_shift: Vec<usize>,
+    nb_nodes: usize,
+    register_node_halfedges: bool,
+) -> Result<Simplicial3> {
+    let nb_halftriangles = tet_nodes.len();
+    if !nb_halftriangles.is_multiple_of(4)
+        || halftriangle_opposite.len() != nb_halftriangles
+        || halftriangle_shift.len() != nb_halftriangles
+    {
+        return Err(anyhow::Error::msg("Inconsistent halftriangle arrays"));
+    }
+    if tet_nodes.iter().any(|&node| node >= nb_nodes) {
+        return Err(anyhow::Error::msg("Node value out of bounds"));
+    }
+    for (ind_htri, &ind_htri_opp) in halftriangle_opposite.iter().enumerate() {
+        if ind_htri_opp >= nb_halftriangles
+            || halftriangle_opposite[ind_htri_opp] != ind_htri
+            || halftriangle_shift[ind_htri] > 2
+            || halftriangle_shift[ind_htri_opp] != halftriangle_shift[ind_htri]
+        {
+            return Err(anyhow::Error::msg(
+                "Inconsistent halftriangle opposite array",
+            ));
+        }
+    }
+
+    let mut simpl3 = Simplicial3::new(register_node_halfedges);
+    for ind_first in (0..nb_halftriangles).step_by(4) {
+        let ind_tet = add_empty_tetrahedron(&mut simpl3);
+        set_tetrahedron(
+            &mut simpl3,
+            ind_tet,
+            tet_nodes[ind_first],
+            tet_nodes[ind_first + 1],
+            tet_nodes[ind_first + 2],
+            tet_nodes[ind_first + 3],
+        );
+    }
+    simpl3.halftriangle_opposite = halftriangle_opposite;
+    simpl3.halftriangle_shift = halftriangle_shift;
+
+    Ok(simpl3)
+}
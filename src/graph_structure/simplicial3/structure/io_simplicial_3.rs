@@ -0,0 +1,75 @@
+use anyhow::Result;
+use nalgebra::base::*;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+
+use super::{build_from_halftriangle_arrays, Simplicial3};
+use crate::graph_structure::binary_io::{
+    read_coords, read_header, read_u64, read_u64_vec, read_u8_vec, write_coords, write_header,
+    write_u64, write_u64_vec, write_u8_vec,
+};
+use crate::graph_structure::simplicial3::simplicial3_is_valid;
+
+const SIMPLICIAL3_MAGIC: &[u8; 4] = b"SKS3";
+
+/// Saves simplicial as binary file, with optional node coordinates (indexed by node value)
+///
+/// Layout (little endian): magic, version, number of tetrahedra,
+/// tetrahedra nodes, halftriangle opposites, halftriangle shifts, node coordinates
+pub fn save_simplicial3_bin(
+    filename: &str,
+    simplicial: &Simplicial3,
+    node_coords: Option<&Vec<Vector3<f64>>>,
+) -> Result<()> {
+    let file = File::create(filename)?;
+    let mut writer = BufWriter::new(file);
+
+    write_header(&mut writer, SIMPLICIAL3_MAGIC)?;
+    write_u64(&mut writer, simplicial.get_nb_tetrahedra())?;
+    write_u64_vec(&mut writer, &simplicial.tet_nodes)?;
+    write_u64_vec(&mut writer, &simplicial.halftriangle_opposite)?;
+    write_u8_vec(&mut writer, &simplicial.halftriangle_shift)?;
+    write_coords(&mut writer, node_coords)?;
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Loads simplicial from binary file, with optional node coordinates
+///
+/// Node values have to be lower than the number of coordinates if there are some, than the
+/// number of halftriangles otherwise. Node positions are rebuilt if requested, and simplicial
+/// validity is checked
+pub fn load_simplicial3_bin(
+    filename: &str,
+    register_node_halfedges: bool,
+) -> Result<(Simplicial3, Option<Vec<Vector3<f64>>>)> {
+    let file = File::open(filename)?;
+    let mut reader = BufReader::new(file);
+
+    read_header(&mut reader, SIMPLICIAL3_MAGIC)?;
+    let nb_halftriangles = read_u64(&mut reader)?
+        .checked_mul(4)
+        .ok_or(anyhow::Error::msg("Wrong number of tetrahedra"))?;
+    let tet_nodes = read_u64_vec(&mut reader, nb_halftriangles)?;
+    let halftriangle_opposite = read_u64_vec(&mut reader, nb_halftriangles)?;
+    let halftriangle_shift = read_u8_vec(&mut reader, nb_halftriangles)?;
+    let node_coords = read_coords::<3>(&mut reader)?;
+
+    let nb_nodes = node_coords
+        .as_ref()
+        .map_or(nb_halftriangles, |coords| coords.len());
+    let simplicial = build_from_halftriangle_arrays(
+        tet_nodes,
+        halftriangle_opposite,
+        halftriangle_shift,
+        nb_nodes,
+        register_node_halfedges,
+    )?;
+
+    if !simplicial3_is_valid(&simplicial)? {
+        return Err(anyhow::Error::msg("Loaded simplicial is not valid"));
+    }
+
+    Ok((simplicial, node_coords))
+}
@@ -25,3 +25,7 @@ pub use bowyer_watson_inserter::BowyerWatsonInserter;
 /// Build function for 3D simplicial
 mod build_simplicial_3;
 pub use build_simplicial_3::*;
+
+/// Binary input/output functions for 3D simplicial
+mod io_simplicial_3;
+pub use io_simplicial_3::*;
@@ -1,5 +1,7 @@
 use anyhow::Result;
 
+use crate::graph_structure::attribute_set::AttributeSet;
+
 use super::IterHalfEdge3;
 use super::IterHalfTriangle3;
 use super::IterNode3;
@@ -30,6 +32,13 @@ pub struct Simplicial3 {
 
     // optional attribute, containing  node positions within tet_nodes
     pub(super) node_positions: Option<Vec<Vec<usize>>>,
+
+    /// User attributes on tetrahedra (indexed by tetrahedron index)
+    pub(super) tetrahedron_attributes: AttributeSet,
+    /// User attributes on halftriangles (indexed by halftriangle index)
+    pub(super) halftriangle_attributes: AttributeSet,
+    /// User attributes on nodes (indexed by node value)
+    pub(super) node_attributes: AttributeSet,
 }
 
 impl Simplicial3 {
@@ -46,6 +55,9 @@ impl Simplicial3 {
             halftriangle_shift: Vec::new(),
             nb_tetrahedra: 0,
             node_positions,
+            tetrahedron_attributes: AttributeSet::new(0),
+            halftriangle_attributes: AttributeSet::new(0),
+            node_attributes: AttributeSet::new(0),
         }
     }
 
@@ -215,4 +227,34 @@ impl Simplicial3 {
             .map(|ind_tetra| IterTetrahedron3::new(self, ind_tetra))
             .collect()
     }
+
+    /// Gets tetrahedron attributes (indexed by tetrahedron index)
+    pub fn get_tetrahedron_attributes(&self) -> &AttributeSet {
+        &self.tetrahedron_attributes
+    }
+
+    /// Gets mutable tetrahedron attributes (indexed by tetrahedron index)
+    pub fn get_tetrahedron_attributes_mut(&mut self) -> &mut AttributeSet {
+        &mut self.tetrahedron_attributes
+    }
+
+    /// Gets halftriangle attributes (indexed by halftriangle index)
+    pub fn get_halftriangle_attributes(&self) -> &AttributeSet {
+        &self.halftriangle_attributes
+    }
+
+    /// Gets mutable halftriangle attributes (indexed by halftriangle index)
+    pub fn get_halftriangle_attributes_mut(&mut self) -> &mut AttributeSet {
+        &mut self.halftriangle_attributes
+    }
+
+    /// Gets node attributes (indexed by node value)
+    pub fn get_node_attributes(&self) -> &AttributeSet {
+        &self.node_attributes
+    }
+
+    /// Gets mutable node attributes (indexed by node value)
+    pub fn get_node_attributes_mut(&mut self) -> &mut AttributeSet {
+        &mut self.node_attributes
+    }
 }
@@ -4,10 +4,18 @@ mod simplicial3_test {
     use rand::Rng;
     use rstest::rstest;
 
+    use crate::graph_structure::simplicial2::simplicial_2_quality::simplicial2_is_valid;
+    use crate::graph_structure::simplicial2::simplicial_2_topology;
     use crate::graph_structure::simplicial3::{
-        first_tetrahedron, simplicial3_is_valid, BowyerWatsonInserter, IterHalfTriangle3,
-        Simplicial3,
+        betti_numbers, build_from_halftriangle_arrays, build_from_tetrahedron_list,
+        close_tetrahedron_list, extract_region_boundary, extract_region_boundary_mesh,
+        first_tetrahedron, load_simplicial3_bin, save_simplicial3_bin, save_simplicial3_vtk,
+        save_simplicial3_vtu, simplicial3_is_valid, simplicial3_violations,
+        subcomplex_betti_numbers, BowyerWatsonInserter, IterHalfTriangle3, IterTetrahedron3,
+        NonManifoldFacesError, Simplicial3, Simplicial3Violation,
     };
+    use crate::mesh_structure::property_set::PropertySet;
+    use ply_rs::ply::{Property, PropertyType, ScalarType};
 
     fn test_triangle(triabc: IterHalfTriangle3, a: usize, b: usize, c: usize) -> () {
         assert!(triabc.node_values() == [a, b, c]);
@@ -185,4 +193,437 @@ mod simplicial3_test {
 
         Ok(())
     }
+
+    #[test]
+    fn attributes_bw_test() -> Result<()> {
+        let mut rng = rand::thread_rng();
+
+        let mut simpl = Simplicial3::new(true);
+        first_tetrahedron(&mut simpl, [0, 1, 2, 3])?;
+
+        simpl
+            .get_tetrahedron_attributes_mut()
+            .add_attribute::<Option<[usize; 4]>>("nodes".to_string(), None);
+        simpl
+            .get_halftriangle_attributes_mut()
+            .add_attribute::<Option<[usize; 3]>>("nodes".to_string(), None);
+        simpl
+            .get_node_attributes_mut()
+            .add_attribute::<usize>("value".to_string(), 0);
+
+        let mut bw_inserter = BowyerWatsonInserter::new(&simpl);
+        for ind_nod in 4..20 {
+            // label current elements with their nodes
+            for ind_tetra in 0..simpl.get_nb_tetrahedra() {
+                let nodes = simpl.get_tetrahedron_from_index(ind_tetra)?.node_values();
+                simpl.get_tetrahedron_attributes_mut().set_attribute_value(
+                    ind_tetra,
+                    "nodes",
+                    Some(nodes),
+                )?;
+            }
+            for ind_htri in 0..simpl.get_nb_tetrahedra() << 2 {
+                let nodes = simpl.get_halftriangle_from_index(ind_htri)?.node_values();
+                simpl
+                    .get_halftriangle_attributes_mut()
+                    .set_attribute_value(ind_htri, "nodes", Some(nodes))?;
+            }
+
+            let nb_max = std::cmp::min(4, (simpl.get_nb_tetrahedra() >> 1) - 1);
+            let nb_insert = if nb_max != 0 {
+                rng.gen_range(0..nb_max)
+            } else {
+                0
+            };
+
+            let ind_first = rng.gen_range(0..simpl.get_nb_tetrahedra());
+            bw_inserter.set_first_tetra(&simpl, ind_first)?;
+            for _ in 0..nb_insert {
+                if bw_inserter.bw_tetra_to_check(&simpl).is_some() {
+                    bw_inserter.bw_rem_tetra(&simpl)?;
+                }
+            }
+            while bw_inserter.bw_tetra_to_check(&simpl).is_some() {
+                bw_inserter.bw_keep_tetra()?;
+            }
+            bw_inserter.bw_insert_node(&mut simpl, ind_nod)?;
+            simpl
+                .get_node_attributes_mut()
+                .set_attribute_value(ind_nod, "value", ind_nod)?;
+        }
+        bw_inserter.clean(&mut simpl)?;
+        assert!(simplicial3_is_valid(&simpl)?);
+
+        assert!(simpl.get_tetrahedron_attributes().get_nb_elements() == simpl.get_nb_tetrahedra());
+        assert!(
+            simpl.get_halftriangle_attributes().get_nb_elements() == simpl.get_nb_tetrahedra() << 2
+        );
+        assert!(simpl.get_node_attributes().get_nb_elements() == 20);
+
+        for tetra in simpl.get_all_tetrahedra() {
+            let attr = simpl
+                .get_tetrahedron_attributes()
+                .get_attribute_value::<Option<[usize; 4]>>(tetra.ind(), "nodes")?;
+            if let Some(nodes) = attr {
+                assert!(*nodes == tetra.node_values());
+            }
+        }
+        for htri in simpl.get_all_halftriangles() {
+            let attr = simpl
+                .get_halftriangle_attributes()
+                .get_attribute_value::<Option<[usize; 3]>>(htri.ind(), "nodes")?;
+            if let Some(nodes) = attr {
+                assert!(*nodes == htri.node_values());
+            }
+        }
+        for ind_nod in 4..20 {
+            assert!(
+                *simpl
+                    .get_node_attributes()
+                    .get_attribute_value::<usize>(ind_nod, "value")?
+                    == ind_nod
+            );
+        }
+        assert!(simpl
+            .get_node_attributes()
+            .get_attribute_value::<f64>(4, "value")
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_io_test() -> Result<()> {
+        let mut simpl = Simplicial3::new(false);
+        let [ind_tetra0, _] = first_tetrahedron(&mut simpl, [0, 1, 2, 3])?;
+
+        let mut bw_inserter = BowyerWatsonInserter::new(&simpl);
+        bw_inserter.set_first_tetra(&simpl, ind_tetra0)?;
+        while bw_inserter.bw_tetra_to_check(&simpl).is_some() {
+            bw_inserter.bw_keep_tetra()?;
+        }
+        bw_inserter.bw_insert_node(&mut simpl, 4)?;
+        bw_inserter.clean(&mut simpl)?;
+
+        let coords = (0..5)
+            .map(|i| nalgebra::Vector3::new(i as f64, (i * i) as f64, 1.0))
+            .collect::<Vec<_>>();
+
+        let filename = std::env::temp_dir().join("skeletal_structures_simplicial3.bin");
+        let filename = filename.to_str().unwrap();
+
+        save_simplicial3_bin(filename, &simpl, Some(&coords))?;
+        let (simpl_loaded, coords_loaded) = load_simplicial3_bin(filename, true)?;
+        assert!(simpl_loaded.get_nb_tetrahedra() == simpl.get_nb_tetrahedra());
+        for tetra in simpl.get_all_tetrahedra() {
+            assert!(
+                simpl_loaded
+                    .get_tetrahedron_from_index(tetra.ind())?
+                    .node_values()
+                    == tetra.node_values()
+            );
+        }
+        assert!(coords_loaded.as_ref() == Some(&coords));
+        assert!(simpl_loaded.find_node(4).is_some());
+        assert!(simplicial3_is_valid(&simpl_loaded)?);
+
+        // truncated file
+        let bytes = std::fs::read(filename)?;
+        std::fs::write(filename, &bytes[..bytes.len() / 2])?;
+        assert!(load_simplicial3_bin(filename, false).is_err());
+
+        // overflowing number of tetrahedra
+        let mut bytes = bytes;
+        bytes[8..16].copy_from_slice(&(u64::MAX / 2).to_le_bytes());
+        std::fs::write(filename, &bytes)?;
+        assert!(load_simplicial3_bin(filename, false)
+            .is_err_and(|err| err.to_string() == "Wrong number of tetrahedra"));
+
+        // node values beyond coordinates, or huge without coordinates
+        for (coords, node) in [(Some(&coords), 5), (None, 1 << 40), (None, u64::MAX)] {
+            save_simplicial3_bin(filename, &simpl, coords)?;
+            let mut bytes = std::fs::read(filename)?;
+            bytes[16..24].copy_from_slice(&node.to_le_bytes());
+            std::fs::write(filename, &bytes)?;
+            assert!(load_simplicial3_bin(filename, true)
+                .is_err_and(|err| err.to_string() == "Node value out of bounds"));
+        }
+
+        std::fs::remove_file(filename)?;
+        Ok(())
+    }
+
+    #[test]
+    fn build_from_tetrahedron_list_test() -> Result<()> {
+        let mut rng = rand::thread_rng();
+
+        let mut simpl = Simplicial3::new(false);
+        first_tetrahedron(&mut simpl, [0, 1, 2, 3])?;
+        let mut bw_inserter = BowyerWatsonInserter::new(&simpl);
+        for ind_nod in 4..12 {
+            let ind_first = rng.gen_range(0..simpl.get_nb_tetrahedra());
+            bw_inserter.set_first_tetra(&simpl, ind_first)?;
+            while bw_inserter.bw_tetra_to_check(&simpl).is_some() {
+                bw_inserter.bw_keep_tetra()?;
+            }
+            bw_inserter.bw_insert_node(&mut simpl, ind_nod)?;
+        }
+        bw_inserter.clean(&mut simpl)?;
+
+        let tetrahedra = simpl
+            .get_all_tetrahedra()
+            .iter()
+            .map(|tetra| tetra.node_values())
+            .collect::<Vec<_>>();
+
+        for register_node_halfedges in [true, false] {
+            let simpl_built =
+                build_from_tetrahedron_list(tetrahedra.clone(), register_node_halfedges)?;
+            assert!(simpl_built.get_nb_tetrahedra() == tetrahedra.len());
+            assert!(simplicial3_is_valid(&simpl_built)?);
+        }
+
+        // open list
+        let err = build_from_tetrahedron_list(vec![[0, 1, 2, 3]], false)
+            .err()
+            .ok_or(anyhow::Error::msg("Open list should not be built"))?;
+        let err = err
+            .downcast_ref::<NonManifoldFacesError>()
+            .ok_or(anyhow::Error::msg("Wrong error type"))?;
+        assert!(err.faces.len() == 4);
+        assert!(err.faces[0].nodes == [0, 1, 2]);
+        assert!(err.faces[0].tetrahedra == vec![0]);
+
+        // inconsistent orientation
+        let err = build_from_tetrahedron_list(vec![[0, 1, 2, 3], [0, 1, 2, 3]], false)
+            .err()
+            .ok_or(anyhow::Error::msg("Inconsistent list should not be built"))?;
+        let err = err
+            .downcast_ref::<NonManifoldFacesError>()
+            .ok_or(anyhow::Error::msg("Wrong error type"))?;
+        assert!(err.faces.len() == 4);
+        assert!(err.faces.iter().all(|face| face.tetrahedra == vec![0, 1]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn vtk_io_test() -> Result<()> {
+        let tetrahedra = close_tetrahedron_list(&[[0, 1, 2, 3]], 4)?;
+        let simpl = build_from_tetrahedron_list(tetrahedra, false)?;
+        let coords = vec![
+            nalgebra::Vector3::new(0.0, 0.0, 0.0),
+            nalgebra::Vector3::new(1.0, 0.0, 0.0),
+            nalgebra::Vector3::new(0.0, 1.0, 0.0),
+            nalgebra::Vector3::new(0.0, 0.0, 1.0),
+        ];
+
+        let mut node_properties = PropertySet::new("vertex", 4);
+        node_properties.add_property(
+            "label".to_string(),
+            PropertyType::Scalar(ScalarType::Int),
+            Property::Int(0),
+        );
+        node_properties.add_property(
+            "list".to_string(),
+            PropertyType::List(ScalarType::UChar, ScalarType::Int),
+            Property::ListInt(Vec::new()),
+        );
+        node_properties.set_property_value(2, "label".to_string(), Property::Int(7))?;
+        let mut tetrahedron_properties = PropertySet::new("tetrahedron", 5);
+        tetrahedron_properties.add_property(
+            "quality".to_string(),
+            PropertyType::Scalar(ScalarType::Double),
+            Property::Double(0.5),
+        );
+
+        let filename = std::env::temp_dir().join("skeletal_structures_simplicial3.vtk");
+        let filename = filename.to_str().unwrap();
+        save_simplicial3_vtk(
+            filename,
+            &simpl,
+            &coords,
+            Some(&node_properties),
+            Some(&tetrahedron_properties),
+            Some(4),
+        )?;
+        let content = std::fs::read_to_string(filename)?;
+        std::fs::remove_file(filename)?;
+        assert!(content.contains("POINTS 4 double"));
+        assert!(content.contains("CELLS 1 5\n4 0 1 2 3\n"));
+        assert!(content.contains("CELL_TYPES 1\n10\n"));
+        assert!(content.contains("SCALARS label int 1\nLOOKUP_TABLE default\n0\n0\n7\n0\n"));
+        assert!(content.contains("CELL_DATA 1\nSCALARS quality double 1"));
+        assert!(!content.contains("list"));
+
+        // infinite node has no coordinates
+        assert!(save_simplicial3_vtk(filename, &simpl, &coords, None, None, None).is_err());
+
+        let filename = std::env::temp_dir().join("skeletal_structures_simplicial3.vtu");
+        let filename = filename.to_str().unwrap();
+        save_simplicial3_vtu(
+            filename,
+            &simpl,
+            &coords,
+            Some(&node_properties),
+            Some(&tetrahedron_properties),
+            Some(4),
+        )?;
+        let content = std::fs::read_to_string(filename)?;
+        std::fs::remove_file(filename)?;
+        assert!(content.contains("<Piece NumberOfPoints=\"4\" NumberOfCells=\"1\">"));
+        assert!(content.contains("<DataArray type=\"Int32\" Name=\"label\" format=\"ascii\">"));
+        assert!(content.contains("<DataArray type=\"Float64\" Name=\"quality\" format=\"ascii\">"));
+        assert!(content.trim_end().ends_with("</VTKFile>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn betti_numbers_test() -> Result<()> {
+        // single tetrahedron closed with infinite node: 3-sphere
+        let tetrahedra = close_tetrahedron_list(&[[0, 1, 2, 3]], 4)?;
+        let simpl = build_from_tetrahedron_list(tetrahedra, false)?;
+        assert!(betti_numbers(&simpl) == [1, 0, 0, 1]);
+        let finite = subcomplex_betti_numbers(&simpl, |tetra| !tetra.node_values().contains(&4));
+        assert!(finite == [1, 0, 0, 0]);
+
+        // join of a 6-cycle (nodes 0 to 5) and a 3-cycle (nodes 6 to 8): 3-sphere
+        let tetrahedra = (0..6)
+            .flat_map(|i| (0..3).map(move |j| [i, (i + 1) % 6, 6 + j, 6 + (j + 1) % 3]))
+            .collect::<Vec<_>>();
+        let simpl = build_from_tetrahedron_list(tetrahedra, false)?;
+        assert!(betti_numbers(&simpl) == [1, 0, 0, 1]);
+
+        // removing star of a node gives a ball
+        let without_0 = subcomplex_betti_numbers(&simpl, |tetra| !tetra.node_values().contains(&0));
+        assert!(without_0 == [1, 0, 0, 0]);
+
+        // removing stars of two distant nodes gives a thick sphere
+        let without_0_3 = subcomplex_betti_numbers(&simpl, |tetra| {
+            !tetra.node_values().contains(&0) && !tetra.node_values().contains(&3)
+        });
+        assert!(without_0_3 == [1, 0, 1, 0]);
+
+        // ring of 4 triangular prisms: solid torus
+        let tetrahedra = (0..4)
+            .flat_map(|k| {
+                let [a0, a1, a2] = [3 * k, 3 * k + 1, 3 * k + 2];
+                let [b0, b1, b2] = [(a0 + 3) % 12, (a1 + 3) % 12, (a2 + 3) % 12];
+                [[a0, a1, a2, b2], [a0, a1, b2, b1], [a0, b0, b1, b2]]
+            })
+            .collect::<Vec<_>>();
+        let tetrahedra = close_tetrahedron_list(&tetrahedra, 12)?;
+        let simpl = build_from_tetrahedron_list(tetrahedra, false)?;
+        let solid_torus =
+            subcomplex_betti_numbers(&simpl, |tetra| !tetra.node_values().contains(&12));
+        assert!(solid_torus == [1, 1, 0, 0]);
+        // coning boundary torus kills the loop and creates a cavity
+        assert!(betti_numbers(&simpl) == [1, 0, 1, 1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn region_boundary_test() -> Result<()> {
+        // bipyramid closed with infinite node 5
+        let node_coords = vec![
+            nalgebra::Vector3::new(0., 0., 0.),
+            nalgebra::Vector3::new(1., 0., 0.),
+            nalgebra::Vector3::new(0., 1., 0.),
+            nalgebra::Vector3::new(0., 0., 1.),
+            nalgebra::Vector3::new(0.3, 0.3, -1.),
+        ];
+        let tetrahedra = close_tetrahedron_list(&[[0, 1, 2, 3], [0, 2, 1, 4]], 5)?;
+        let simpl = build_from_tetrahedron_list(tetrahedra, false)?;
+        let finite = |tetra: &IterTetrahedron3| !tetra.node_values().contains(&5);
+
+        let simpl2 = extract_region_boundary(&simpl, finite, false)?;
+        assert!(simpl2.get_nb_triangles() == 6);
+        assert!(simplicial2_is_valid(&simpl2)?);
+        assert!(simplicial_2_topology::euler_characteristic(&simpl2) == 2);
+        assert!(simpl2.find_triangle(0, 1, 2).is_none());
+
+        // outward orientation gives positive enclosed volume
+        let mesh = extract_region_boundary_mesh(&simpl, &node_coords, finite)?;
+        assert!(mesh.get_nb_faces() == 6);
+        let mut volume = 0.;
+        for ind_face in 0..mesh.get_nb_faces() {
+            let face = mesh.get_face(ind_face)?;
+            let [v0, v1, v2] = [0, 1, 2].map(|i| node_coords[face[i]]);
+            volume += v0.dot(&v1.cross(&v2)) / 6.;
+        }
+        assert!((volume - 1. / 3.).abs() < 1e-10);
+
+        // single tetrahedron
+        let simpl2 = extract_region_boundary(
+            &simpl,
+            |tetra| finite(tetra) && tetra.node_values().contains(&3),
+            true,
+        )?;
+        assert!(simpl2.get_nb_triangles() == 4);
+        assert!(simplicial2_is_valid(&simpl2)?);
+
+        // infinite node has no coordinates
+        assert!(extract_region_boundary_mesh(&simpl, &node_coords, |tetra| {
+            tetra.node_values().contains(&5) && tetra.node_values().contains(&3)
+        })
+        .is_err());
+
+        // tetrahedra sharing a single node have a pinched boundary
+        let tetrahedra = close_tetrahedron_list(&[[0, 1, 2, 3], [0, 4, 5, 6]], 7)?;
+        let simpl = build_from_tetrahedron_list(tetrahedra, false)?;
+        assert!(
+            extract_region_boundary(&simpl, |tetra| !tetra.node_values().contains(&7), false)
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn violations_test() -> Result<()> {
+        // two tetrahedra glued on all faces, as built by first_tetrahedron
+        let tet_nodes = vec![0, 1, 2, 3, 1, 2, 3, 0];
+        let opposite = vec![7, 4, 5, 6, 1, 2, 3, 0];
+        let simpl = build_from_halftriangle_arrays(
+            tet_nodes.clone(),
+            opposite.clone(),
+            vec![1; 8],
+            4,
+            true,
+        )?;
+        assert!(simplicial3_violations(&simpl).is_empty());
+
+        // wrong shift between halftriangles 0 and 7
+        let mut shift = vec![1; 8];
+        shift[0] = 0;
+        shift[7] = 0;
+        let simpl =
+            build_from_halftriangle_arrays(tet_nodes.clone(), opposite.clone(), shift, 4, false)?;
+        assert!(!simplicial3_is_valid(&simpl)?);
+        let violations = simplicial3_violations(&simpl);
+        assert!(violations.contains(&Simplicial3Violation::WrongShift { halftriangle: 0 }));
+        assert!(violations.contains(&Simplicial3Violation::WrongShift { halftriangle: 7 }));
+        assert!(violations.iter().all(|violation| match violation {
+            Simplicial3Violation::WrongShift { halftriangle } => [0, 7].contains(halftriangle),
+            Simplicial3Violation::WrongOpposite(loc) => [0, 7].contains(&loc.halftriangle),
+            _ => false,
+        }));
+
+        // halftriangles 0 and 4 opposed, with different nodes
+        let mut opposite = opposite;
+        opposite.swap(0, 1);
+        opposite.swap(4, 7);
+        let simpl = build_from_halftriangle_arrays(tet_nodes, opposite, vec![1; 8], 4, false)?;
+        let violations = simplicial3_violations(&simpl);
+        for halftriangle in [0, 1, 4, 7] {
+            assert!(violations
+                .contains(&Simplicial3Violation::WrongOppositeHalftriangle { halftriangle }));
+        }
+        assert!(!violations
+            .contains(&Simplicial3Violation::WrongOppositeHalftriangle { halftriangle: 2 }));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,39 @@
+/// Disjoint sets of elements (union-find with path compression)
+pub(crate) struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    /// Creates one set per element
+    pub(crate) fn new(nb_elements: usize) -> UnionFind {
+        UnionFind {
+            parent: (0..nb_elements).collect(),
+        }
+    }
+
+    /// Gets representative element of the set containing element
+    pub(crate) fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut cur = element;
+        while self.parent[cur] != root {
+            let next = self.parent[cur];
+            self.parent[cur] = root;
+            cur = next;
+        }
+        root
+    }
+
+    /// Merges sets containing the two elements, returns false if already in same set
+    pub(crate) fn union(&mut self, element0: usize, element1: usize) -> bool {
+        let root0 = self.find(element0);
+        let root1 = self.find(element1);
+        if root0 == root1 {
+            return false;
+        }
+        self.parent[root1] = root0;
+        true
+    }
+}
@@ -0,0 +1,404 @@
+use anyhow::Result;
+use nalgebra::base::*;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+/// Gmsh element type of 3-node triangles
+pub(crate) const GMSH_TRIANGLE: usize = 2;
+/// Gmsh element type of 4-node tetrahedra
+pub(crate) const GMSH_TETRAHEDRON: usize = 4;
+/// Gmsh element type of 6-node (second order) triangles
+const GMSH_TRIANGLE_6: usize = 9;
+/// Gmsh element type of 10-node (second order) tetrahedra
+const GMSH_TETRAHEDRON_10: usize = 11;
+
+/// Element read from a Gmsh file
+pub(crate) struct GmshElement {
+    /// Element type (GMSH_TRIANGLE or GMSH_TETRAHEDRON, higher order elements are reduced)
+    pub(crate) element_type: usize,
+    /// Vertex indices of element corners
+    pub(crate) vertices: Vec<usize>,
+    /// First physical tag of the element entity, if any
+    pub(crate) physical_tag: Option<i32>,
+}
+
+/// Content of a Gmsh file (only triangles and tetrahedra are kept)
+pub(crate) struct GmshContent {
+    /// Node coordinates, in order of node tags
+    pub(crate) vertices: Vec<Vector3<f64>>,
+    /// Elements, in order of element tags
+    pub(crate) elements: Vec<GmshElement>,
+}
+
+/// Token reader over the lines of a section
+struct SectionTokens<'a> {
+    tokens: std::iter::Flatten<std::vec::IntoIter<std::str::SplitWhitespace<'a>>>,
+}
+
+impl<'a> SectionTokens<'a> {
+    fn new(lines: &[&'a str]) -> SectionTokens<'a> {
+        SectionTokens {
+            tokens: lines
+                .iter()
+                .map(|line| line.split_whitespace())
+                .collect::<Vec<_>>()
+                .into_iter()
+                .flatten(),
+        }
+    }
+
+    fn next<T: std::str::FromStr>(&mut self) -> Result<T> {
+        let token = self
+            .tokens
+            .next()
+            .ok_or(anyhow::Error::msg("Unexpected end of section"))?;
+        token
+            .parse::<T>()
+            .map_err(|_| anyhow::Error::msg("Could not parse value ".to_owned() + token))
+    }
+}
+
+/// Splits file content into sections ($Name ... $EndName)
+fn split_sections(content: &str) -> Result<HashMap<String, Vec<&str>>> {
+    let mut sections = HashMap::new();
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let name = line.strip_prefix('$').ok_or(anyhow::Error::msg(
+            "Expected section, found ".to_owned() + line,
+        ))?;
+        let end = "$End".to_owned() + name;
+        let mut section_lines = Vec::new();
+        loop {
+            let section_line = lines.next().ok_or(anyhow::Error::msg(
+                "Section ".to_owned() + name + " not ended",
+            ))?;
+            if section_line.trim() == end {
+                break;
+            }
+            section_lines.push(section_line);
+        }
+        sections.insert(name.to_string(), section_lines);
+    }
+    Ok(sections)
+}
+
+/// Reads physical tags of entities, by (dimension, entity tag)
+fn read_entities(lines: &[&str]) -> Result<HashMap<(usize, i32), i32>> {
+    let mut tokens = SectionTokens::new(lines);
+    let nb_entities = [
+        tokens.next::<usize>()?,
+        tokens.next::<usize>()?,
+        tokens.next::<usize>()?,
+        tokens.next::<usize>()?,
+    ];
+    let mut physical_tags = HashMap::new();
+    for (dim, &nb) in nb_entities.iter().enumerate() {
+        for _ in 0..nb {
+            let tag = tokens.next::<i32>()?;
+            // points have coordinates, other entities a bounding box
+            let nb_coords = if dim == 0 { 3 } else { 6 };
+            for _ in 0..nb_coords {
+                tokens.next::<f64>()?;
+            }
+            let nb_physical = tokens.next::<usize>()?;
+            for ind_physical in 0..nb_physical {
+                let physical = tokens.next::<i32>()?;
+                if ind_physical == 0 {
+                    physical_tags.insert((dim, tag), physical);
+                }
+            }
+            if dim != 0 {
+                let nb_bounding = tokens.next::<usize>()?;
+                for _ in 0..nb_bounding {
+                    tokens.next::<i32>()?;
+                }
+            }
+        }
+    }
+    Ok(physical_tags)
+}
+
+/// Gets number of nodes of a Gmsh element type, and type it is reduced to (None if skipped)
+///
+/// Returns None for types missing from the MSH 4.1 specification. Higher order triangles
+/// and tetrahedra are reduced to their corners, which come first in Gmsh node ordering.
+fn gmsh_element_nodes(element_type: usize) -> Option<(usize, Option<usize>)> {
+    let element_nodes = match element_type {
+        GMSH_TRIANGLE => (3, Some(GMSH_TRIANGLE)),
+        GMSH_TETRAHEDRON => (4, Some(GMSH_TETRAHEDRON)),
+        GMSH_TRIANGLE_6 => (6, Some(GMSH_TRIANGLE)),
+        GMSH_TETRAHEDRON_10 => (10, Some(GMSH_TETRAHEDRON)),
+        20 => (9, Some(GMSH_TRIANGLE)),  // 9-node incomplete triangle
+        21 => (10, Some(GMSH_TRIANGLE)), // 10-node triangle
+        22 => (12, Some(GMSH_TRIANGLE)), // 12-node incomplete triangle
+        23 => (15, Some(GMSH_TRIANGLE)), // 15-node triangle
+        24 => (15, Some(GMSH_TRIANGLE)), // 15-node incomplete triangle
+        25 => (21, Some(GMSH_TRIANGLE)), // 21-node triangle
+        29 => (20, Some(GMSH_TETRAHEDRON)), // 20-node tetrahedron
+        30 => (35, Some(GMSH_TETRAHEDRON)), // 35-node tetrahedron
+        31 => (56, Some(GMSH_TETRAHEDRON)), // 56-node tetrahedron
+        // other elements are skipped
+        15 => (1, None),   // point
+        1 => (2, None),    // line
+        8 => (3, None),    // 3-node line
+        26 => (4, None),   // 4-node line
+        27 => (5, None),   // 5-node line
+        28 => (6, None),   // 6-node line
+        3 => (4, None),    // quadrangle
+        16 => (8, None),   // 8-node quadrangle
+        10 => (9, None),   // 9-node quadrangle
+        5 => (8, None),    // hexahedron
+        17 => (20, None),  // 20-node hexahedron
+        12 => (27, None),  // 27-node hexahedron
+        92 => (64, None),  // 64-node hexahedron
+        93 => (125, None), // 125-node hexahedron
+        6 => (6, None),    // prism
+        18 => (15, None),  // 15-node prism
+        13 => (18, None),  // 18-node prism
+        7 => (5, None),    // pyramid
+        19 => (13, None),  // 13-node pyramid
+        14 => (14, None),  // 14-node pyramid
+        _ => return None,
+    };
+    Some(element_nodes)
+}
+
+/// Reads a Gmsh MSH 4.1 ASCII file
+///
+/// Only triangles and tetrahedra are kept, higher order ones are reduced to their corners.
+/// Other element types of the MSH specification are skipped, unknown types are an error.
+pub(crate) fn read_gmsh(filename: &str) -> Result<GmshContent> {
+    let content = std::fs::read_to_string(filename)?;
+    let sections = split_sections(&content)?;
+
+    // check format
+    let format = sections
+        .get("MeshFormat")
+        .ok_or(anyhow::Error::msg("No MeshFormat section"))?;
+    let mut tokens = SectionTokens::new(format);
+    let version = tokens.next::<String>()?;
+    let file_type = tokens.next::<usize>()?;
+    if !version.starts_with("4.1") {
+        return Err(anyhow::Error::msg(
+            "Unsupported Gmsh version ".to_owned() + &version + " (4.1 expected)",
+        ));
+    }
+    if file_type != 0 {
+        return Err(anyhow::Error::msg("Only ASCII Gmsh files are handled"));
+    }
+
+    let physical_tags = if let Some(lines) = sections.get("Entities") {
+        read_entities(lines)?
+    } else {
+        HashMap::new()
+    };
+
+    // read nodes
+    let lines = sections
+        .get("Nodes")
+        .ok_or(anyhow::Error::msg("No Nodes section"))?;
+    let mut tokens = SectionTokens::new(lines);
+    let nb_blocks = tokens.next::<usize>()?;
+    let _nb_nodes = tokens.next::<usize>()?;
+    let _min_tag = tokens.next::<usize>()?;
+    let _max_tag = tokens.next::<usize>()?;
+    let mut nodes = Vec::new();
+    for _ in 0..nb_blocks {
+        let _entity_dim = tokens.next::<usize>()?;
+        let _entity_tag = tokens.next::<i32>()?;
+        let parametric = tokens.next::<usize>()? != 0;
+        let nb_block_nodes = tokens.next::<usize>()?;
+        if parametric {
+            return Err(anyhow::Error::msg("Parametric nodes are not handled"));
+        }
+        let mut tags = Vec::new();
+        for _ in 0..nb_block_nodes {
+            tags.push(tokens.next::<usize>()?);
+        }
+        for tag in tags {
+            let vertex = Vector3::new(
+                tokens.next::<f64>()?,
+                tokens.next::<f64>()?,
+                tokens.next::<f64>()?,
+            );
+            nodes.push((tag, vertex));
+        }
+    }
+    nodes.sort_by_key(|&(tag, _)| tag);
+    let mut node_indices = HashMap::new();
+    for (ind, &(tag, _)) in nodes.iter().enumerate() {
+        if node_indices.insert(tag, ind).is_some() {
+            return Err(anyhow::Error::msg(format!(
+                "Node tag {} is duplicated",
+                tag
+            )));
+        }
+    }
+    let vertices = nodes.into_iter().map(|(_, vertex)| vertex).collect();
+
+    // read elements
+    let lines = sections
+        .get("Elements")
+        .ok_or(anyhow::Error::msg("No Elements section"))?;
+    let mut tokens = SectionTokens::new(lines);
+    let nb_blocks = tokens.next::<usize>()?;
+    let _nb_elements = tokens.next::<usize>()?;
+    let _min_tag = tokens.next::<usize>()?;
+    let _max_tag = tokens.next::<usize>()?;
+    let mut elements = Vec::new();
+    for _ in 0..nb_blocks {
+        let entity_dim = tokens.next::<usize>()?;
+        let entity_tag = tokens.next::<i32>()?;
+        let element_type = tokens.next::<usize>()?;
+        let nb_block_elements = tokens.next::<usize>()?;
+        let (nb_element_nodes, reduced_type) = gmsh_element_nodes(element_type).ok_or(
+            anyhow::Error::msg(format!("Unhandled element type {}", element_type)),
+        )?;
+        let physical_tag = physical_tags.get(&(entity_dim, entity_tag)).copied();
+        for _ in 0..nb_block_elements {
+            let element_tag = tokens.next::<usize>()?;
+            let mut element_nodes = Vec::new();
+            for _ in 0..nb_element_nodes {
+                let node_tag = tokens.next::<usize>()?;
+                let ind = node_indices
+                    .get(&node_tag)
+                    .ok_or(anyhow::Error::msg(format!(
+                        "Element {} refers to unknown node {}",
+                        element_tag, node_tag
+                    )))?;
+                element_nodes.push(*ind);
+            }
+            if let Some(reduced_type) = reduced_type {
+                let nb_corners = if reduced_type == GMSH_TRIANGLE { 3 } else { 4 };
+                element_nodes.truncate(nb_corners);
+                elements.push((
+                    element_tag,
+                    GmshElement {
+                        element_type: reduced_type,
+                        vertices: element_nodes,
+                        physical_tag,
+                    },
+                ));
+            }
+        }
+    }
+    elements.sort_by_key(|(tag, _)| *tag);
+    let elements = elements.into_iter().map(|(_, element)| element).collect();
+
+    Ok(GmshContent { vertices, elements })
+}
+
+/// Writes a Gmsh MSH 4.1 ASCII file (triangles and tetrahedra)
+///
+/// One entity is created by dimension and physical tag, element tags follow element order.
+pub(crate) fn write_gmsh(
+    filename: &str,
+    vertices: &[Vector3<f64>],
+    elements: &[GmshElement],
+) -> Result<()> {
+    let element_dim = |element: &GmshElement| -> Result<usize> {
+        match element.element_type {
+            GMSH_TRIANGLE => Ok(2),
+            GMSH_TETRAHEDRON => Ok(3),
+            _ => Err(anyhow::Error::msg(
+                "Only triangles and tetrahedra can be written",
+            )),
+        }
+    };
+
+    // entities, by dimension and physical tag
+    let element_dims = elements
+        .iter()
+        .map(element_dim)
+        .collect::<Result<Vec<usize>>>()?;
+    let entities: BTreeSet<(usize, Option<i32>)> = elements
+        .iter()
+        .zip(element_dims.iter())
+        .map(|(element, &dim)| (dim, element.physical_tag))
+        .collect();
+    let entities: Vec<(usize, Option<i32>)> = entities.into_iter().collect();
+
+    let mut file = BufWriter::new(File::create(filename)?);
+    writeln!(file, "$MeshFormat\n4.1 0 8\n$EndMeshFormat")?;
+
+    writeln!(file, "$Entities")?;
+    let mut nb_entities = [0; 4];
+    for &(dim, _) in entities.iter() {
+        nb_entities[dim] += 1;
+    }
+    writeln!(
+        file,
+        "{} {} {} {}",
+        nb_entities[0], nb_entities[1], nb_entities[2], nb_entities[3]
+    )?;
+    for (ind, &(_, physical)) in entities.iter().enumerate() {
+        write!(file, "{} 0 0 0 0 0 0", ind + 1)?;
+        if let Some(physical) = physical {
+            write!(file, " 1 {}", physical)?;
+        } else {
+            write!(file, " 0")?;
+        }
+        writeln!(file, " 0")?;
+    }
+    writeln!(file, "$EndEntities")?;
+
+    // all nodes are attached to the first entity
+    writeln!(file, "$Nodes")?;
+    let nb_vertices = vertices.len();
+    if let (Some(&(dim, _)), true) = (entities.first(), nb_vertices != 0) {
+        writeln!(file, "1 {} 1 {}", nb_vertices, nb_vertices)?;
+        writeln!(file, "{} 1 0 {}", dim, nb_vertices)?;
+        for ind in 0..nb_vertices {
+            writeln!(file, "{}", ind + 1)?;
+        }
+        for vertex in vertices.iter() {
+            writeln!(file, "{} {} {}", vertex[0], vertex[1], vertex[2])?;
+        }
+    } else {
+        writeln!(file, "0 0 0 0")?;
+    }
+    writeln!(file, "$EndNodes")?;
+
+    writeln!(file, "$Elements")?;
+    let nb_elements = elements.len();
+    writeln!(
+        file,
+        "{} {} {} {}",
+        entities.len(),
+        nb_elements,
+        (nb_elements != 0) as usize,
+        nb_elements
+    )?;
+    for (ind_entity, &(dim, physical)) in entities.iter().enumerate() {
+        let block: Vec<usize> = (0..nb_elements)
+            .filter(|&ind| element_dims[ind] == dim && elements[ind].physical_tag == physical)
+            .collect();
+        writeln!(
+            file,
+            "{} {} {} {}",
+            dim,
+            ind_entity + 1,
+            elements[block[0]].element_type,
+            block.len()
+        )?;
+        for &ind in block.iter() {
+            write!(file, "{}", ind + 1)?;
+            for &ind_vertex in elements[ind].vertices.iter() {
+                if ind_vertex >= nb_vertices {
+                    return Err(anyhow::Error::msg("Vertex index out of bounds"));
+                }
+                write!(file, " {}", ind_vertex + 1)?;
+            }
+            writeln!(file)?;
+        }
+    }
+    writeln!(file, "$EndElements")?;
+    file.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,363 @@
+use anyhow::Result;
+use nalgebra::base::*;
+
+use super::mesh3d::Mesh3D;
+use super::winding_number::WindingNumberTree;
+
+/// Maximal number of faces in a leaf of bounding volume hierarchy
+const LEAF_SIZE: usize = 4;
+
+/// Point on mesh surface, result of BVH queries
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurfacePoint {
+    /// Point position
+    pub position: Vector3<f64>,
+    /// Index of face containing the point
+    pub face: usize,
+    /// Barycentric coordinates of the point within its face
+    pub barycentric: Vector3<f64>,
+    /// Distance from query point (or ray origin)
+    pub distance: f64,
+}
+
+/// Node of bounding volume hierarchy
+struct BvhNode {
+    /// Bounding box corners
+    min: Vector3<f64>,
+    max: Vector3<f64>,
+    /// Range of node faces
+    first_face: usize,
+    last_face: usize,
+    /// Child nodes (None for leaves)
+    children: Option<[usize; 2]>,
+}
+
+impl BvhNode {
+    /// Squared distance from a point to node bounding box
+    fn squared_distance(&self, point: &Vector3<f64>) -> f64 {
+        (self.min - point)
+            .sup(&(point - self.max))
+            .sup(&Vector3::zeros())
+            .norm_squared()
+    }
+
+    /// Parameter of ray entry in node bounding box, if any before max_param
+    fn ray_entry(
+        &self,
+        origin: &Vector3<f64>,
+        inv_direction: &Vector3<f64>,
+        max_param: f64,
+    ) -> Option<f64> {
+        let mut param_min: f64 = 0.;
+        let mut param_max = max_param;
+        for axis in 0..3 {
+            let param0 = (self.min[axis] - origin[axis]) * inv_direction[axis];
+            let param1 = (self.max[axis] - origin[axis]) * inv_direction[axis];
+            // NaN (0 * inf) happens for rays within a box plane, and is ignored by min/max
+            param_min = param_min.max(param0.min(param1));
+            param_max = param_max.min(param0.max(param1));
+        }
+        if param_min <= param_max {
+            Some(param_min)
+        } else {
+            None
+        }
+    }
+}
+
+/// Ray intersection with a triangle (Moller-Trumbore), returns ray parameter and barycentric
+/// coordinates
+fn ray_triangle_intersection(
+    triangle: &[Vector3<f64>; 3],
+    origin: &Vector3<f64>,
+    direction: &Vector3<f64>,
+) -> Option<(f64, Vector3<f64>)> {
+    let edge1 = triangle[1] - triangle[0];
+    let edge2 = triangle[2] - triangle[0];
+    let pvec = direction.cross(&edge2);
+    let det = edge1.dot(&pvec);
+    if det == 0. {
+        return None;
+    }
+    let tvec = origin - triangle[0];
+    let u = tvec.dot(&pvec) / det;
+    if !(0. ..=1.).contains(&u) {
+        return None;
+    }
+    let qvec = tvec.cross(&edge1);
+    let v = direction.dot(&qvec) / det;
+    if v < 0. || u + v > 1. {
+        return None;
+    }
+    let param = edge2.dot(&qvec) / det;
+    if param <= 0. {
+        return None;
+    }
+    Some((param, Vector3::new(1. - u - v, u, v)))
+}
+
+/// Closest point of a triangle, returns barycentric coordinates
+fn closest_triangle_point(triangle: &[Vector3<f64>; 3], point: &Vector3<f64>) -> Vector3<f64> {
+    let [a, b, c] = triangle;
+    let ab = b - a;
+    let ac = c - a;
+    let ap = point - a;
+    let d1 = ab.dot(&ap);
+    let d2 = ac.dot(&ap);
+    if d1 <= 0. && d2 <= 0. {
+        return Vector3::new(1., 0., 0.);
+    }
+
+    let bp = point - b;
+    let d3 = ab.dot(&bp);
+    let d4 = ac.dot(&bp);
+    if d3 >= 0. && d4 <= d3 {
+        return Vector3::new(0., 1., 0.);
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0. && d1 >= 0. && d3 <= 0. {
+        let v = d1 / (d1 - d3);
+        return Vector3::new(1. - v, v, 0.);
+    }
+
+    let cp = point - c;
+    let d5 = ab.dot(&cp);
+    let d6 = ac.dot(&cp);
+    if d6 >= 0. && d5 <= d6 {
+        return Vector3::new(0., 0., 1.);
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0. && d2 >= 0. && d6 <= 0. {
+        let w = d2 / (d2 - d6);
+        return Vector3::new(1. - w, 0., w);
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0. && (d4 - d3) >= 0. && (d5 - d6) >= 0. {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return Vector3::new(0., 1. - w, w);
+    }
+
+    let denom = va + vb + vc;
+    if denom == 0. {
+        // degenerate triangle, closest vertex
+        let distances = Vector3::new(ap.norm(), bp.norm(), cp.norm());
+        let mut barycentric = Vector3::zeros();
+        barycentric[distances.imin()] = 1.;
+        return barycentric;
+    }
+    let v = vb / denom;
+    let w = vc / denom;
+    Vector3::new(1. - v - w, v, w)
+}
+
+/// Bounding volume hierarchy over triangle faces of a mesh
+///
+/// Supports ray casting, closest point and signed distance queries in logarithmic time.
+pub struct MeshBvh {
+    /// Face vertex coordinates
+    triangles: Vec<[Vector3<f64>; 3]>,
+    /// Face indices, ordered by node
+    faces: Vec<usize>,
+    nodes: Vec<BvhNode>,
+    /// Winding numbers for signed distance
+    winding_tree: WindingNumberTree,
+}
+
+impl MeshBvh {
+    /// Builds hierarchy from mesh faces (mesh must be triangular)
+    pub fn new(mesh: &Mesh3D) -> Result<MeshBvh> {
+        let mut triangles = Vec::with_capacity(mesh.get_nb_faces());
+        for ind_face in 0..mesh.get_nb_faces() {
+            if let [v0, v1, v2] = mesh.get_face(ind_face)?[..] {
+                triangles.push([
+                    mesh.get_vertex(v0)?,
+                    mesh.get_vertex(v1)?,
+                    mesh.get_vertex(v2)?,
+                ]);
+            } else {
+                return Err(anyhow::Error::msg("Mesh has non triangle faces"));
+            }
+        }
+
+        let mut bvh = MeshBvh {
+            faces: (0..triangles.len()).collect(),
+            triangles,
+            nodes: Vec::new(),
+            winding_tree: WindingNumberTree::new(mesh, 2.)?,
+        };
+        if !bvh.faces.is_empty() {
+            bvh.build_node(0, bvh.faces.len());
+        }
+        Ok(bvh)
+    }
+
+    /// Builds node from range of faces, returns node index
+    fn build_node(&mut self, first_face: usize, last_face: usize) -> usize {
+        let triangles = &self.triangles;
+        let faces = &mut self.faces[first_face..last_face];
+
+        let (min, max) = faces.iter().flat_map(|&face| triangles[face].iter()).fold(
+            (Vector3::repeat(f64::MAX), Vector3::repeat(f64::MIN)),
+            |(min, max), vertex| (min.inf(vertex), max.sup(vertex)),
+        );
+
+        let ind_node = self.nodes.len();
+        self.nodes.push(BvhNode {
+            min,
+            max,
+            first_face,
+            last_face,
+            children: None,
+        });
+
+        if faces.len() > LEAF_SIZE {
+            // split at median of face barycenters along largest box extent
+            let axis = (max - min).imax();
+            let middle = faces.len() / 2;
+            let barycenter = |face: &usize| triangles[*face].iter().map(|v| v[axis]).sum::<f64>();
+            faces
+                .select_nth_unstable_by(middle, |f0, f1| barycenter(f0).total_cmp(&barycenter(f1)));
+
+            let child0 = self.build_node(first_face, first_face + middle);
+            let child1 = self.build_node(first_face + middle, last_face);
+            self.nodes[ind_node].children = Some([child0, child1]);
+        }
+
+        ind_node
+    }
+
+    /// Builds surface point from face and barycentric coordinates
+    fn surface_point(
+        &self,
+        face: usize,
+        barycentric: Vector3<f64>,
+        from: &Vector3<f64>,
+    ) -> SurfacePoint {
+        let [v0, v1, v2] = self.triangles[face];
+        let position = barycentric[0] * v0 + barycentric[1] * v1 + barycentric[2] * v2;
+        SurfacePoint {
+            position,
+            face,
+            barycentric,
+            distance: (position - from).norm(),
+        }
+    }
+
+    /// Gets first intersection of a ray with mesh faces (origin excluded)
+    pub fn ray_intersection(
+        &self,
+        origin: &Vector3<f64>,
+        direction: &Vector3<f64>,
+    ) -> Option<SurfacePoint> {
+        let mut best: Option<(f64, usize, Vector3<f64>)> = None;
+        self.visit_ray(origin, direction, |face, param, barycentric| {
+            if best.is_none_or(|(best_param, _, _)| param < best_param) {
+                best = Some((param, face, barycentric));
+            }
+            best.map_or(f64::MAX, |(best_param, _, _)| best_param)
+        });
+        best.map(|(_, face, barycentric)| self.surface_point(face, barycentric, origin))
+    }
+
+    /// Gets all intersections of a ray with mesh faces (origin excluded), sorted by distance
+    pub fn ray_intersections(
+        &self,
+        origin: &Vector3<f64>,
+        direction: &Vector3<f64>,
+    ) -> Vec<SurfacePoint> {
+        let mut hits = Vec::new();
+        self.visit_ray(origin, direction, |face, param, barycentric| {
+            hits.push((param, face, barycentric));
+            f64::MAX
+        });
+        hits.sort_by(|hit0, hit1| hit0.0.total_cmp(&hit1.0));
+        hits.into_iter()
+            .map(|(_, face, barycentric)| self.surface_point(face, barycentric, origin))
+            .collect()
+    }
+
+    /// Visits faces intersected by a ray, visitor returns maximal ray parameter of interest
+    fn visit_ray<F>(&self, origin: &Vector3<f64>, direction: &Vector3<f64>, mut visitor: F)
+    where
+        F: FnMut(usize, f64, Vector3<f64>) -> f64,
+    {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let inv_direction = direction.map(|coord| 1. / coord);
+        let mut max_param = f64::MAX;
+        let mut to_visit = vec![0];
+        while let Some(ind_node) = to_visit.pop() {
+            let node = &self.nodes[ind_node];
+            if node.ray_entry(origin, &inv_direction, max_param).is_none() {
+                continue;
+            }
+            if let Some(children) = node.children {
+                to_visit.extend(children);
+                continue;
+            }
+            for &face in self.faces[node.first_face..node.last_face].iter() {
+                if let Some((param, barycentric)) =
+                    ray_triangle_intersection(&self.triangles[face], origin, direction)
+                {
+                    if param <= max_param {
+                        max_param = visitor(face, param, barycentric);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Gets closest point on mesh faces (None if mesh is empty)
+    pub fn closest_point(&self, point: &Vector3<f64>) -> Option<SurfacePoint> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        let mut best: Option<(f64, usize, Vector3<f64>)> = None;
+        let mut to_visit = vec![(0, self.nodes[0].squared_distance(point))];
+        while let Some((ind_node, node_distance)) = to_visit.pop() {
+            if best.is_some_and(|(best_distance, _, _)| node_distance >= best_distance) {
+                continue;
+            }
+            let node = &self.nodes[ind_node];
+            if let Some(children) = node.children {
+                // nearest child is visited first
+                let mut children =
+                    children.map(|child| (child, self.nodes[child].squared_distance(point)));
+                if children[0].1 < children[1].1 {
+                    children.swap(0, 1);
+                }
+                to_visit.extend(children);
+                continue;
+            }
+            for &face in self.faces[node.first_face..node.last_face].iter() {
+                let triangle = &self.triangles[face];
+                let barycentric = closest_triangle_point(triangle, point);
+                let position = barycentric[0] * triangle[0]
+                    + barycentric[1] * triangle[1]
+                    + barycentric[2] * triangle[2];
+                let distance = (position - point).norm_squared();
+                if best.is_none_or(|(best_distance, _, _)| distance < best_distance) {
+                    best = Some((distance, face, barycentric));
+                }
+            }
+        }
+        best.map(|(_, face, barycentric)| self.surface_point(face, barycentric, point))
+    }
+
+    /// Gets signed distance to mesh surface (negative inside, None if mesh is empty)
+    ///
+    /// Inside is defined by generalized winding number (faces oriented counterclockwise from
+    /// outside), so that the sign is robust to small holes.
+    pub fn signed_distance(&self, point: &Vector3<f64>) -> Option<f64> {
+        let distance = self.closest_point(point)?.distance;
+        if self.winding_tree.is_inside(point) {
+            Some(-distance)
+        } else {
+            Some(distance)
+        }
+    }
+}
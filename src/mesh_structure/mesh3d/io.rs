@@ -1,215 +1,725 @@
 use anyhow::Result;
 use nalgebra::base::*;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
-use std::io::{self, BufRead};
+use std::io::{self, BufRead, BufWriter, Write};
 
 use ply_rs::parser::Parser;
-use ply_rs::ply::{Addable, DefaultElement, Encoding, Ply, Property};
-use ply_rs::writer::Writer;
+use ply_rs::ply::{Addable, DefaultElement, Encoding, Ply, Property, PropertyType, ScalarType};
 
 use super::mesh3d::Mesh3D;
+use crate::graph_structure::simplicial2::simplicial_2_build::build_from_triangle_list_with_boundary;
+use crate::graph_structure::simplicial2::Simplicial2;
+use crate::mesh_structure::gmsh::{read_gmsh, write_gmsh, GmshElement, GMSH_TRIANGLE};
+use crate::mesh_structure::ply_writer::write_ply;
+use crate::mesh_structure::property_set::{property_to_f64, property_to_indices, PropertySet};
+
+/// Face property storing the obj group name of faces (utf-8 bytes)
+pub const OBJ_GROUP_PROPERTY: &str = "group";
+
+/// Face property storing the obj material name of faces (utf-8 bytes)
+pub const OBJ_MATERIAL_PROPERTY: &str = "material";
+
+/// Face property storing normals of face corners (3 values per corner, empty if none)
+pub const OBJ_CORNER_NORMAL_PROPERTY: &str = "corner_normals";
+
+/// Face property storing texture coordinates of face corners (2 values per corner, empty if
+/// none)
+pub const OBJ_CORNER_TEXTURE_PROPERTY: &str = "corner_texture_coordinates";
+
+/// Vertex properties storing normals
+const NORMAL_PROPERTIES: [&str; 3] = ["nx", "ny", "nz"];
+
+/// Vertex properties storing texture coordinates
+const TEXTURE_PROPERTIES: [&str; 2] = ["u", "v"];
+
+/// Converts an obj index (starting at 1, or negative and relative to the end) to an index
+fn obj_index(token: &str, nb_elements: usize) -> Result<usize> {
+    let index = token.parse::<i64>()?;
+    let resolved = if index > 0 {
+        index - 1
+    } else {
+        nb_elements as i64 + index
+    };
+    if index == 0 || resolved < 0 || resolved >= nb_elements as i64 {
+        return Err(anyhow::Error::msg(format!(
+            "Obj index {} out of bounds",
+            token
+        )));
+    }
+    Ok(resolved as usize)
+}
+
+/// Parses between min and max values of an obj statement
+fn obj_values<'a>(
+    words: impl Iterator<Item = &'a str>,
+    min_values: usize,
+    max_values: usize,
+) -> Result<Vec<f64>> {
+    let values = words
+        .take(max_values)
+        .map(|word| word.parse::<f64>())
+        .collect::<std::result::Result<Vec<f64>, _>>()?;
+    if values.len() < min_values {
+        return Err(anyhow::Error::msg("Expected value"));
+    }
+    Ok(values)
+}
+
+/// Gets a name stored as utf-8 bytes in a property
+fn obj_name(properties: &PropertySet, ind_element: usize, property_name: &str) -> Result<String> {
+    match properties.get_property_value(ind_element, property_name.to_string())? {
+        Property::ListUChar(bytes) => Ok(String::from_utf8(bytes)?),
+        _ => Err(anyhow::Error::msg(format!(
+            "Property {} is not a name",
+            property_name
+        ))),
+    }
+}
+
+/// Values written in obj file, and their index by value bits
+type ObjElements = (Vec<Vec<f64>>, HashMap<Vec<u64>, usize>);
+
+/// Gets values of face corners (normals or texture coordinates)
+///
+/// Values come from the face corner property if the face has some, from vertex properties
+/// otherwise. Returns None if there is no value for the face.
+fn obj_corner_values(
+    mesh: &Mesh3D,
+    ind_face: usize,
+    corner_property: &str,
+    vertex_property_names: &[&str],
+) -> Result<Option<Vec<Vec<f64>>>> {
+    let face = mesh.get_face(ind_face)?;
+    let dim = vertex_property_names.len();
+    let face_properties = mesh.get_face_properties();
+    if face_properties.has_property(corner_property) {
+        match face_properties.get_property_value(ind_face, corner_property.to_string())? {
+            Property::ListDouble(values) if values.len() == dim * face.len() => {
+                return Ok(Some(values.chunks(dim).map(<[f64]>::to_vec).collect()));
+            }
+            Property::ListDouble(values) if values.is_empty() => (),
+            _ => {
+                return Err(anyhow::Error::msg(format!(
+                    "Wrong {} property of face {}",
+                    corner_property, ind_face
+                )))
+            }
+        }
+    }
+
+    let vertex_properties = mesh.get_vertex_properties();
+    if !vertex_property_names
+        .iter()
+        .all(|name| vertex_properties.has_property(name))
+    {
+        return Ok(None);
+    }
+    face.iter()
+        .map(|&ind_vertex| {
+            vertex_property_names
+                .iter()
+                .map(|name| vertex_properties.get_property_value_f64(ind_vertex, name))
+                .collect::<Result<Vec<f64>>>()
+        })
+        .collect::<Result<Vec<Vec<f64>>>>()
+        .map(Some)
+}
 
 /// Loads obj file as mesh
+///
+/// Faces may be polygons, and indices may be negative (relative to the last element read).
+/// Each obj vertex gives one mesh vertex, so that corners with different normals or
+/// texture coordinates (sharp edges, seams) still share their vertex: normals and texture
+/// coordinates are stored per face corner, in [`OBJ_CORNER_NORMAL_PROPERTY`] and
+/// [`OBJ_CORNER_TEXTURE_PROPERTY`] face properties. Group and material names are stored in
+/// [`OBJ_GROUP_PROPERTY`] and [`OBJ_MATERIAL_PROPERTY`] face properties. Other statements
+/// are ignored.
 pub fn load_mesh_obj(filename: &str) -> Result<Mesh3D> {
     let mut vertices = Vec::new();
+    let mut normals: Vec<Vector3<f64>> = Vec::new();
+    let mut texture_coordinates: Vec<Vector2<f64>> = Vec::new();
     let mut faces = Vec::new();
+    let mut face_normals = Vec::new();
+    let mut face_textures = Vec::new();
+    let mut face_groups = Vec::new();
+    let mut face_materials = Vec::new();
+    let mut group: Option<String> = None;
+    let mut material: Option<String> = None;
 
     let file = File::open(filename)?;
-    let lines = io::BufReader::new(file).lines();
-    for line_ in lines {
-        if let Ok(line) = line_ {
-            if line.len() > 2 {
-                if &line[..2] == "v " {
-                    let mut line_split = line.split_whitespace();
-                    let mut vert: Vector3<f64> = Vector3::new(0.0, 0.0, 0.0);
-                    line_split.next();
-                    for i in 0..3 {
-                        let cur = line_split
-                            .next()
-                            .ok_or(anyhow::Error::msg("Expected value"))?;
-                        vert[i] = cur.parse::<f64>()?;
+    let mut statement = String::new();
+    for line in io::BufReader::new(file).lines() {
+        let line = line?;
+        // trailing backslash continues statement on next line
+        if let Some(start) = line.strip_suffix('\\') {
+            statement.push_str(start);
+            statement.push(' ');
+            continue;
+        }
+        statement.push_str(&line);
+        let content = statement.split('#').next().unwrap_or("");
+        let mut words = content.split_whitespace();
+        match words.next() {
+            Some("v") => {
+                let values = obj_values(words, 3, 3)?;
+                vertices.push(Vector3::new(values[0], values[1], values[2]));
+            }
+            Some("vn") => {
+                let values = obj_values(words, 3, 3)?;
+                normals.push(Vector3::new(values[0], values[1], values[2]));
+            }
+            Some("vt") => {
+                let values = obj_values(words, 1, 2)?;
+                texture_coordinates.push(Vector2::new(values[0], *values.get(1).unwrap_or(&0.)));
+            }
+            Some("f") => {
+                let mut face = Vec::new();
+                let mut corner_normals = Vec::new();
+                let mut corner_textures = Vec::new();
+                for word in words {
+                    let mut tokens = word.split('/');
+                    face.push(obj_index(tokens.next().unwrap_or(""), vertices.len())?);
+                    if let Some(token) = tokens.next().filter(|token| !token.is_empty()) {
+                        let texture =
+                            texture_coordinates[obj_index(token, texture_coordinates.len())?];
+                        corner_textures.extend(texture.iter());
                     }
-
-                    vertices.push(vert);
-                }
-                if &line[..2] == "f " {
-                    let mut line_split = line.split_whitespace();
-                    let mut triangle: [usize; 3] = [0, 0, 0];
-                    line_split.next();
-                    for i in 0..3 {
-                        let cur = line_split
-                            .next()
-                            .ok_or(anyhow::Error::msg("Expected value"))?;
-                        let mut cur_split = cur.split('/');
-                        let ind = cur_split
-                            .next()
-                            .ok_or(anyhow::Error::msg("Expected value"))?;
-                        triangle[i] = ind.parse::<usize>()? - 1;
+                    if let Some(token) = tokens.next().filter(|token| !token.is_empty()) {
+                        corner_normals.extend(normals[obj_index(token, normals.len())?].iter());
                     }
-
-                    faces.push(vec![triangle[0], triangle[1], triangle[2]]);
                 }
+                if face.len() < 3 {
+                    return Err(anyhow::Error::msg("Face with less than 3 vertices"));
+                }
+                if ![0, 3 * face.len()].contains(&corner_normals.len())
+                    || ![0, 2 * face.len()].contains(&corner_textures.len())
+                {
+                    return Err(anyhow::Error::msg(
+                        "Normals or texture coordinates missing on some face corners",
+                    ));
+                }
+                faces.push(face);
+                face_normals.push(corner_normals);
+                face_textures.push(corner_textures);
+                face_groups.push(group.clone());
+                face_materials.push(material.clone());
             }
+            Some("g") => group = Some(words.collect::<Vec<&str>>().join(" ")),
+            Some("usemtl") => material = Some(words.collect::<Vec<&str>>().join(" ")),
+            _ => (),
         }
+        statement.clear();
     }
 
-    Ok(Mesh3D::create(vertices, faces))
+    let mut mesh = Mesh3D::create(vertices, faces);
+    for (name, corner_values) in [
+        (OBJ_CORNER_NORMAL_PROPERTY, face_normals),
+        (OBJ_CORNER_TEXTURE_PROPERTY, face_textures),
+    ] {
+        if corner_values.iter().all(Vec::is_empty) {
+            continue;
+        }
+        mesh.add_face_property(
+            name.to_string(),
+            PropertyType::List(ScalarType::UChar, ScalarType::Double),
+            Property::ListDouble(Vec::new()),
+        );
+        for (ind_face, values) in corner_values.into_iter().enumerate() {
+            mesh.set_face_property_value(ind_face, name.to_string(), Property::ListDouble(values))?;
+        }
+    }
+    for (name, face_names) in [
+        (OBJ_GROUP_PROPERTY, face_groups),
+        (OBJ_MATERIAL_PROPERTY, face_materials),
+    ] {
+        if face_names.iter().all(Option::is_none) {
+            continue;
+        }
+        mesh.add_face_property(
+            name.to_string(),
+            PropertyType::List(ScalarType::UChar, ScalarType::UChar),
+            Property::ListUChar(Vec::new()),
+        );
+        for (ind_face, face_name) in face_names.into_iter().enumerate() {
+            if let Some(face_name) = face_name {
+                mesh.set_face_property_value(
+                    ind_face,
+                    name.to_string(),
+                    Property::ListUChar(face_name.into_bytes()),
+                )?;
+            }
+        }
+    }
+
+    Ok(mesh)
 }
 
-/// Loads off file as mesh
-pub fn load_mesh_off(filename: &str) -> Result<Mesh3D> {
-    let mut vertices = Vec::new();
-    let mut faces = Vec::new();
+/// Saves mesh as obj file
+///
+/// Faces may be polygons. Normals and texture coordinates of face corners are written from
+/// [`OBJ_CORNER_NORMAL_PROPERTY`] and [`OBJ_CORNER_TEXTURE_PROPERTY`] face properties, or
+/// from "nx", "ny", "nz" and "u", "v" vertex properties for faces without corner values.
+/// Group and material names are written if the mesh has [`OBJ_GROUP_PROPERTY`] and
+/// [`OBJ_MATERIAL_PROPERTY`] face properties.
+pub fn save_mesh_obj(filename: &str, mesh: &Mesh3D) -> Result<()> {
+    let face_properties = mesh.get_face_properties();
 
-    let file = File::open(filename)?;
-    let lines = io::BufReader::new(file).lines();
-    let mut opt_nb_vert = None;
-    let mut opt_nb_triangle = None;
-    let mut cur_vert = 0;
-    let mut cur_triangle = 0;
-    for line_ in lines {
-        if let Ok(line) = line_ {
-            if opt_nb_vert.is_none() {
-                if line == "OFF" {
-                    continue;
-                }
-                let mut line_split = line.split_whitespace();
-                let nb_vert = line_split
-                    .next()
-                    .ok_or(anyhow::Error::msg("Expected value1"))?
-                    .parse::<usize>()?;
-                opt_nb_vert = Some(nb_vert);
-                let nb_triangle = line_split
-                    .next()
-                    .ok_or(anyhow::Error::msg("Expected value2"))?
-                    .parse::<usize>()?;
-                opt_nb_triangle = Some(nb_triangle);
-            } else {
-                let nb_vert = opt_nb_vert.unwrap();
-                let nb_triangle = opt_nb_triangle.unwrap();
-                if cur_vert < nb_vert {
-                    let mut line_split = line.split_whitespace();
-                    let mut vert: Vector3<f64> = Vector3::new(0.0, 0.0, 0.0);
-                    for i in 0..3 {
-                        let ind = line_split
-                            .next()
-                            .ok_or(anyhow::Error::msg("Expected value3"))?
-                            .parse::<f64>()?;
-                        vert[i] = ind;
-                    }
+    // texture coordinates and normals, shared by corners with identical values
+    let mut corner_elements: [ObjElements; 2] = Default::default();
+    let mut face_corners = Vec::with_capacity(mesh.get_nb_faces());
+    for ind_face in 0..mesh.get_nb_faces() {
+        let mut corner_indices: [Option<Vec<usize>>; 2] = [None, None];
+        for (ind_element, (corner_property, vertex_property_names)) in [
+            (OBJ_CORNER_TEXTURE_PROPERTY, &TEXTURE_PROPERTIES[..]),
+            (OBJ_CORNER_NORMAL_PROPERTY, &NORMAL_PROPERTIES[..]),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let Some(values) =
+                obj_corner_values(mesh, ind_face, corner_property, vertex_property_names)?
+            else {
+                continue;
+            };
+            let (elements, element_indices) = &mut corner_elements[ind_element];
+            let indices = values
+                .into_iter()
+                .map(|value| {
+                    let key = value.iter().map(|coord| coord.to_bits()).collect();
+                    *element_indices.entry(key).or_insert_with(|| {
+                        elements.push(value);
+                        elements.len() - 1
+                    })
+                })
+                .collect();
+            corner_indices[ind_element] = Some(indices);
+        }
+        face_corners.push(corner_indices);
+    }
 
-                    vertices.push(vert);
-                    cur_vert = cur_vert + 1;
-                } else if cur_vert < nb_triangle {
-                    let mut line_split = line.split_whitespace();
-                    let mut triangle = Vec::new();
-                    let nbv = line_split
-                        .next()
-                        .ok_or(anyhow::Error::msg("Expected value4"))?
-                        .parse::<usize>()?;
-                    for _ in 0..nbv {
-                        let ind = line_split
-                            .next()
-                            .ok_or(anyhow::Error::msg("Expected value5"))?
-                            .parse::<usize>()?;
-                        triangle.push(ind);
-                    }
-                    if triangle.len() != 3 {
-                        return Err(anyhow::Error::msg("Triangle with more than 3 vertices"));
-                    }
-                    faces.push(vec![triangle[0], triangle[1], triangle[2]]);
-                    cur_triangle = cur_triangle + 1;
+    let mut file = BufWriter::new(File::create(filename)?);
+    for ind_vertex in 0..mesh.get_nb_vertices() {
+        let vertex = mesh.get_vertex(ind_vertex)?;
+        writeln!(file, "v {} {} {}", vertex[0], vertex[1], vertex[2])?;
+    }
+    for (statement, (elements, _)) in ["vt", "vn"].iter().zip(corner_elements.iter()) {
+        for element in elements.iter() {
+            let values: Vec<String> = element.iter().map(|value| value.to_string()).collect();
+            writeln!(file, "{} {}", statement, values.join(" "))?;
+        }
+    }
+
+    // group and material statements are only written when they change
+    let mut current_names = [None, None];
+    for (ind_face, [textures, normals]) in face_corners.iter().enumerate() {
+        for (current_name, (statement, property_name)) in current_names
+            .iter_mut()
+            .zip([("g", OBJ_GROUP_PROPERTY), ("usemtl", OBJ_MATERIAL_PROPERTY)])
+        {
+            if !face_properties.has_property(property_name) {
+                continue;
+            }
+            let name = obj_name(face_properties, ind_face, property_name)?;
+            if current_name.as_ref() != Some(&name) {
+                writeln!(file, "{} {}", statement, name)?;
+                *current_name = Some(name);
+            }
+        }
+
+        let corners: Vec<String> = mesh
+            .get_face(ind_face)?
+            .iter()
+            .enumerate()
+            .map(|(ind_corner, ind_vertex)| {
+                let vertex = ind_vertex + 1;
+                match (textures, normals) {
+                    (None, None) => format!("{}", vertex),
+                    (Some(textures), None) => format!("{}/{}", vertex, textures[ind_corner] + 1),
+                    (None, Some(normals)) => format!("{}//{}", vertex, normals[ind_corner] + 1),
+                    (Some(textures), Some(normals)) => format!(
+                        "{}/{}/{}",
+                        vertex,
+                        textures[ind_corner] + 1,
+                        normals[ind_corner] + 1
+                    ),
                 }
+            })
+            .collect();
+        writeln!(file, "f {}", corners.join(" "))?;
+    }
+    file.flush()?;
+    Ok(())
+}
+
+/// Vertex or face properties storing colors
+const COLOR_PROPERTIES: [&str; 4] = ["red", "green", "blue", "alpha"];
+
+/// Parses an off color with 3 or 4 components (integers in [0, 255] or floats in [0, 1])
+fn off_color(words: &[&str]) -> Result<[u8; 4]> {
+    if words.len() != 3 && words.len() != 4 {
+        return Err(anyhow::Error::msg(
+            "Off color has to have 3 or 4 components",
+        ));
+    }
+    let is_integer = words.iter().all(|word| word.parse::<i64>().is_ok());
+    let mut color = [255; 4];
+    for (component, word) in color.iter_mut().zip(words) {
+        let value = word.parse::<f64>()?;
+        let value = if is_integer { value } else { 255. * value };
+        *component = value.round().clamp(0., 255.) as u8;
+    }
+    Ok(color)
+}
+
+/// Parses an optional off color, a single colormap index giving no color
+fn off_optional_color(words: &[&str]) -> Result<Option<[u8; 4]>> {
+    match words {
+        [] => Ok(None),
+        [index] => {
+            index.parse::<usize>()?;
+            Ok(None)
+        }
+        _ => Ok(Some(off_color(words)?)),
+    }
+}
+
+/// Stores colors in "red", "green", "blue" and "alpha" properties of type u8
+///
+/// Elements without color are opaque black.
+fn set_color_properties(properties: &mut PropertySet, colors: &[Option<[u8; 4]>]) -> Result<()> {
+    for (ind_component, name) in COLOR_PROPERTIES.iter().enumerate() {
+        properties.add_property(
+            name.to_string(),
+            PropertyType::Scalar(ScalarType::UChar),
+            Property::UChar(if ind_component == 3 { 255 } else { 0 }),
+        );
+        for (ind_element, color) in colors.iter().enumerate() {
+            if let Some(color) = color {
+                properties.set_property_value(
+                    ind_element,
+                    name.to_string(),
+                    Property::UChar(color[ind_component]),
+                )?;
             }
         }
     }
+    Ok(())
+}
 
-    Ok(Mesh3D::create(vertices, faces))
+/// Checks if elements have "red", "green" and "blue" properties
+fn has_color_properties(properties: &PropertySet) -> bool {
+    COLOR_PROPERTIES[..3]
+        .iter()
+        .all(|name| properties.has_property(name))
 }
 
-/// Load a mesh from a ply file
-pub fn load_mesh_ply(file_path: &str) -> Result<Mesh3D> {
-    let mut f = std::fs::File::open(file_path).unwrap();
+/// Gets color of an element, opaque if there is no "alpha" property
+fn property_color(properties: &PropertySet, ind_element: usize) -> Result<[u8; 4]> {
+    let mut color = [255; 4];
+    for (component, name) in color.iter_mut().zip(COLOR_PROPERTIES) {
+        if properties.has_property(name) {
+            *component = properties
+                .get_property_value_f64(ind_element, name)?
+                .round()
+                .clamp(0., 255.) as u8;
+        }
+    }
+    Ok(color)
+}
 
-    let p = Parser::<DefaultElement>::new();
-    let ply = p.read_ply(&mut f)?;
+/// Loads off file as mesh
+///
+/// Header keyword may be [ST][C][N]OFF, comments start with #, and faces may be polygons.
+/// Vertex normals and texture coordinates are stored in "nx", "ny", "nz" and "u", "v"
+/// vertex properties. Vertex and face colors are stored in "red", "green", "blue" and
+/// "alpha" properties of type u8. Colormap indices are ignored, and elements without color
+/// are opaque black.
+pub fn load_mesh_off(filename: &str) -> Result<Mesh3D> {
+    let file = File::open(filename)?;
+    let mut contents = Vec::new();
+    for line in io::BufReader::new(file).lines() {
+        let line = line?;
+        let content = line.split('#').next().unwrap_or("");
+        if !content.trim().is_empty() {
+            contents.push(content.to_string());
+        }
+    }
+    let mut lines = contents
+        .iter()
+        .map(|content| content.split_whitespace().collect::<Vec<&str>>());
 
-    let mut mesh = Mesh3D::create(Vec::new(), Vec::new());
+    // header keyword, counts may follow on the same line
+    let mut words = lines.next().ok_or(anyhow::Error::msg("Empty off file"))?;
+    let (mut has_texture, mut has_colors, mut has_normals) = (false, false, false);
+    if let Some(keyword) = words.first().and_then(|word| word.strip_suffix("OFF")) {
+        let mut prefix = keyword;
+        if let Some(rest) = prefix.strip_prefix("ST") {
+            has_texture = true;
+            prefix = rest;
+        }
+        if let Some(rest) = prefix.strip_prefix('C') {
+            has_colors = true;
+            prefix = rest;
+        }
+        if let Some(rest) = prefix.strip_prefix('N') {
+            has_normals = true;
+            prefix = rest;
+        }
+        if !prefix.is_empty() {
+            return Err(anyhow::Error::msg(format!(
+                "Unsupported off variant {}OFF",
+                keyword
+            )));
+        }
+        words.remove(0);
+        if words.is_empty() {
+            words = lines
+                .next()
+                .ok_or(anyhow::Error::msg("Expected vertex and face numbers"))?;
+        }
+    }
+    if words.len() < 2 {
+        return Err(anyhow::Error::msg("Expected vertex and face numbers"));
+    }
+    let nb_vertices = words[0].parse::<usize>()?;
+    let nb_faces = words[1].parse::<usize>()?;
 
-    // load vertices
-    if !ply.payload.contains_key("vertex") {
-        return Err(anyhow::Error::msg("No vertex element in file"));
+    // vertex lines: coordinates, [normal], [color], [texture coordinates]
+    let nb_values = 3 + if has_normals { 3 } else { 0 } + if has_texture { 2 } else { 0 };
+    let mut vertices = Vec::with_capacity(nb_vertices);
+    let mut normals = Vec::new();
+    let mut vertex_colors = Vec::new();
+    let mut texture_coordinates = Vec::new();
+    for _ in 0..nb_vertices {
+        let words = lines
+            .next()
+            .ok_or(anyhow::Error::msg("Missing off vertex"))?;
+        if words.len() < nb_values + if has_colors { 1 } else { 0 } {
+            return Err(anyhow::Error::msg("Missing off vertex values"));
+        }
+        let value = |ind: usize| words[ind].parse::<f64>();
+        vertices.push(Vector3::new(value(0)?, value(1)?, value(2)?));
+        let mut color_start = 3;
+        if has_normals {
+            normals.push(Vector3::new(value(3)?, value(4)?, value(5)?));
+            color_start = 6;
+        }
+        let mut color_end = words.len();
+        if has_texture {
+            texture_coordinates.push(Vector2::new(value(color_end - 2)?, value(color_end - 1)?));
+            color_end -= 2;
+        }
+        if has_colors {
+            vertex_colors.push(off_optional_color(&words[color_start..color_end])?);
+        }
     }
-    for v in ply.payload["vertex"].iter() {
-        let x = if let Some(x_prop) = v.get("x") {
-            if let &Property::Float(x) = x_prop {
-                x
-            } else {
-                return Err(anyhow::Error::msg("No x property in vertex"));
+
+    // face lines: number of vertices, vertex indices, [color]
+    let mut faces = Vec::with_capacity(nb_faces);
+    let mut face_colors = Vec::with_capacity(nb_faces);
+    for _ in 0..nb_faces {
+        let words = lines.next().ok_or(anyhow::Error::msg("Missing off face"))?;
+        let nb_face_vertices = words[0].parse::<usize>()?;
+        if nb_face_vertices < 3 {
+            return Err(anyhow::Error::msg("Face with less than 3 vertices"));
+        }
+        if words.len() <= nb_face_vertices {
+            return Err(anyhow::Error::msg("Missing off face vertices"));
+        }
+        let face = words[1..=nb_face_vertices]
+            .iter()
+            .map(|word| {
+                let ind_vertex = word.parse::<usize>()?;
+                if ind_vertex >= nb_vertices {
+                    return Err(anyhow::Error::msg("Vertex index out of bounds"));
+                }
+                Ok(ind_vertex)
+            })
+            .collect::<Result<Vec<usize>>>()?;
+        face_colors.push(off_optional_color(&words[nb_face_vertices + 1..])?);
+        faces.push(face);
+    }
+
+    let mut mesh = Mesh3D::create(vertices, faces);
+    if has_normals {
+        for (ind_coord, name) in NORMAL_PROPERTIES.iter().enumerate() {
+            mesh.add_vertex_property_f64(name.to_string(), 0.);
+            for (ind_vertex, normal) in normals.iter().enumerate() {
+                mesh.set_vertex_property_f64(ind_vertex, name.to_string(), normal[ind_coord])?;
             }
-        } else {
-            return Err(anyhow::Error::msg("No x property in vertex"));
-        };
-        let y = if let Some(y_prop) = v.get("y") {
-            if let &Property::Float(y) = y_prop {
-                y
-            } else {
-                return Err(anyhow::Error::msg("No y property in vertex"));
+        }
+    }
+    if has_texture {
+        for (ind_coord, name) in TEXTURE_PROPERTIES.iter().enumerate() {
+            mesh.add_vertex_property_f64(name.to_string(), 0.);
+            for (ind_vertex, texture) in texture_coordinates.iter().enumerate() {
+                mesh.set_vertex_property_f64(ind_vertex, name.to_string(), texture[ind_coord])?;
             }
-        } else {
-            return Err(anyhow::Error::msg("No y property in vertex"));
-        };
-        let z = if let Some(z_prop) = v.get("z") {
-            if let &Property::Float(z) = z_prop {
-                z
-            } else {
-                return Err(anyhow::Error::msg("No z property in vertex"));
+        }
+    }
+    if has_colors {
+        set_color_properties(&mut mesh.vertex_properties, &vertex_colors)?;
+    }
+    if face_colors.iter().any(Option::is_some) {
+        set_color_properties(&mut mesh.face_properties, &face_colors)?;
+    }
+
+    Ok(mesh)
+}
+
+/// Saves mesh as off file
+///
+/// Faces may be polygons. Header keyword is chosen from vertex properties: normals and
+/// texture coordinates are written if the mesh has "nx", "ny", "nz" and "u", "v" vertex
+/// properties, and colors if it has "red", "green", "blue" (and optionally "alpha") vertex
+/// properties. Face colors are written from the same face properties.
+pub fn save_mesh_off(filename: &str, mesh: &Mesh3D) -> Result<()> {
+    let vertex_properties = mesh.get_vertex_properties();
+    let face_properties = mesh.get_face_properties();
+    let has_normals = NORMAL_PROPERTIES
+        .iter()
+        .all(|name| vertex_properties.has_property(name));
+    let has_texture = TEXTURE_PROPERTIES
+        .iter()
+        .all(|name| vertex_properties.has_property(name));
+    let has_vertex_colors = has_color_properties(vertex_properties);
+    let has_face_colors = has_color_properties(face_properties);
+
+    let mut edges = HashSet::new();
+    for face in mesh.faces.iter() {
+        for (ind, &v1) in face.iter().enumerate() {
+            let v2 = face[(ind + 1) % face.len()];
+            edges.insert((v1.min(v2), v1.max(v2)));
+        }
+    }
+
+    let mut file = BufWriter::new(File::create(filename)?);
+    writeln!(
+        file,
+        "{}{}{}OFF",
+        if has_texture { "ST" } else { "" },
+        if has_vertex_colors { "C" } else { "" },
+        if has_normals { "N" } else { "" }
+    )?;
+    writeln!(
+        file,
+        "{} {} {}",
+        mesh.get_nb_vertices(),
+        mesh.get_nb_faces(),
+        edges.len()
+    )?;
+    for ind_vertex in 0..mesh.get_nb_vertices() {
+        let vertex = mesh.get_vertex(ind_vertex)?;
+        let mut values: Vec<String> = vertex.iter().map(|value| value.to_string()).collect();
+        if has_normals {
+            for name in NORMAL_PROPERTIES {
+                values.push(
+                    vertex_properties
+                        .get_property_value_f64(ind_vertex, name)?
+                        .to_string(),
+                );
             }
-        } else {
-            return Err(anyhow::Error::msg("No z property in vertex"));
-        };
-        let ind_vertex = mesh.insert_vertex(Vector3::new(x as f64, y as f64, z as f64))?;
-
-        for (key, prop) in v.into_iter() {
-            match (key.as_ref(), prop) {
-                ("x", _) => (),
-                ("y", _) => (),
-                ("z", _) => (),
-                (k, p) => {
-                    mesh.set_vertex_property_value(ind_vertex, k.to_string(), p.clone())?;
-                    ()
-                }
+        }
+        if has_vertex_colors {
+            let color = property_color(vertex_properties, ind_vertex)?;
+            values.extend(color.iter().map(|component| component.to_string()));
+        }
+        if has_texture {
+            for name in TEXTURE_PROPERTIES {
+                values.push(
+                    vertex_properties
+                        .get_property_value_f64(ind_vertex, name)?
+                        .to_string(),
+                );
             }
         }
+        writeln!(file, "{}", values.join(" "))?;
     }
-
-    // load faces
-    if !ply.payload.contains_key("face") {
-        return Err(anyhow::Error::msg("No face element in file"));
+    for ind_face in 0..mesh.get_nb_faces() {
+        let face = mesh.get_face(ind_face)?;
+        let mut values = vec![face.len().to_string()];
+        values.extend(face.iter().map(|ind_vertex| ind_vertex.to_string()));
+        if has_face_colors {
+            let color = property_color(face_properties, ind_face)?;
+            values.extend(color.iter().map(|component| component.to_string()));
+        }
+        writeln!(file, "{}", values.join(" "))?;
     }
-    for f in ply.payload["face"].iter() {
-        let vertex_index = if let Some(vertex_index_prop) = f.get("vertex_index") {
-            if let Property::ListInt(vertex_index) = vertex_index_prop {
-                vertex_index
-            } else {
-                return Err(anyhow::Error::msg("No vertex_index property in face"));
+    file.flush()?;
+    Ok(())
+}
+
+/// Accepted names of the face property listing vertex indices
+const PLY_FACE_INDICES: [&str; 2] = ["vertex_indices", "vertex_index"];
+
+/// Load a mesh from a ply file (ascii or binary)
+///
+/// Coordinates may have any scalar type, and vertex indices of faces may be stored in
+/// "vertex_indices" or "vertex_index" lists of any integer type. Other properties are kept
+/// with their type. Face element is optional (point clouds).
+pub fn load_mesh_ply(file_path: &str) -> Result<Mesh3D> {
+    let mut f = std::fs::File::open(file_path)?;
+    let ply = Parser::<DefaultElement>::new().read_ply(&mut f)?;
+
+    let mut mesh = Mesh3D::new();
+
+    // load vertices
+    let vertex_header = ply
+        .header
+        .elements
+        .get("vertex")
+        .ok_or(anyhow::Error::msg("No vertex element in file"))?;
+    mesh.vertex_properties
+        .add_header_properties(vertex_header, &["x", "y", "z"]);
+    for v in ply.payload.get("vertex").into_iter().flatten() {
+        let coordinate = |name: &str| {
+            v.get(name)
+                .and_then(property_to_f64)
+                .ok_or(anyhow::Error::msg(format!(
+                    "No {} property in vertex",
+                    name
+                )))
+        };
+        let ind_vertex = mesh.insert_vertex(Vector3::new(
+            coordinate("x")?,
+            coordinate("y")?,
+            coordinate("z")?,
+        ))?;
+
+        for (key, prop) in v.iter() {
+            if !["x", "y", "z"].contains(&key.as_str()) {
+                mesh.set_vertex_property_value(ind_vertex, key.clone(), prop.clone())?;
             }
-        } else {
-            return Err(anyhow::Error::msg("No vertex_index property in face"));
         }
-        .iter()
-        .map(|&v| v as usize)
-        .collect();
+    }
 
-        let ind_face = mesh.insert_face(vertex_index)?;
+    // load faces
+    let Some(face_header) = ply.header.elements.get("face") else {
+        return Ok(mesh);
+    };
+    let indices_name = PLY_FACE_INDICES
+        .into_iter()
+        .find(|name| face_header.properties.contains_key(*name))
+        .ok_or(anyhow::Error::msg("No vertex_indices property in face"))?;
+    mesh.face_properties
+        .add_header_properties(face_header, &PLY_FACE_INDICES);
+    for f in ply.payload.get("face").into_iter().flatten() {
+        let vertex_indices = f
+            .get(indices_name)
+            .and_then(property_to_indices)
+            .ok_or(anyhow::Error::msg("Wrong vertex_indices property in face"))?;
+        if vertex_indices
+            .iter()
+            .any(|&ind_vertex| ind_vertex >= mesh.get_nb_vertices())
+        {
+            return Err(anyhow::Error::msg("Vertex index out of bounds"));
+        }
+        let ind_face = mesh.insert_face(vertex_indices)?;
 
-        for (key, prop) in f.into_iter() {
-            match (key.as_ref(), prop) {
-                ("vertex_index", _) => (),
-                (k, p) => {
-                    mesh.set_face_property_value(ind_face, k.to_string(), p.clone())?;
-                    ()
-                }
+        for (key, prop) in f.iter() {
+            if !PLY_FACE_INDICES.contains(&key.as_str()) {
+                mesh.set_face_property_value(ind_face, key.clone(), prop.clone())?;
             }
         }
     }
@@ -217,10 +727,20 @@ pub fn load_mesh_ply(file_path: &str) -> Result<Mesh3D> {
     Ok(mesh)
 }
 
-/// Save mesh as ply file
+/// Save mesh as ply file (ascii encoding)
 pub fn save_mesh_ply(filename: &str, mesh: &Mesh3D, header: Option<String>) -> Result<()> {
+    save_mesh_ply_with_encoding(filename, mesh, header, Encoding::Ascii)
+}
+
+/// Save mesh as ply file with given encoding (ascii, binary little endian or big endian)
+pub fn save_mesh_ply_with_encoding(
+    filename: &str,
+    mesh: &Mesh3D,
+    header: Option<String>,
+    encoding: Encoding,
+) -> Result<()> {
     let mut ply = Ply::<DefaultElement>::new();
-    ply.header.encoding = Encoding::Ascii;
+    ply.header.encoding = encoding;
     if let Some(h) = header {
         ply.header.comments.push(h);
     }
@@ -239,10 +759,332 @@ pub fn save_mesh_ply(filename: &str, mesh: &Mesh3D, header: Option<String>) -> R
     ply.payload
         .insert("face".to_string(), faces_payload_element);
 
-    ply.make_consistent().unwrap();
+    write_ply(filename, &mut ply)
+}
+
+/// Builds mesh from triangle corners, merging vertices with identical coordinates
+///
+/// Triangles with merged corners are degenerate, they are dropped (with a warning giving
+/// their number).
+fn mesh_from_triangle_soup(triangles: &[[Vector3<f64>; 3]]) -> Mesh3D {
+    let mut vertices = Vec::new();
+    let mut vertex_indices = HashMap::new();
+    let mut faces = Vec::with_capacity(triangles.len());
+    for triangle in triangles.iter() {
+        // adding 0 turns -0 into +0, so that both share the same key
+        let keys = triangle.map(|vertex| {
+            [
+                (vertex[0] + 0.).to_bits(),
+                (vertex[1] + 0.).to_bits(),
+                (vertex[2] + 0.).to_bits(),
+            ]
+        });
+        if keys[0] == keys[1] || keys[1] == keys[2] || keys[2] == keys[0] {
+            continue;
+        }
+        let face = keys
+            .iter()
+            .zip(triangle.iter())
+            .map(|(key, vertex)| {
+                *vertex_indices.entry(*key).or_insert_with(|| {
+                    vertices.push(*vertex);
+                    vertices.len() - 1
+                })
+            })
+            .collect();
+        faces.push(face);
+    }
+    if faces.len() != triangles.len() {
+        log::warn!(
+            "{} degenerate facets dropped",
+            triangles.len() - faces.len()
+        );
+    }
+    Mesh3D::create(vertices, faces)
+}
+
+/// Parses triangles of an ascii stl file
+fn parse_stl_ascii(content: &str) -> Result<Vec<[Vector3<f64>; 3]>> {
+    let mut words = content.split_whitespace();
+    if words.next() != Some("solid") {
+        return Err(anyhow::Error::msg("Ascii stl file has to start with solid"));
+    }
+
+    let mut triangles = Vec::new();
+    let mut corners = Vec::with_capacity(3);
+    while let Some(word) = words.next() {
+        match word {
+            "vertex" => {
+                let mut vertex = Vector3::zeros();
+                for i in 0..3 {
+                    vertex[i] = words
+                        .next()
+                        .ok_or(anyhow::Error::msg("Expected vertex coordinate"))?
+                        .parse::<f64>()?;
+                }
+                corners.push(vertex);
+            }
+            "endloop" => {
+                if corners.len() != 3 {
+                    return Err(anyhow::Error::msg("Stl facet has to have 3 vertices"));
+                }
+                triangles.push([corners[0], corners[1], corners[2]]);
+                corners.clear();
+            }
+            _ => (),
+        }
+    }
+    Ok(triangles)
+}
+
+/// Parses triangles of a binary stl file
+fn parse_stl_binary(content: &[u8]) -> Vec<[Vector3<f64>; 3]> {
+    content[STL_HEADER_SIZE..]
+        .chunks_exact(STL_FACET_SIZE)
+        .map(|facet| {
+            let read_f32 = |offset: usize| {
+                f32::from_le_bytes([
+                    facet[offset],
+                    facet[offset + 1],
+                    facet[offset + 2],
+                    facet[offset + 3],
+                ]) as f64
+            };
+            let read_vertex = |offset: usize| {
+                Vector3::new(read_f32(offset), read_f32(offset + 4), read_f32(offset + 8))
+            };
+            // facet starts with its normal, which is skipped
+            [read_vertex(12), read_vertex(24), read_vertex(36)]
+        })
+        .collect()
+}
+
+/// Size of binary stl header (80 bytes of comment, then number of facets)
+const STL_HEADER_SIZE: usize = 84;
+
+/// Size of a binary stl facet (normal, 3 vertices and attribute byte count)
+const STL_FACET_SIZE: usize = 50;
+
+/// Loads stl file (ascii or binary) as mesh
+///
+/// Vertices with identical coordinates are merged, and facets with merged corners are
+/// dropped, so that the mesh can be converted to a simplicial. Facet normals are ignored,
+/// orientation is given by vertex order.
+pub fn load_mesh_stl(filename: &str) -> Result<Mesh3D> {
+    let content = std::fs::read(filename)?;
+
+    // binary files may also start with "solid", they are recognized by their size
+    let is_binary = content.len() >= STL_HEADER_SIZE && {
+        let nb_facets = u32::from_le_bytes([content[80], content[81], content[82], content[83]]);
+        content.len() == STL_HEADER_SIZE + STL_FACET_SIZE * nb_facets as usize
+    };
+    let triangles = if is_binary {
+        parse_stl_binary(&content)
+    } else {
+        parse_stl_ascii(std::str::from_utf8(&content)?)?
+    };
+
+    Ok(mesh_from_triangle_soup(&triangles))
+}
 
-    let mut file = File::create(filename)?;
-    let w = Writer::new();
-    w.write_ply(&mut file, &mut ply).unwrap();
+/// Saves triangle mesh as stl file (ascii or binary)
+///
+/// Facet normals are computed from vertex positions.
+pub fn save_mesh_stl(filename: &str, mesh: &Mesh3D, binary: bool) -> Result<()> {
+    let mut facets = Vec::with_capacity(mesh.get_nb_faces());
+    for ind_face in 0..mesh.get_nb_faces() {
+        let face = mesh.get_face(ind_face)?;
+        if face.len() != 3 {
+            return Err(anyhow::Error::msg("Only triangle faces can be saved"));
+        }
+        facets.push([
+            mesh.face_normal(ind_face)?,
+            mesh.get_vertex(face[0])?,
+            mesh.get_vertex(face[1])?,
+            mesh.get_vertex(face[2])?,
+        ]);
+    }
+
+    let mut file = BufWriter::new(File::create(filename)?);
+    if binary {
+        let mut header = [0u8; 80];
+        let title = b"binary stl";
+        header[..title.len()].copy_from_slice(title);
+        file.write_all(&header)?;
+        file.write_all(&u32::try_from(facets.len())?.to_le_bytes())?;
+        for facet in facets.iter() {
+            for vector in facet.iter() {
+                for i in 0..3 {
+                    file.write_all(&(vector[i] as f32).to_le_bytes())?;
+                }
+            }
+            file.write_all(&0u16.to_le_bytes())?;
+        }
+    } else {
+        writeln!(file, "solid mesh")?;
+        for [normal, v0, v1, v2] in facets.iter() {
+            writeln!(
+                file,
+                "facet normal {} {} {}",
+                normal[0], normal[1], normal[2]
+            )?;
+            writeln!(file, "outer loop")?;
+            for vertex in [v0, v1, v2] {
+                writeln!(file, "vertex {} {} {}", vertex[0], vertex[1], vertex[2])?;
+            }
+            writeln!(file, "endloop")?;
+            writeln!(file, "endfacet")?;
+        }
+        writeln!(file, "endsolid mesh")?;
+    }
+    file.flush()?;
     Ok(())
 }
+
+/// Loads triangles of a Gmsh file (MSH 4.1 ASCII) as mesh
+///
+/// Physical tags are stored in the physical_tag face property (0 if no physical group).
+pub fn load_mesh_gmsh(filename: &str) -> Result<Mesh3D> {
+    let content = read_gmsh(filename)?;
+    let triangles: Vec<&GmshElement> = content
+        .elements
+        .iter()
+        .filter(|element| element.element_type == GMSH_TRIANGLE)
+        .collect();
+
+    let faces = triangles
+        .iter()
+        .map(|element| element.vertices.clone())
+        .collect();
+    let mut mesh = Mesh3D::create(content.vertices, faces);
+
+    if triangles
+        .iter()
+        .any(|element| element.physical_tag.is_some())
+    {
+        mesh.add_face_property(
+            "physical_tag".to_string(),
+            PropertyType::Scalar(ScalarType::Int),
+            Property::Int(0),
+        );
+        for (ind_face, element) in triangles.iter().enumerate() {
+            if let Some(tag) = element.physical_tag {
+                mesh.set_face_property_value(
+                    ind_face,
+                    "physical_tag".to_string(),
+                    Property::Int(tag),
+                )?;
+            }
+        }
+    }
+
+    Ok(mesh)
+}
+
+/// Saves triangle mesh as Gmsh file (MSH 4.1 ASCII)
+///
+/// The physical_tag face property, if any, gives physical groups (0 if no physical group).
+pub fn save_mesh_gmsh(filename: &str, mesh: &Mesh3D) -> Result<()> {
+    let vertices: Vec<Vector3<f64>> = (0..mesh.get_nb_vertices())
+        .map(|ind_vertex| mesh.get_vertex(ind_vertex))
+        .collect::<Result<Vec<Vector3<f64>>>>()?;
+    let face_properties = mesh.get_face_properties();
+    let has_physical = face_properties.has_property("physical_tag");
+
+    let mut elements = Vec::new();
+    for ind_face in 0..mesh.get_nb_faces() {
+        let face = mesh.get_face(ind_face)?;
+        if face.len() != 3 {
+            return Err(anyhow::Error::msg("Only triangle faces can be saved"));
+        }
+        let physical_tag = if has_physical {
+            let tag = face_properties.get_property_value_f64(ind_face, "physical_tag")? as i32;
+            if tag != 0 {
+                Some(tag)
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+        elements.push(GmshElement {
+            element_type: GMSH_TRIANGLE,
+            vertices: face.clone(),
+            physical_tag,
+        });
+    }
+
+    write_gmsh(filename, &vertices, &elements)
+}
+
+/// Loads triangles of a Gmsh file (MSH 4.1 ASCII) as 2D simplicial, with node coordinates
+///
+/// Node value is vertex index, surface may have boundaries. Physical tags are stored in the
+/// physical_tag triangle attribute (i32, 0 if no physical group).
+pub fn load_simplicial2_gmsh(
+    filename: &str,
+    register_node_halfedges: bool,
+) -> Result<(Simplicial2, Vec<Vector3<f64>>)> {
+    let content = read_gmsh(filename)?;
+    let triangles: Vec<&GmshElement> = content
+        .elements
+        .iter()
+        .filter(|element| element.element_type == GMSH_TRIANGLE)
+        .collect();
+
+    let triangle_list = triangles
+        .iter()
+        .map(|element| {
+            [
+                element.vertices[0],
+                element.vertices[1],
+                element.vertices[2],
+            ]
+        })
+        .collect();
+    let mut simpl = build_from_triangle_list_with_boundary(triangle_list, register_node_halfedges)?;
+
+    if triangles
+        .iter()
+        .any(|element| element.physical_tag.is_some())
+    {
+        let attributes = simpl.get_triangle_attributes_mut();
+        attributes.add_attribute("physical_tag".to_string(), 0i32);
+        for (ind_tri, element) in triangles.iter().enumerate() {
+            if let Some(tag) = element.physical_tag {
+                attributes.set_attribute_value(ind_tri, "physical_tag", tag)?;
+            }
+        }
+    }
+
+    Ok((simpl, content.vertices))
+}
+
+/// Saves 2D simplicial as Gmsh file (MSH 4.1 ASCII), node values are indices of node coordinates
+///
+/// The physical_tag triangle attribute (i32), if any, gives physical groups
+/// (0 if no physical group).
+pub fn save_simplicial2_gmsh(
+    filename: &str,
+    simpl: &Simplicial2,
+    node_coords: &[Vector3<f64>],
+) -> Result<()> {
+    let physical_tags = simpl
+        .get_triangle_attributes()
+        .get_attribute_values::<i32>("physical_tag")
+        .ok();
+
+    let mut elements = Vec::new();
+    for ind_tri in 0..simpl.get_nb_triangles() {
+        let physical_tag = physical_tags
+            .map(|tags| tags[ind_tri])
+            .filter(|&tag| tag != 0);
+        elements.push(GmshElement {
+            element_type: GMSH_TRIANGLE,
+            vertices: simpl.triangle_node_values(ind_tri).to_vec(),
+            physical_tag,
+        });
+    }
+
+    write_gmsh(filename, node_coords, &elements)
+}
@@ -2,8 +2,14 @@ use anyhow::Result;
 use nalgebra::base::*;
 use ply_rs::ply::{Property, PropertyType, ScalarType};
 
+use crate::graph_structure::attribute_set::AttributeEvent;
+use crate::graph_structure::simplicial2::simplicial_2_build::build_from_triangle_list_with_boundary;
+use crate::graph_structure::simplicial2::Simplicial2;
 use crate::mesh_structure::property_set::PropertySet;
 
+/// Triangle attribute storing index of mesh face (usize::MAX if none)
+pub const MESH_FACE_ATTRIBUTE: &str = "mesh_face";
+
 #[derive(Clone)]
 /// Mesh3D is a struct that represents a mesh in three-dimensional space.
 pub struct Mesh3D {
@@ -151,6 +157,157 @@ impl Mesh3D {
         Ok(&self.faces[ind_face])
     }
 
+    /// Sum of cross products of face edges (Newell's method), twice the area vector
+    fn face_area_vector(&self, ind_face: usize) -> Result<Vector3<f64>> {
+        let face = self.get_face(ind_face)?;
+        let mut area_vector = Vector3::zeros();
+        for i in 0..face.len() {
+            let v0 = self.get_vertex(face[i])?;
+            let v1 = self.get_vertex(face[(i + 1) % face.len()])?;
+            area_vector += v0.cross(&v1);
+        }
+        Ok(area_vector)
+    }
+
+    /// Gets face unit normal (counterclockwise orientation, zero for degenerated faces)
+    pub fn face_normal(&self, ind_face: usize) -> Result<Vector3<f64>> {
+        let area_vector = self.face_area_vector(ind_face)?;
+        let norm = area_vector.norm();
+        if norm == 0. {
+            Ok(area_vector)
+        } else {
+            Ok(area_vector / norm)
+        }
+    }
+
+    /// Gets face area (faces are supposed to be planar)
+    pub fn face_area(&self, ind_face: usize) -> Result<f64> {
+        Ok(0.5 * self.face_area_vector(ind_face)?.norm())
+    }
+
+    /// Gets total area of faces
+    pub fn total_area(&self) -> Result<f64> {
+        (0..self.faces.len())
+            .map(|ind_face| self.face_area(ind_face))
+            .sum()
+    }
+
+    /// Gets vertex unit normals, as area weighted average of adjacent face normals
+    /// (zero for isolated vertices)
+    pub fn vertex_normals(&self) -> Result<Vec<Vector3<f64>>> {
+        let mut normals = vec![Vector3::zeros(); self.vertices.len()];
+        for ind_face in 0..self.faces.len() {
+            let area_vector = self.face_area_vector(ind_face)?;
+            for &ind_vertex in self.faces[ind_face].iter() {
+                normals[ind_vertex] += area_vector;
+            }
+        }
+        for normal in normals.iter_mut() {
+            let norm = normal.norm();
+            if norm != 0. {
+                *normal /= norm;
+            }
+        }
+        Ok(normals)
+    }
+
+    /// Gets volume enclosed by faces
+    ///
+    /// Mesh has to be closed, and faces oriented counterclockwise from outside
+    /// (volume is negative otherwise).
+    pub fn enclosed_volume(&self) -> Result<f64> {
+        let mut volume = 0.;
+        for face in self.faces.iter() {
+            let v0 = self.get_vertex(face[0])?;
+            for i in 1..face.len().saturating_sub(1) {
+                let v1 = self.get_vertex(face[i])?;
+                let v2 = self.get_vertex(face[i + 1])?;
+                volume += v0.dot(&v1.cross(&v2)) / 6.;
+            }
+        }
+        Ok(volume)
+    }
+
+    /// Gets centroid of surface (faces weighted by their area)
+    pub fn centroid(&self) -> Result<Vector3<f64>> {
+        let mut centroid = Vector3::zeros();
+        let mut total_area = 0.;
+        for face in self.faces.iter() {
+            let v0 = self.get_vertex(face[0])?;
+            for i in 1..face.len().saturating_sub(1) {
+                let v1 = self.get_vertex(face[i])?;
+                let v2 = self.get_vertex(face[i + 1])?;
+                let area = 0.5 * (v1 - v0).cross(&(v2 - v0)).norm();
+                centroid += area * (v0 + v1 + v2) / 3.;
+                total_area += area;
+            }
+        }
+        if total_area == 0. {
+            return Err(anyhow::Error::msg("Mesh has no area"));
+        }
+        Ok(centroid / total_area)
+    }
+
+    /// Gets axis aligned bounding box, as minimal and maximal corners
+    pub fn bounding_box(&self) -> Result<[Vector3<f64>; 2]> {
+        let first = *self
+            .vertices
+            .first()
+            .ok_or(anyhow::Error::msg("Mesh has no vertex"))?;
+        Ok(self
+            .vertices
+            .iter()
+            .fold([first, first], |[min, max], vertex| {
+                [min.inf(vertex), max.sup(vertex)]
+            }))
+    }
+
+    /// Stores vertex normals in "nx", "ny" and "nz" vertex properties
+    pub fn add_vertex_normal_properties(&mut self) -> Result<()> {
+        let normals = self.vertex_normals()?;
+        for (ind_coord, name) in ["nx", "ny", "nz"].iter().enumerate() {
+            self.add_vertex_property_f64(name.to_string(), 0.);
+            for (ind_vertex, normal) in normals.iter().enumerate() {
+                self.set_vertex_property_f64(ind_vertex, name.to_string(), normal[ind_coord])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores face normals in "nx", "ny" and "nz" face properties
+    pub fn add_face_normal_properties(&mut self) -> Result<()> {
+        for (ind_coord, name) in ["nx", "ny", "nz"].iter().enumerate() {
+            self.add_face_property(
+                name.to_string(),
+                PropertyType::Scalar(ScalarType::Double),
+                Property::Double(0.),
+            );
+            for ind_face in 0..self.faces.len() {
+                let normal = self.face_normal(ind_face)?;
+                self.set_face_property_value(
+                    ind_face,
+                    name.to_string(),
+                    Property::Double(normal[ind_coord]),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores face areas in "area" face property
+    pub fn add_face_area_property(&mut self) -> Result<()> {
+        self.add_face_property(
+            "area".to_string(),
+            PropertyType::Scalar(ScalarType::Double),
+            Property::Double(0.),
+        );
+        for ind_face in 0..self.faces.len() {
+            let area = self.face_area(ind_face)?;
+            self.set_face_property_value(ind_face, "area".to_string(), Property::Double(area))?;
+        }
+        Ok(())
+    }
+
     /// Inserts new vertex
     pub fn insert_vertex(&mut self, vertex: Vector3<f64>) -> Result<usize> {
         let id = self.vertices.len();
@@ -182,7 +339,7 @@ impl Mesh3D {
             .set_property_value(
                 id,
                 "vertex_indices".to_string(),
-                Property::ListInt(face.iter().map(|&i| i as i32).collect()),
+                Property::ListUInt(face.iter().map(|&i| i as u32).collect()),
             )
             .unwrap();
         self.faces.push(face);
@@ -361,4 +518,101 @@ impl Mesh3D {
             Err(anyhow::Error::msg("Property is not of type Vec<u32>"))
         }
     }
+
+    /// Builds 2D simplicial from triangle faces (node value is vertex index)
+    ///
+    /// Surfaces with boundaries are accepted. Each triangle stores the index of its face in
+    /// the [`MESH_FACE_ATTRIBUTE`] attribute: split triangles keep the face of their parent,
+    /// flipped triangles take the face of their first parent. Polygonal faces have to be
+    /// triangulated first (see [`triangulate_faces`](super::triangulation::triangulate_faces)).
+    pub fn to_simplicial2(&self, register_node_halfedges: bool) -> Result<Simplicial2> {
+        let mut triangles = Vec::with_capacity(self.faces.len());
+        for face in self.faces.iter() {
+            if let [v0, v1, v2] = face[..] {
+                triangles.push([v0, v1, v2]);
+            } else {
+                return Err(anyhow::Error::msg("Mesh has non triangle faces"));
+            }
+        }
+
+        let mut simpl = build_from_triangle_list_with_boundary(triangles, register_node_halfedges)?;
+        let triangle_attributes = simpl.get_triangle_attributes_mut();
+        triangle_attributes.add_attribute_with_rule(
+            MESH_FACE_ATTRIBUTE.to_string(),
+            usize::MAX,
+            |event, parents: &[usize]| match event {
+                AttributeEvent::Creation => usize::MAX,
+                AttributeEvent::Split | AttributeEvent::Flip => parents[0],
+            },
+        );
+        for ind_face in 0..self.faces.len() {
+            triangle_attributes.set_attribute_value(ind_face, MESH_FACE_ATTRIBUTE, ind_face)?;
+        }
+        Ok(simpl)
+    }
+
+    /// Builds mesh from 2D simplicial triangles (vertex index is node value)
+    ///
+    /// Vertices and their properties are copied from given mesh. Face properties are copied
+    /// from the face stored in the [`MESH_FACE_ATTRIBUTE`] triangle attribute, if any
+    /// (default values otherwise).
+    pub fn from_simplicial2(simpl: &Simplicial2, mesh: &Mesh3D) -> Result<Mesh3D> {
+        let mesh_faces = if simpl
+            .get_triangle_attributes()
+            .has_attribute(MESH_FACE_ATTRIBUTE)
+        {
+            simpl
+                .get_triangle_attributes()
+                .get_attribute_values::<usize>(MESH_FACE_ATTRIBUTE)?
+                .to_vec()
+        } else {
+            vec![usize::MAX; simpl.get_nb_triangles()]
+        };
+
+        let mut face_properties = mesh.face_properties.clone();
+        while face_properties.get_nb_elements() != 0 {
+            face_properties.pop_element();
+        }
+        let property_names: Vec<String> = face_properties
+            .get_property_names()
+            .into_iter()
+            .filter(|name| name != "vertex_indices")
+            .collect();
+
+        let mut faces = Vec::with_capacity(simpl.get_nb_triangles());
+        for (ind_tri, &ind_face) in mesh_faces.iter().enumerate() {
+            let face = simpl.triangle_node_values(ind_tri).to_vec();
+            if face
+                .iter()
+                .any(|&ind_vertex| ind_vertex >= mesh.vertices.len())
+            {
+                return Err(anyhow::Error::msg("Node value out of mesh vertices"));
+            }
+
+            face_properties.push_element();
+            face_properties.set_property_value(
+                ind_tri,
+                "vertex_indices".to_string(),
+                Property::ListUInt(face.iter().map(|&i| i as u32).collect()),
+            )?;
+            if ind_face < mesh.faces.len() {
+                for name in property_names.iter() {
+                    face_properties.set_property_value(
+                        ind_tri,
+                        name.clone(),
+                        mesh.face_properties
+                            .get_property_value(ind_face, name.clone())?,
+                    )?;
+                }
+            }
+            faces.push(face);
+        }
+
+        Ok(Mesh3D {
+            vertices: mesh.vertices.clone(),
+            faces,
+            vertex_properties: mesh.vertex_properties.clone(),
+            face_properties,
+        })
+    }
 }
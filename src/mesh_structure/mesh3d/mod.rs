@@ -1,8 +1,23 @@
 // Mesh 3D structure
 mod mesh3d;
-pub use mesh3d::Mesh3D;
+pub use mesh3d::{Mesh3D, MESH_FACE_ATTRIBUTE};
 
 /// Input/Ouput functions
 pub mod io;
 
+/// Surface sampling functions
+pub mod sampling;
+
+/// Generalized winding number functions
+pub mod winding_number;
+
+/// Bounding volume hierarchy for ray casting and distance queries
+pub mod bvh;
+
+/// Mesh repair functions
+pub mod repair;
+
+/// Polygon triangulation functions
+pub mod triangulation;
+
 mod unit_tests;
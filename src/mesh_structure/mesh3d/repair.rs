@@ -0,0 +1,390 @@
+use anyhow::Result;
+use nalgebra::base::*;
+use ply_rs::ply::Property;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::mesh3d::Mesh3D;
+
+/// Summary of repair passes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    /// Number of vertices merged into another vertex
+    pub merged_vertices: usize,
+    /// Number of degenerate faces removed
+    pub degenerate_faces: usize,
+    /// Number of duplicate faces removed
+    pub duplicate_faces: usize,
+    /// Number of flipped faces
+    pub flipped_faces: usize,
+    /// Number of filled boundary loops
+    pub filled_holes: usize,
+}
+
+/// Sets vertex indices of a face, keeping "vertex_indices" property up to date
+fn set_face(mesh: &mut Mesh3D, ind_face: usize, face: Vec<usize>) -> Result<()> {
+    mesh.face_properties.set_property_value(
+        ind_face,
+        "vertex_indices".to_string(),
+        Property::ListUInt(face.iter().map(|&i| i as u32).collect()),
+    )?;
+    mesh.faces[ind_face] = face;
+    Ok(())
+}
+
+/// Keeps given faces (in given order) with their properties
+fn keep_faces(mesh: &mut Mesh3D, kept_faces: &[usize]) -> Result<()> {
+    mesh.face_properties.keep_elements(kept_faces)?;
+    mesh.faces = kept_faces
+        .iter()
+        .map(|&ind_face| mesh.faces[ind_face].clone())
+        .collect();
+    Ok(())
+}
+
+/// Merges vertices closer than tolerance, returns number of merged vertices
+///
+/// Each vertex is merged into the first vertex (in index order) within tolerance, which keeps
+/// its position and properties. Merged vertices are removed and faces are reindexed, faces
+/// may become degenerate (see [`remove_degenerate_faces`]).
+pub fn merge_close_vertices(mesh: &mut Mesh3D, tolerance: f64) -> Result<usize> {
+    if tolerance <= 0. {
+        return Err(anyhow::Error::msg("Tolerance has to be positive"));
+    }
+
+    // grid hash of kept vertices, with cell size equal to tolerance
+    let cell = |vertex: &Vector3<f64>| vertex.map(|coord| (coord / tolerance).floor() as i64);
+    let mut grid: HashMap<Vector3<i64>, Vec<usize>> = HashMap::new();
+    let mut representatives = Vec::with_capacity(mesh.get_nb_vertices());
+    for (ind_vertex, vertex) in mesh.vertices.iter().enumerate() {
+        let center = cell(vertex);
+        let mut representative = ind_vertex;
+        'search: for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let Some(candidates) = grid.get(&(center + Vector3::new(dx, dy, dz))) else {
+                        continue;
+                    };
+                    if let Some(&candidate) = candidates
+                        .iter()
+                        .find(|&&candidate| (mesh.vertices[candidate] - vertex).norm() <= tolerance)
+                    {
+                        representative = candidate;
+                        break 'search;
+                    }
+                }
+            }
+        }
+        if representative == ind_vertex {
+            grid.entry(center).or_default().push(ind_vertex);
+        }
+        representatives.push(representative);
+    }
+
+    let kept_vertices: Vec<usize> = (0..mesh.get_nb_vertices())
+        .filter(|&ind_vertex| representatives[ind_vertex] == ind_vertex)
+        .collect();
+    let nb_merged = mesh.get_nb_vertices() - kept_vertices.len();
+    if nb_merged == 0 {
+        return Ok(0);
+    }
+
+    let mut new_indices = vec![usize::MAX; mesh.get_nb_vertices()];
+    for (new_index, &ind_vertex) in kept_vertices.iter().enumerate() {
+        new_indices[ind_vertex] = new_index;
+    }
+    for ind_face in 0..mesh.get_nb_faces() {
+        let face = mesh.faces[ind_face]
+            .iter()
+            .map(|&ind_vertex| new_indices[representatives[ind_vertex]])
+            .collect();
+        set_face(mesh, ind_face, face)?;
+    }
+    mesh.vertex_properties.keep_elements(&kept_vertices)?;
+    mesh.vertices = kept_vertices
+        .iter()
+        .map(|&ind_vertex| mesh.vertices[ind_vertex])
+        .collect();
+
+    Ok(nb_merged)
+}
+
+/// Removes faces with repeated vertices or with area not greater than min_area, returns
+/// number of removed faces
+pub fn remove_degenerate_faces(mesh: &mut Mesh3D, min_area: f64) -> Result<usize> {
+    let mut kept_faces = Vec::with_capacity(mesh.get_nb_faces());
+    for ind_face in 0..mesh.get_nb_faces() {
+        let face = &mesh.faces[ind_face];
+        let distinct: HashSet<usize> = face.iter().copied().collect();
+        if face.len() >= 3 && distinct.len() == face.len() && mesh.face_area(ind_face)? > min_area {
+            kept_faces.push(ind_face);
+        }
+    }
+    let nb_removed = mesh.get_nb_faces() - kept_faces.len();
+    keep_faces(mesh, &kept_faces)?;
+    Ok(nb_removed)
+}
+
+/// Removes faces with the same vertices as a previous face (whatever their order), returns
+/// number of removed faces
+pub fn remove_duplicate_faces(mesh: &mut Mesh3D) -> Result<usize> {
+    let mut known_faces = HashSet::new();
+    let mut kept_faces = Vec::with_capacity(mesh.get_nb_faces());
+    for (ind_face, face) in mesh.faces.iter().enumerate() {
+        let mut sorted_face = face.clone();
+        sorted_face.sort();
+        if known_faces.insert(sorted_face) {
+            kept_faces.push(ind_face);
+        }
+    }
+    let nb_removed = mesh.get_nb_faces() - kept_faces.len();
+    keep_faces(mesh, &kept_faces)?;
+    Ok(nb_removed)
+}
+
+/// Gets faces containing each undirected edge
+fn edge_faces(mesh: &Mesh3D) -> HashMap<[usize; 2], Vec<usize>> {
+    let mut edge_faces: HashMap<[usize; 2], Vec<usize>> = HashMap::new();
+    for (ind_face, face) in mesh.faces.iter().enumerate() {
+        for i in 0..face.len() {
+            let (v0, v1) = (face[i], face[(i + 1) % face.len()]);
+            edge_faces
+                .entry([v0.min(v1), v0.max(v1)])
+                .or_default()
+                .push(ind_face);
+        }
+    }
+    edge_faces
+}
+
+/// Checks if a face contains directed edge [v0, v1]
+fn has_directed_edge(face: &[usize], v0: usize, v1: usize) -> bool {
+    (0..face.len()).any(|i| face[i] == v0 && face[(i + 1) % face.len()] == v1)
+}
+
+/// Orients faces consistently within each edge-connected component, returns number of
+/// flipped faces
+///
+/// Orientation is propagated through edges shared by exactly two faces. Closed components
+/// are oriented with positive enclosed volume (counterclockwise from outside), other
+/// components keep the orientation of most of their faces. Non orientable components are
+/// oriented as much as possible (a warning is logged).
+pub fn orient_faces(mesh: &mut Mesh3D) -> Result<usize> {
+    let edge_faces = edge_faces(mesh);
+    let mut flipped = vec![false; mesh.get_nb_faces()];
+    let mut visited = vec![false; mesh.get_nb_faces()];
+
+    for seed in 0..mesh.get_nb_faces() {
+        if visited[seed] {
+            continue;
+        }
+        visited[seed] = true;
+        let mut component = vec![seed];
+        let mut is_closed = true;
+        let mut to_visit = VecDeque::from([seed]);
+        while let Some(ind_face) = to_visit.pop_front() {
+            let face = &mesh.faces[ind_face];
+            for i in 0..face.len() {
+                let (v0, v1) = (face[i], face[(i + 1) % face.len()]);
+                // edge direction within face once oriented
+                let (v0, v1) = if flipped[ind_face] {
+                    (v1, v0)
+                } else {
+                    (v0, v1)
+                };
+                let neighbors = &edge_faces[&[v0.min(v1), v0.max(v1)]];
+                if neighbors.len() != 2 {
+                    is_closed = false;
+                    continue;
+                }
+                let ind_nei = if neighbors[0] == ind_face {
+                    neighbors[1]
+                } else {
+                    neighbors[0]
+                };
+                // consistent neighbor goes through the edge in reverse direction
+                let nei_flipped = has_directed_edge(&mesh.faces[ind_nei], v0, v1);
+                if visited[ind_nei] {
+                    if nei_flipped != flipped[ind_nei] {
+                        log::warn!("Face {} can not be oriented consistently", ind_nei);
+                    }
+                    continue;
+                }
+                visited[ind_nei] = true;
+                flipped[ind_nei] = nei_flipped;
+                component.push(ind_nei);
+                to_visit.push_back(ind_nei);
+            }
+        }
+
+        let nb_flipped = component.iter().filter(|&&ind| flipped[ind]).count();
+        let reverse = if is_closed {
+            let volume: f64 = component
+                .iter()
+                .map(|&ind_face| {
+                    let face = &mesh.faces[ind_face];
+                    let v0 = mesh.vertices[face[0]];
+                    let signed_volume: f64 = (1..face.len().saturating_sub(1))
+                        .map(|i| v0.dot(&mesh.vertices[face[i]].cross(&mesh.vertices[face[i + 1]])))
+                        .sum();
+                    if flipped[ind_face] {
+                        -signed_volume
+                    } else {
+                        signed_volume
+                    }
+                })
+                .sum();
+            volume < 0.
+        } else {
+            2 * nb_flipped > component.len()
+        };
+        if reverse {
+            for &ind_face in component.iter() {
+                flipped[ind_face] = !flipped[ind_face];
+            }
+        }
+    }
+
+    let mut nb_flipped = 0;
+    for (ind_face, &is_flipped) in flipped.iter().enumerate() {
+        if is_flipped {
+            let mut face = mesh.faces[ind_face].clone();
+            face.reverse();
+            set_face(mesh, ind_face, face)?;
+            nb_flipped += 1;
+        }
+    }
+    Ok(nb_flipped)
+}
+
+/// Gets boundary loops, as vertex lists oriented like the faces that would close them
+///
+/// Loops going through a vertex with several boundary edges are ignored.
+fn boundary_loops(mesh: &Mesh3D) -> Vec<Vec<usize>> {
+    // a boundary edge [v0, v1] of a face is [v1, v0] in the closing face
+    let mut loop_next: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (edge, faces) in edge_faces(mesh).iter() {
+        if faces.len() != 1 {
+            continue;
+        }
+        let [v0, v1] = *edge;
+        if has_directed_edge(&mesh.faces[faces[0]], v0, v1) {
+            loop_next.entry(v1).or_default().push(v0);
+        } else {
+            loop_next.entry(v0).or_default().push(v1);
+        }
+    }
+
+    let mut starts: Vec<usize> = loop_next.keys().copied().collect();
+    starts.sort();
+    let mut visited = HashSet::new();
+    let mut loops = Vec::new();
+    for start in starts {
+        if visited.contains(&start) {
+            continue;
+        }
+        let mut boundary_loop = vec![start];
+        let mut is_simple = true;
+        visited.insert(start);
+        let mut current = start;
+        loop {
+            let next = match loop_next.get(&current).map(|next| &next[..]) {
+                Some([next]) => *next,
+                _ => {
+                    is_simple = false;
+                    break;
+                }
+            };
+            if next == start {
+                break;
+            }
+            if !visited.insert(next) {
+                is_simple = false;
+                break;
+            }
+            boundary_loop.push(next);
+            current = next;
+        }
+        if is_simple {
+            loops.push(boundary_loop);
+        }
+    }
+    loops
+}
+
+/// Triangulates a polygon minimizing total triangle area (dynamic programming)
+fn minimal_area_triangulation(polygon: &[Vector3<f64>]) -> Vec<[usize; 3]> {
+    let nb = polygon.len();
+    // cost[i][j]: minimal area of triangulation of polygon i..=j, split[i][j]: best middle
+    let mut cost = vec![vec![0.; nb]; nb];
+    let mut split = vec![vec![0; nb]; nb];
+    for length in 2..nb {
+        for i in 0..nb - length {
+            let j = i + length;
+            cost[i][j] = f64::MAX;
+            for k in i + 1..j {
+                let area = 0.5
+                    * (polygon[k] - polygon[i])
+                        .cross(&(polygon[j] - polygon[i]))
+                        .norm();
+                let total = cost[i][k] + cost[k][j] + area;
+                if total < cost[i][j] {
+                    cost[i][j] = total;
+                    split[i][j] = k;
+                }
+            }
+        }
+    }
+
+    let mut triangles = Vec::with_capacity(nb - 2);
+    let mut to_split = vec![(0, nb - 1)];
+    while let Some((i, j)) = to_split.pop() {
+        if j < i + 2 {
+            continue;
+        }
+        let k = split[i][j];
+        triangles.push([i, k, j]);
+        to_split.push((i, k));
+        to_split.push((k, j));
+    }
+    triangles
+}
+
+/// Fills boundary loops with at most max_hole_size edges, returns number of filled loops
+///
+/// Loops are triangulated without new vertices, minimizing total area. New faces have
+/// default properties.
+pub fn fill_small_holes(mesh: &mut Mesh3D, max_hole_size: usize) -> Result<usize> {
+    let mut nb_filled = 0;
+    for boundary_loop in boundary_loops(mesh) {
+        if boundary_loop.len() < 3 || boundary_loop.len() > max_hole_size {
+            continue;
+        }
+        let polygon: Vec<Vector3<f64>> = boundary_loop
+            .iter()
+            .map(|&ind_vertex| mesh.vertices[ind_vertex])
+            .collect();
+        for [i, j, k] in minimal_area_triangulation(&polygon) {
+            mesh.insert_face(vec![boundary_loop[i], boundary_loop[j], boundary_loop[k]])?;
+        }
+        nb_filled += 1;
+    }
+    Ok(nb_filled)
+}
+
+/// Runs all repair passes: merges close vertices, removes degenerate and duplicate faces,
+/// orients faces and fills small holes
+pub fn repair_mesh(
+    mesh: &mut Mesh3D,
+    tolerance: f64,
+    max_hole_size: usize,
+) -> Result<RepairReport> {
+    let mut report = RepairReport {
+        merged_vertices: merge_close_vertices(mesh, tolerance)?,
+        ..Default::default()
+    };
+    report.degenerate_faces = remove_degenerate_faces(mesh, 0.)?;
+    report.duplicate_faces = remove_duplicate_faces(mesh)?;
+    report.flipped_faces = orient_faces(mesh)?;
+    report.filled_holes = fill_small_holes(mesh, max_hole_size)?;
+    Ok(report)
+}
@@ -0,0 +1,200 @@
+use anyhow::Result;
+use nalgebra::base::*;
+use ply_rs::ply::{Property, PropertyType, ScalarType};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+
+use super::io::save_mesh_ply;
+use super::mesh3d::Mesh3D;
+
+/// Point sampled on mesh surface
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurfaceSample {
+    /// Sample position
+    pub position: Vector3<f64>,
+    /// Index of face containing the sample
+    pub face: usize,
+    /// Barycentric coordinates of the sample within its face
+    pub barycentric: Vector3<f64>,
+    /// Unit normal, interpolated from vertex normals
+    pub normal: Vector3<f64>,
+}
+
+/// Area-weighted face sampler, shared by sampling methods
+struct FaceSampler<'a> {
+    mesh: &'a Mesh3D,
+    triangles: Vec<[usize; 3]>,
+    cumulated_areas: Vec<f64>,
+    vertex_normals: Vec<Vector3<f64>>,
+}
+
+impl<'a> FaceSampler<'a> {
+    fn new(mesh: &'a Mesh3D) -> Result<FaceSampler<'a>> {
+        let mut triangles = Vec::with_capacity(mesh.get_nb_faces());
+        let mut cumulated_areas = Vec::with_capacity(mesh.get_nb_faces());
+        let mut total_area = 0.;
+        for ind_face in 0..mesh.get_nb_faces() {
+            if let [v0, v1, v2] = mesh.get_face(ind_face)?[..] {
+                triangles.push([v0, v1, v2]);
+            } else {
+                return Err(anyhow::Error::msg("Mesh has non triangle faces"));
+            }
+            total_area += mesh.face_area(ind_face)?;
+            cumulated_areas.push(total_area);
+        }
+        if total_area == 0. {
+            return Err(anyhow::Error::msg("Mesh has no area"));
+        }
+
+        Ok(FaceSampler {
+            mesh,
+            triangles,
+            cumulated_areas,
+            vertex_normals: mesh.vertex_normals()?,
+        })
+    }
+
+    fn total_area(&self) -> f64 {
+        *self.cumulated_areas.last().unwrap_or(&0.)
+    }
+
+    fn sample(&self, rng: &mut StdRng) -> Result<SurfaceSample> {
+        let area = rng.gen_range(0.0..self.total_area());
+        let face = self
+            .cumulated_areas
+            .partition_point(|&cumulated| cumulated <= area)
+            .min(self.triangles.len() - 1);
+
+        // uniform barycentric coordinates
+        let r0: f64 = rng.gen();
+        let r1: f64 = rng.gen();
+        let sqrt_r0 = r0.sqrt();
+        let barycentric = Vector3::new(1. - sqrt_r0, sqrt_r0 * (1. - r1), sqrt_r0 * r1);
+
+        let [v0, v1, v2] = self.triangles[face];
+        let position = barycentric[0] * self.mesh.get_vertex(v0)?
+            + barycentric[1] * self.mesh.get_vertex(v1)?
+            + barycentric[2] * self.mesh.get_vertex(v2)?;
+        let normal = barycentric[0] * self.vertex_normals[v0]
+            + barycentric[1] * self.vertex_normals[v1]
+            + barycentric[2] * self.vertex_normals[v2];
+        let normal = if normal.norm() == 0. {
+            self.mesh.face_normal(face)?
+        } else {
+            normal.normalize()
+        };
+
+        Ok(SurfaceSample {
+            position,
+            face,
+            barycentric,
+            normal,
+        })
+    }
+}
+
+/// Samples points uniformly on mesh triangles (faces are chosen according to their area)
+///
+/// Samples are reproducible from the seed.
+pub fn sample_uniform(mesh: &Mesh3D, nb_samples: usize, seed: u64) -> Result<Vec<SurfaceSample>> {
+    let sampler = FaceSampler::new(mesh)?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..nb_samples).map(|_| sampler.sample(&mut rng)).collect()
+}
+
+/// Samples points on mesh triangles, such that samples are at least at given distance
+/// from each other (Poisson disk sampling)
+///
+/// Uniform candidates are drawn and kept if they are far enough from kept samples,
+/// until a given number of consecutive candidates has been rejected.
+/// Samples are reproducible from the seed.
+pub fn sample_poisson_disk(
+    mesh: &Mesh3D,
+    radius: f64,
+    nb_max_rejections: usize,
+    seed: u64,
+) -> Result<Vec<SurfaceSample>> {
+    if radius <= 0. {
+        return Err(anyhow::Error::msg("Radius has to be positive"));
+    }
+    let sampler = FaceSampler::new(mesh)?;
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    // grid with cell size equal to radius: close samples are in neighbouring cells
+    let cell = |position: &Vector3<f64>| {
+        [
+            (position[0] / radius).floor() as i64,
+            (position[1] / radius).floor() as i64,
+            (position[2] / radius).floor() as i64,
+        ]
+    };
+    let mut grid: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+    let mut samples: Vec<SurfaceSample> = Vec::new();
+
+    let mut nb_rejections = 0;
+    while nb_rejections < nb_max_rejections {
+        let candidate = sampler.sample(&mut rng)?;
+        let [i, j, k] = cell(&candidate.position);
+        let too_close = (i - 1..=i + 1).any(|ci| {
+            (j - 1..=j + 1).any(|cj| {
+                (k - 1..=k + 1).any(|ck| {
+                    grid.get(&[ci, cj, ck]).is_some_and(|inds| {
+                        inds.iter().any(|&ind| {
+                            (samples[ind].position - candidate.position).norm() < radius
+                        })
+                    })
+                })
+            })
+        });
+        if too_close {
+            nb_rejections += 1;
+        } else {
+            nb_rejections = 0;
+            grid.entry([i, j, k]).or_default().push(samples.len());
+            samples.push(candidate);
+        }
+    }
+
+    Ok(samples)
+}
+
+/// Converts samples to a point cloud mesh (no face)
+///
+/// Vertex properties: normal ("nx", "ny", "nz"), source face ("face")
+/// and barycentric coordinates ("u", "v", "w").
+pub fn samples_to_point_cloud(samples: &[SurfaceSample]) -> Result<Mesh3D> {
+    let mut mesh = Mesh3D::create(
+        samples.iter().map(|sample| sample.position).collect(),
+        Vec::new(),
+    );
+    for name in ["nx", "ny", "nz", "u", "v", "w"] {
+        mesh.add_vertex_property_f64(name.to_string(), 0.);
+    }
+    mesh.add_vertex_property(
+        "face".to_string(),
+        PropertyType::Scalar(ScalarType::Int),
+        Property::Int(-1),
+    );
+
+    for (ind_sample, sample) in samples.iter().enumerate() {
+        for (i, name) in ["nx", "ny", "nz"].iter().enumerate() {
+            mesh.set_vertex_property_f64(ind_sample, name.to_string(), sample.normal[i])?;
+        }
+        for (i, name) in ["u", "v", "w"].iter().enumerate() {
+            mesh.set_vertex_property_f64(ind_sample, name.to_string(), sample.barycentric[i])?;
+        }
+        mesh.set_vertex_property_value(
+            ind_sample,
+            "face".to_string(),
+            Property::Int(sample.face as i32),
+        )?;
+    }
+
+    Ok(mesh)
+}
+
+/// Saves samples as a ply point cloud
+pub fn save_samples_ply(filename: &str, samples: &[SurfaceSample]) -> Result<()> {
+    save_mesh_ply(filename, &samples_to_point_cloud(samples)?, None)
+}
@@ -0,0 +1,149 @@
+use anyhow::Result;
+use nalgebra::base::*;
+use ply_rs::ply::{Property, PropertyType, ScalarType};
+
+use super::mesh3d::Mesh3D;
+
+/// Face property storing index of parent polygon in triangulated mesh
+pub const PARENT_FACE_PROPERTY: &str = "parent_face";
+
+/// Twice the signed area of a 2D triangle
+fn signed_area_2d(p0: &Vector2<f64>, p1: &Vector2<f64>, p2: &Vector2<f64>) -> f64 {
+    (p1 - p0).perp(&(p2 - p0))
+}
+
+/// Checks if a 2D point is inside a counterclockwise triangle (boundary included)
+fn is_in_triangle_2d(point: &Vector2<f64>, triangle: [&Vector2<f64>; 3]) -> bool {
+    signed_area_2d(triangle[0], triangle[1], point) >= 0.
+        && signed_area_2d(triangle[1], triangle[2], point) >= 0.
+        && signed_area_2d(triangle[2], triangle[0], point) >= 0.
+}
+
+/// Triangulates a polygon by ear clipping, returns triangles as polygon vertex indices
+///
+/// Polygon is projected on its best-fit plane (normal computed with Newell's method), and
+/// triangles keep polygon orientation. Ears are clipped first, if none is found (degenerate
+/// or self-intersecting polygon) the most convex corner is clipped.
+pub fn triangulate_polygon(polygon: &[Vector3<f64>]) -> Result<Vec<[usize; 3]>> {
+    if polygon.len() < 3 {
+        return Err(anyhow::Error::msg("Polygon has less than 3 vertices"));
+    }
+
+    // best-fit plane basis
+    let mut normal = Vector3::zeros();
+    for i in 0..polygon.len() {
+        normal += polygon[i].cross(&polygon[(i + 1) % polygon.len()]);
+    }
+    let normal = normal.try_normalize(0.).unwrap_or(Vector3::z());
+    let axis_u = if normal[0].abs() < 0.9 {
+        Vector3::x()
+    } else {
+        Vector3::y()
+    };
+    let axis_u = (axis_u - axis_u.dot(&normal) * normal).normalize();
+    let axis_v = normal.cross(&axis_u);
+    let projected: Vec<Vector2<f64>> = polygon
+        .iter()
+        .map(|point| Vector2::new(point.dot(&axis_u), point.dot(&axis_v)))
+        .collect();
+
+    let mut remaining: Vec<usize> = (0..polygon.len()).collect();
+    let mut triangles = Vec::with_capacity(polygon.len() - 2);
+    while remaining.len() > 3 {
+        let nb = remaining.len();
+        let corner = |i: usize| {
+            [
+                remaining[(i + nb - 1) % nb],
+                remaining[i],
+                remaining[(i + 1) % nb],
+            ]
+        };
+        let is_ear = |i: usize| {
+            let [prev, cur, next] = corner(i);
+            let triangle = [&projected[prev], &projected[cur], &projected[next]];
+            signed_area_2d(triangle[0], triangle[1], triangle[2]) > 0.
+                && remaining
+                    .iter()
+                    .filter(|&&other| other != prev && other != cur && other != next)
+                    .all(|&other| !is_in_triangle_2d(&projected[other], triangle))
+        };
+
+        let ear = (0..nb).find(|&i| is_ear(i)).unwrap_or_else(|| {
+            (0..nb)
+                .max_by(|&i, &j| {
+                    let [pi, ci, ni] = corner(i);
+                    let [pj, cj, nj] = corner(j);
+                    signed_area_2d(&projected[pi], &projected[ci], &projected[ni]).total_cmp(
+                        &signed_area_2d(&projected[pj], &projected[cj], &projected[nj]),
+                    )
+                })
+                .unwrap_or(0)
+        });
+        triangles.push(corner(ear));
+        remaining.remove(ear);
+    }
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+    Ok(triangles)
+}
+
+/// Builds mesh with polygonal faces split into triangles
+///
+/// Vertices and their properties are kept. Each triangle copies the properties of its parent
+/// face, whose index is stored in the [`PARENT_FACE_PROPERTY`] face property.
+pub fn triangulate_faces(mesh: &Mesh3D) -> Result<Mesh3D> {
+    let mut face_properties = mesh.face_properties.clone();
+    let property_names: Vec<String> = face_properties
+        .get_property_names()
+        .into_iter()
+        .filter(|name| name != "vertex_indices" && name != PARENT_FACE_PROPERTY)
+        .collect();
+    while face_properties.get_nb_elements() != 0 {
+        face_properties.pop_element();
+    }
+    if !face_properties.has_property(PARENT_FACE_PROPERTY) {
+        face_properties.add_property(
+            PARENT_FACE_PROPERTY.to_string(),
+            PropertyType::Scalar(ScalarType::UInt),
+            Property::UInt(0),
+        );
+    }
+
+    let mut faces = Vec::with_capacity(mesh.get_nb_faces());
+    for (ind_face, face) in mesh.faces.iter().enumerate() {
+        let polygon = face
+            .iter()
+            .map(|&ind_vertex| mesh.get_vertex(ind_vertex))
+            .collect::<Result<Vec<_>>>()?;
+        for [i, j, k] in triangulate_polygon(&polygon)? {
+            let ind_tri = faces.len();
+            let triangle = vec![face[i], face[j], face[k]];
+            face_properties.push_element();
+            face_properties.set_property_value(
+                ind_tri,
+                "vertex_indices".to_string(),
+                Property::ListUInt(triangle.iter().map(|&i| i as u32).collect()),
+            )?;
+            face_properties.set_property_value(
+                ind_tri,
+                PARENT_FACE_PROPERTY.to_string(),
+                Property::UInt(ind_face as u32),
+            )?;
+            for name in property_names.iter() {
+                face_properties.set_property_value(
+                    ind_tri,
+                    name.clone(),
+                    mesh.face_properties
+                        .get_property_value(ind_face, name.clone())?,
+                )?;
+            }
+            faces.push(triangle);
+        }
+    }
+
+    Ok(Mesh3D {
+        vertices: mesh.vertices.clone(),
+        faces,
+        vertex_properties: mesh.vertex_properties.clone(),
+        face_properties,
+    })
+}
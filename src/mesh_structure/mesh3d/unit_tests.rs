@@ -3,9 +3,30 @@ mod mesh_test {
     use anyhow::Result;
     use nalgebra::base::*;
 
-    use super::super::mesh3d::Mesh3D;
+    use super::super::bvh::MeshBvh;
+    use super::super::io::{
+        load_mesh_gmsh, load_mesh_obj, load_mesh_off, load_mesh_ply, load_mesh_stl,
+        load_simplicial2_gmsh, save_mesh_gmsh, save_mesh_obj, save_mesh_off,
+        save_mesh_ply_with_encoding, save_mesh_stl, save_simplicial2_gmsh,
+        OBJ_CORNER_NORMAL_PROPERTY, OBJ_CORNER_TEXTURE_PROPERTY, OBJ_GROUP_PROPERTY,
+        OBJ_MATERIAL_PROPERTY,
+    };
+    use super::super::mesh3d::{Mesh3D, MESH_FACE_ATTRIBUTE};
+    use super::super::repair::{fill_small_holes, orient_faces, repair_mesh, RepairReport};
+    use super::super::sampling::{sample_poisson_disk, sample_uniform, save_samples_ply};
+    use super::super::triangulation::{
+        triangulate_faces, triangulate_polygon, PARENT_FACE_PROPERTY,
+    };
+    use super::super::winding_number::{winding_number, WindingNumberTree};
+    use crate::graph_structure::simplicial2::simplicial_2_build;
+    use crate::graph_structure::simplicial2::simplicial_2_quality::simplicial2_is_valid;
+    use crate::mesh_structure::ply_writer::write_ply;
 
-    use ply_rs::ply::{Property, PropertyType, ScalarType};
+    use ply_rs::parser::Parser;
+    use ply_rs::ply::{
+        Addable, DefaultElement, ElementDef, Encoding, Ply, Property, PropertyDef, PropertyType,
+        ScalarType,
+    };
 
     fn build_cube() -> Result<Mesh3D> {
         let vertex_coordinates = vec![
@@ -86,4 +107,1055 @@ mod mesh_test {
         }
         Ok(())
     }
+
+    #[test]
+    fn gmsh_io_test() -> Result<()> {
+        let mut mesh = build_cube()?;
+        mesh.add_face_property(
+            "physical_tag".to_string(),
+            PropertyType::Scalar(ScalarType::Int),
+            Property::Int(0),
+        );
+        for ind_face in 0..4 {
+            mesh.set_face_property_value(ind_face, "physical_tag".to_string(), Property::Int(3))?;
+        }
+        mesh.set_face_property_value(7, "physical_tag".to_string(), Property::Int(5))?;
+
+        let filename = std::env::temp_dir()
+            .join("skeletal_structures_mesh_gmsh_test.msh")
+            .to_string_lossy()
+            .to_string();
+        save_mesh_gmsh(&filename, &mesh)?;
+        let mesh_loaded = load_mesh_gmsh(&filename)?;
+        let (simpl, coords) = load_simplicial2_gmsh(&filename, false)?;
+        std::fs::remove_file(&filename)?;
+
+        assert_eq!(mesh_loaded.get_nb_vertices(), mesh.get_nb_vertices());
+        assert_eq!(mesh_loaded.get_nb_faces(), mesh.get_nb_faces());
+        for ind_vertex in 0..mesh.get_nb_vertices() {
+            assert_eq!(
+                mesh_loaded.get_vertex(ind_vertex)?,
+                mesh.get_vertex(ind_vertex)?
+            );
+        }
+        for ind_face in 0..mesh.get_nb_faces() {
+            assert_eq!(mesh_loaded.get_face(ind_face)?, mesh.get_face(ind_face)?);
+            let tag = mesh_loaded
+                .get_face_properties()
+                .get_property_value(ind_face, "physical_tag".to_string())?;
+            let expected = match ind_face {
+                0..=3 => 3,
+                7 => 5,
+                _ => 0,
+            };
+            assert!(tag == Property::Int(expected));
+        }
+
+        assert_eq!(simpl.get_nb_triangles(), 12);
+        assert_eq!(coords.len(), 8);
+        let tags = simpl
+            .get_triangle_attributes()
+            .get_attribute_values::<i32>("physical_tag")?;
+        assert_eq!(tags[2], 3);
+        assert_eq!(tags[7], 5);
+
+        // save simplicial back, and check triangles
+        save_simplicial2_gmsh(&filename, &simpl, &coords)?;
+        let mesh_simpl = load_mesh_gmsh(&filename)?;
+        std::fs::remove_file(&filename)?;
+        for ind_face in 0..mesh.get_nb_faces() {
+            assert_eq!(mesh_simpl.get_face(ind_face)?, mesh.get_face(ind_face)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn gmsh_load_test() -> Result<()> {
+        // non consecutive node tags, one line element and one second order triangle
+        let content = "$MeshFormat\n4.1 0 8\n$EndMeshFormat\n\
+            $PhysicalNames\n1\n2 7 \"my surface\"\n$EndPhysicalNames\n\
+            $Entities\n0 1 1 0\n\
+            1 0 0 0 1 1 0 0 0\n\
+            1 0 0 0 1 1 0 1 7 1 1\n\
+            $EndEntities\n\
+            $Nodes\n1 4 10 40\n2 1 0 4\n10\n20\n30\n40\n\
+            0 0 0\n1 0 0\n0 1 0\n1 1 0\n$EndNodes\n\
+            $Elements\n2 3 1 3\n\
+            1 1 1 1\n3 10 20\n\
+            2 1 9 2\n2 20 40 30 10 10 10\n1 10 20 30 10 10 10\n\
+            $EndElements\n";
+        let filename = std::env::temp_dir()
+            .join("skeletal_structures_mesh_gmsh_load_test.msh")
+            .to_string_lossy()
+            .to_string();
+        std::fs::write(&filename, content)?;
+        let mesh = load_mesh_gmsh(&filename)?;
+
+        assert_eq!(mesh.get_nb_vertices(), 4);
+        assert_eq!(mesh.get_vertex(3)?, Vector3::new(1.0, 1.0, 0.0));
+        assert_eq!(mesh.get_nb_faces(), 2);
+        assert_eq!(mesh.get_face(0)?, &vec![0, 1, 2]);
+        assert_eq!(mesh.get_face(1)?, &vec![1, 3, 2]);
+        let tag = mesh
+            .get_face_properties()
+            .get_property_value(1, "physical_tag".to_string())?;
+        assert!(tag == Property::Int(7));
+
+        // third order triangle reduced to its corners, 20-node hexahedron skipped
+        let elements = "$Elements\n2 2 1 2\n\
+            2 1 21 1\n1 10 20 40 10 10 10 10 10 10 10\n\
+            3 1 17 1\n2 10 10 10 10 10 10 10 10 10 10 10 10 10 10 10 10 10 10 10 10\n\
+            $EndElements\n";
+        let start = content
+            .find("$Elements")
+            .ok_or(anyhow::Error::msg("No Elements section"))?;
+        std::fs::write(&filename, content[..start].to_owned() + elements)?;
+        let mesh = load_mesh_gmsh(&filename)?;
+        assert_eq!(mesh.get_nb_faces(), 1);
+        assert_eq!(mesh.get_face(0)?, &vec![0, 1, 3]);
+
+        // unknown element type
+        let elements = "$Elements\n1 1 1 1\n2 1 99 1\n1 10 20 40\n$EndElements\n";
+        std::fs::write(&filename, content[..start].to_owned() + elements)?;
+        assert!(load_mesh_gmsh(&filename).is_err());
+        std::fs::remove_file(&filename)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn simplicial2_bridge_test() -> Result<()> {
+        let mut mesh = build_cube()?;
+        mesh.add_vertex_property_f64("weight".to_string(), 0.);
+        mesh.set_vertex_property_f64(3, "weight".to_string(), 2.)?;
+        mesh.add_face_property(
+            "label".to_string(),
+            PropertyType::Scalar(ScalarType::Int),
+            Property::Int(-1),
+        );
+        for ind_face in 0..mesh.get_nb_faces() {
+            mesh.set_face_property_value(
+                ind_face,
+                "label".to_string(),
+                Property::Int(ind_face as i32),
+            )?;
+        }
+
+        let mut simpl = mesh.to_simplicial2(true)?;
+        assert!(simplicial2_is_valid(&simpl)?);
+        assert!(simpl.get_nb_triangles() == 12);
+        for ind_face in 0..mesh.get_nb_faces() {
+            let face = mesh.get_face(ind_face)?;
+            let ind_tri = simpl
+                .find_triangle_index(face[0], face[1], face[2])
+                .ok_or(anyhow::Error::msg("could not find face"))?;
+            assert!(ind_tri == ind_face);
+        }
+
+        // split first triangle
+        simplicial_2_build::insert_node_within_triangle(&mut simpl, 8, 0)?;
+        let mesh_faces = simpl
+            .get_triangle_attributes()
+            .get_attribute_values::<usize>(MESH_FACE_ATTRIBUTE)?;
+        assert!(mesh_faces[12] == 0 && mesh_faces[13] == 0);
+
+        // new node has no vertex
+        assert!(Mesh3D::from_simplicial2(&simpl, &mesh).is_err());
+
+        let simpl = mesh.to_simplicial2(false)?;
+        let mesh_back = Mesh3D::from_simplicial2(&simpl, &mesh)?;
+        assert!(mesh_back.get_nb_vertices() == 8);
+        assert!(mesh_back.get_nb_faces() == 12);
+        assert!(mesh_back.get_vertex_property_value_f64(3, "weight".to_string())? == 2.);
+        for ind_face in 0..mesh.get_nb_faces() {
+            assert!(mesh_back.get_face(ind_face)? == mesh.get_face(ind_face)?);
+            assert!(
+                mesh_back
+                    .get_face_properties()
+                    .get_property_value(ind_face, "label".to_string())?
+                    == Property::Int(ind_face as i32)
+            );
+        }
+
+        // non triangle faces
+        let quad = Mesh3D::create(
+            vec![Vector3::zeros(), Vector3::x(), Vector3::y(), Vector3::z()],
+            vec![vec![0, 1, 2, 3]],
+        );
+        assert!(quad.to_simplicial2(false).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn geometry_test() -> Result<()> {
+        let mut mesh = build_cube()?;
+
+        assert!((mesh.face_area(0)? - 2.).abs() < 1e-10);
+        assert!((mesh.face_normal(0)? - Vector3::new(0., 1., 0.)).norm() < 1e-10);
+        assert!((mesh.total_area()? - 24.).abs() < 1e-10);
+        assert!((mesh.enclosed_volume()? - 8.).abs() < 1e-10);
+        assert!(mesh.centroid()?.norm() < 1e-10);
+        let [min, max] = mesh.bounding_box()?;
+        assert!(min == Vector3::new(-1., -1., -1.) && max == Vector3::new(1., 1., 1.));
+
+        let normals = mesh.vertex_normals()?;
+        for ind_vertex in 0..mesh.get_nb_vertices() {
+            assert!((normals[ind_vertex].norm() - 1.).abs() < 1e-10);
+            assert!(normals[ind_vertex].dot(&mesh.get_vertex(ind_vertex)?) > 0.);
+        }
+
+        mesh.add_vertex_normal_properties()?;
+        mesh.add_face_normal_properties()?;
+        mesh.add_face_area_property()?;
+        assert!(mesh.get_vertex_property_value_f64(2, "nx".to_string())? == normals[2][0]);
+        assert!(mesh.get_face_properties().get_property_value_f64(0, "ny")? == 1.);
+        assert!(
+            mesh.get_face_properties()
+                .get_property_value_f64(3, "area")?
+                == 2.
+        );
+
+        assert!(Mesh3D::new().bounding_box().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sampling_test() -> Result<()> {
+        let mesh = build_cube()?;
+
+        let samples = sample_uniform(&mesh, 500, 42)?;
+        assert!(samples.len() == 500);
+        assert!(samples == sample_uniform(&mesh, 500, 42)?);
+        assert!(samples != sample_uniform(&mesh, 500, 43)?);
+        let mut face_count = vec![0; mesh.get_nb_faces()];
+        for sample in samples.iter() {
+            let face = mesh.get_face(sample.face)?;
+            let position = sample.barycentric[0] * mesh.get_vertex(face[0])?
+                + sample.barycentric[1] * mesh.get_vertex(face[1])?
+                + sample.barycentric[2] * mesh.get_vertex(face[2])?;
+            assert!((position - sample.position).norm() < 1e-10);
+            assert!(sample.barycentric.iter().all(|&b| b >= 0.));
+            assert!((sample.barycentric.sum() - 1.).abs() < 1e-10);
+            assert!((sample.position.amax() - 1.).abs() < 1e-10);
+            assert!((sample.normal.norm() - 1.).abs() < 1e-10);
+            assert!(sample.normal.dot(&mesh.face_normal(sample.face)?) > 0.);
+            face_count[sample.face] += 1;
+        }
+        // all faces have same area
+        assert!(face_count.iter().all(|&count| count > 10));
+
+        let radius = 0.4;
+        let samples = sample_poisson_disk(&mesh, radius, 200, 7)?;
+        assert!(samples == sample_poisson_disk(&mesh, radius, 200, 7)?);
+        assert!(samples.len() > 50);
+        for (i, sample_i) in samples.iter().enumerate() {
+            for sample_j in samples[i + 1..].iter() {
+                assert!((sample_i.position - sample_j.position).norm() >= radius);
+            }
+        }
+
+        let filename = std::env::temp_dir()
+            .join("skeletal_structures_samples_test.ply")
+            .to_string_lossy()
+            .to_string();
+        save_samples_ply(&filename, &samples)?;
+        let content = std::fs::read_to_string(&filename)?;
+        assert!(content.contains(&format!("element vertex {}", samples.len())));
+        assert!(content.contains("property double nx"));
+        assert!(content.contains("property int face"));
+        std::fs::remove_file(&filename)?;
+
+        Ok(())
+    }
+
+    fn build_sphere(nb_parallels: usize, nb_meridians: usize) -> Mesh3D {
+        let mut vertices = vec![Vector3::new(0., 0., 1.), Vector3::new(0., 0., -1.)];
+        for i in 1..nb_parallels {
+            let theta = std::f64::consts::PI * i as f64 / nb_parallels as f64;
+            for j in 0..nb_meridians {
+                let phi = 2. * std::f64::consts::PI * j as f64 / nb_meridians as f64;
+                vertices.push(Vector3::new(
+                    theta.sin() * phi.cos(),
+                    theta.sin() * phi.sin(),
+                    theta.cos(),
+                ));
+            }
+        }
+        let ring = |i: usize, j: usize| 2 + (i - 1) * nb_meridians + j % nb_meridians;
+        let mut faces = Vec::new();
+        for j in 0..nb_meridians {
+            faces.push(vec![0, ring(1, j), ring(1, j + 1)]);
+            faces.push(vec![
+                1,
+                ring(nb_parallels - 1, j + 1),
+                ring(nb_parallels - 1, j),
+            ]);
+            for i in 1..nb_parallels - 1 {
+                faces.push(vec![ring(i, j), ring(i + 1, j), ring(i + 1, j + 1)]);
+                faces.push(vec![ring(i, j), ring(i + 1, j + 1), ring(i, j + 1)]);
+            }
+        }
+        Mesh3D::create(vertices, faces)
+    }
+
+    #[test]
+    fn winding_number_test() -> Result<()> {
+        let mut mesh = build_cube()?;
+        assert!((winding_number(&mesh, &Vector3::new(0., 0., 0.))? - 1.).abs() < 1e-10);
+        assert!((winding_number(&mesh, &Vector3::new(0.9, -0.5, 0.3))? - 1.).abs() < 1e-10);
+        assert!(winding_number(&mesh, &Vector3::new(1.5, 0., 0.))?.abs() < 1e-10);
+        assert!(winding_number(&mesh, &Vector3::new(-3., 2., 5.))?.abs() < 1e-10);
+
+        // a missing face removes its solid angle only
+        mesh.faces.truncate(mesh.get_nb_faces() - 1);
+        let center_winding = winding_number(&mesh, &Vector3::new(0., 0., 0.))?;
+        assert!((center_winding - 11. / 12.).abs() < 1e-10);
+        let tree = WindingNumberTree::new(&mesh, 2.)?;
+        assert!(tree.is_inside(&Vector3::new(0., 0., 0.)));
+        assert!(!tree.is_inside(&Vector3::new(0., 0., 3.)));
+
+        let sphere = build_sphere(16, 32);
+        assert!(sphere.enclosed_volume()? > 0.);
+        let tree = WindingNumberTree::new(&sphere, 2.)?;
+        let queries = [
+            Vector3::new(0., 0., 0.),
+            Vector3::new(0.3, -0.5, 0.2),
+            Vector3::new(0., 0.7, -0.6),
+            Vector3::new(1.2, 0.1, 0.),
+            Vector3::new(-0.8, 0.8, 0.8),
+            Vector3::new(5., -4., 10.),
+        ];
+        let precise_tree = WindingNumberTree::new(&sphere, 4.)?;
+        for query in queries.iter() {
+            let exact = winding_number(&sphere, query)?;
+            assert!((tree.winding_number(query) - exact).abs() < 1e-1);
+            assert!((precise_tree.winding_number(query) - exact).abs() < 1e-2);
+            assert!(tree.is_inside(query) == (query.norm() < 1.));
+        }
+
+        assert!(WindingNumberTree::new(&sphere, 1.).is_err());
+        assert!(WindingNumberTree::new(&Mesh3D::new(), 2.)?.winding_number(&queries[0]) == 0.);
+
+        Ok(())
+    }
+
+    #[test]
+    fn bvh_test() -> Result<()> {
+        let cube = build_cube()?;
+        let bvh = MeshBvh::new(&cube)?;
+
+        let hit = bvh
+            .ray_intersection(&Vector3::new(-3., 0.2, 0.5), &Vector3::new(2., 0., 0.))
+            .unwrap();
+        assert!((hit.position - Vector3::new(-1., 0.2, 0.5)).norm() < 1e-10);
+        assert!((hit.distance - 2.).abs() < 1e-10);
+        assert!(cube.face_normal(hit.face)?[0] == -1.);
+        let hits = bvh.ray_intersections(&Vector3::new(-3., 0.2, 0.5), &Vector3::new(1., 0., 0.));
+        assert!(hits.len() == 2);
+        assert!((hits[1].position - Vector3::new(1., 0.2, 0.5)).norm() < 1e-10);
+        assert!(bvh
+            .ray_intersection(&Vector3::new(-3., 0.2, 0.5), &Vector3::new(-1., 0., 0.))
+            .is_none());
+        assert!(bvh
+            .ray_intersection(&Vector3::new(0., 3., 0.), &Vector3::new(0., 0., 1.))
+            .is_none());
+
+        let closest = bvh.closest_point(&Vector3::new(0.2, 0.3, 3.)).unwrap();
+        assert!((closest.position - Vector3::new(0.2, 0.3, 1.)).norm() < 1e-10);
+        assert!((closest.distance - 2.).abs() < 1e-10);
+        let face = cube.get_face(closest.face)?;
+        let position = closest.barycentric[0] * cube.get_vertex(face[0])?
+            + closest.barycentric[1] * cube.get_vertex(face[1])?
+            + closest.barycentric[2] * cube.get_vertex(face[2])?;
+        assert!((position - closest.position).norm() < 1e-10);
+        let closest = bvh.closest_point(&Vector3::new(2., 2., 2.)).unwrap();
+        assert!((closest.position - Vector3::new(1., 1., 1.)).norm() < 1e-10);
+
+        assert!((bvh.signed_distance(&Vector3::new(0.5, 0., 0.)).unwrap() + 0.5).abs() < 1e-10);
+        assert!((bvh.signed_distance(&Vector3::new(0., -4., 0.)).unwrap() - 3.).abs() < 1e-10);
+
+        // compare with brute force on a finer mesh
+        let sphere = build_sphere(12, 24);
+        let bvh = MeshBvh::new(&sphere)?;
+        let queries = [
+            Vector3::new(0.1, 0.2, 0.3),
+            Vector3::new(0.7, -0.1, 0.4),
+            Vector3::new(-2., 1., 0.5),
+        ];
+        for query in queries.iter() {
+            let closest = bvh.closest_point(query).unwrap();
+            let brute_force = (0..sphere.get_nb_faces())
+                .map(|ind_face| {
+                    let nested = MeshBvh::new(&Mesh3D::create(
+                        sphere
+                            .get_face(ind_face)?
+                            .iter()
+                            .map(|&v| sphere.get_vertex(v))
+                            .collect::<Result<_>>()?,
+                        vec![vec![0, 1, 2]],
+                    ))?;
+                    Ok(nested.closest_point(query).unwrap().distance)
+                })
+                .collect::<Result<Vec<f64>>>()?
+                .into_iter()
+                .fold(f64::MAX, f64::min);
+            assert!((closest.distance - brute_force).abs() < 1e-10);
+            let signed_distance = bvh.signed_distance(query).unwrap();
+            assert!((signed_distance < 0.) == (query.norm() < 1.));
+        }
+
+        assert!(MeshBvh::new(&Mesh3D::new())?
+            .closest_point(&queries[0])
+            .is_none());
+        let quad = Mesh3D::create(
+            vec![
+                Vector3::new(0., 0., 0.),
+                Vector3::new(1., 0., 0.),
+                Vector3::new(1., 1., 0.),
+                Vector3::new(0., 1., 0.),
+            ],
+            vec![vec![0, 1, 2, 3]],
+        );
+        assert!(MeshBvh::new(&quad).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn repair_test() -> Result<()> {
+        let cube = build_cube()?;
+
+        // triangle soup with labelled vertices and faces
+        let mut mesh = Mesh3D::new();
+        mesh.add_vertex_property_f64("label".to_string(), -1.);
+        mesh.add_face_property(
+            "group".to_string(),
+            PropertyType::Scalar(ScalarType::Int),
+            Property::Int(-1),
+        );
+        for ind_face in 0..cube.get_nb_faces() - 1 {
+            let mut face = Vec::new();
+            for &ind_vertex in cube.get_face(ind_face)?.iter() {
+                let soup_vertex = mesh.insert_vertex(cube.get_vertex(ind_vertex)?)?;
+                mesh.set_vertex_property_f64(soup_vertex, "label".to_string(), ind_vertex as f64)?;
+                face.push(soup_vertex);
+            }
+            if ind_face == 3 || ind_face == 7 {
+                face.reverse();
+            }
+            let soup_face = mesh.insert_face(face)?;
+            mesh.set_face_property_value(
+                soup_face,
+                "group".to_string(),
+                Property::Int(ind_face as i32),
+            )?;
+        }
+        // duplicate of first face, and degenerate face
+        mesh.insert_face(vec![2, 1, 0])?;
+        mesh.insert_face(vec![1, 3, 4])?;
+
+        let report = repair_mesh(&mut mesh, 1e-6, 3)?;
+        assert!(
+            report
+                == RepairReport {
+                    merged_vertices: 33 - 8,
+                    degenerate_faces: 1,
+                    duplicate_faces: 1,
+                    flipped_faces: 2,
+                    filled_holes: 1,
+                }
+        );
+        assert!(mesh.get_nb_vertices() == 8);
+        assert!(mesh.get_nb_faces() == 12);
+        assert!((mesh.enclosed_volume()? - 8.).abs() < 1e-10);
+
+        for ind_vertex in 0..mesh.get_nb_vertices() {
+            let label = mesh.get_vertex_property_value_f64(ind_vertex, "label".to_string())?;
+            assert!(mesh.get_vertex(ind_vertex)? == cube.get_vertex(label as usize)?);
+            assert!(
+                mesh.get_vertex_property_value_f64(ind_vertex, "x".to_string())?
+                    == mesh.get_vertex(ind_vertex)?[0]
+            );
+        }
+        for ind_face in 0..mesh.get_nb_faces() {
+            let face = mesh.get_face(ind_face)?.clone();
+            let indices =
+                mesh.get_face_property_value_vec_u32(ind_face, "vertex_indices".to_string())?;
+            assert!(indices.iter().map(|&i| i as usize).collect::<Vec<_>>() == face);
+            let group = mesh
+                .get_face_properties()
+                .get_property_value_f64(ind_face, "group")?;
+            if ind_face < 11 {
+                assert!(group == ind_face as f64);
+            } else {
+                assert!(group == -1.);
+            }
+        }
+
+        let simpl = mesh.to_simplicial2(true)?;
+        assert!(simplicial2_is_valid(&simpl)?);
+        assert!(simpl.get_nb_triangles() == 12);
+
+        // consistent closed mesh is unchanged, inverted one is reversed
+        assert!(orient_faces(&mut mesh)? == 0);
+        assert!(fill_small_holes(&mut mesh, 10)? == 0);
+        for face in mesh.faces.iter_mut() {
+            face.reverse();
+        }
+        assert!(orient_faces(&mut mesh)? == 12);
+        assert!((mesh.enclosed_volume()? - 8.).abs() < 1e-10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn triangulation_test() -> Result<()> {
+        // tilted L-shaped hexagon (non convex)
+        let rotation = nalgebra::Rotation3::from_euler_angles(0.3, -0.5, 1.2);
+        let l_shape: Vec<Vector3<f64>> =
+            [(0., 0.), (2., 0.), (2., 1.), (1., 1.), (1., 2.), (0., 2.)]
+                .iter()
+                .map(|&(x, y)| rotation * Vector3::new(x, y, 0.))
+                .collect();
+        let triangles = triangulate_polygon(&l_shape)?;
+        assert!(triangles.len() == 4);
+        let mut area: f64 = 0.;
+        for [i, j, k] in triangles.iter() {
+            let area_vector = (l_shape[*j] - l_shape[*i]).cross(&(l_shape[*k] - l_shape[*i])) / 2.;
+            assert!(area_vector.dot(&(rotation * Vector3::z())) > 0.);
+            area += area_vector.norm();
+        }
+        assert!((area - 3.).abs() < 1e-10);
+        assert!(triangulate_polygon(&l_shape[..2]).is_err());
+
+        // quad, hexagon and triangle
+        let mut vertices = l_shape.clone();
+        vertices.push(Vector3::new(5., 0., 0.));
+        vertices.push(Vector3::new(6., 0., 0.));
+        vertices.push(Vector3::new(6., 1., 0.));
+        vertices.push(Vector3::new(5., 1., 0.));
+        vertices.push(Vector3::new(5.5, -1., 0.));
+        let mut mesh = Mesh3D::create(
+            vertices,
+            vec![vec![6, 7, 8, 9], vec![0, 1, 2, 3, 4, 5], vec![7, 6, 10]],
+        );
+        mesh.add_face_property(
+            "group".to_string(),
+            PropertyType::Scalar(ScalarType::Int),
+            Property::Int(-1),
+        );
+        for ind_face in 0..3 {
+            mesh.set_face_property_value(
+                ind_face,
+                "group".to_string(),
+                Property::Int(10 * ind_face as i32),
+            )?;
+        }
+
+        let triangulated = triangulate_faces(&mesh)?;
+        assert!(triangulated.get_nb_faces() == 7);
+        assert!(triangulated.get_nb_vertices() == mesh.get_nb_vertices());
+        assert!((triangulated.total_area()? - mesh.total_area()?).abs() < 1e-10);
+        let properties = triangulated.get_face_properties();
+        for ind_tri in 0..triangulated.get_nb_faces() {
+            let parent = properties.get_property_value_f64(ind_tri, PARENT_FACE_PROPERTY)?;
+            assert!(parent == [0., 0., 1., 1., 1., 1., 2.][ind_tri]);
+            assert!(properties.get_property_value_f64(ind_tri, "group")? == 10. * parent);
+            let face = triangulated.get_face(ind_tri)?;
+            assert!(face.len() == 3);
+            assert!(
+                triangulated
+                    .get_face_property_value_vec_u32(ind_tri, "vertex_indices".to_string())?
+                    .iter()
+                    .map(|&i| i as usize)
+                    .collect::<Vec<_>>()
+                    == *face
+            );
+            assert!(
+                triangulated
+                    .face_normal(ind_tri)?
+                    .dot(&mesh.face_normal(parent as usize)?)
+                    > 0.
+            );
+        }
+        assert!(mesh.to_simplicial2(false).is_err());
+        triangulated.to_simplicial2(false)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn ply_encoding_test() -> Result<()> {
+        let mut mesh = build_cube()?;
+        mesh.add_vertex_property_f64("label".to_string(), 0.);
+        for ind_vertex in 0..mesh.get_nb_vertices() {
+            mesh.set_vertex_property_f64(ind_vertex, "label".to_string(), ind_vertex as f64 / 3.)?;
+        }
+
+        let filename = std::env::temp_dir()
+            .join("skeletal_structures_encoding_test.ply")
+            .to_string_lossy()
+            .to_string();
+        for encoding in [
+            Encoding::Ascii,
+            Encoding::BinaryLittleEndian,
+            Encoding::BinaryBigEndian,
+        ] {
+            save_mesh_ply_with_encoding(&filename, &mesh, None, encoding)?;
+            let ply =
+                Parser::<DefaultElement>::new().read_ply(&mut std::fs::File::open(&filename)?)?;
+            assert!(ply.header.encoding == encoding);
+            assert!(ply.payload["vertex"].len() == mesh.get_nb_vertices());
+            assert!(ply.payload["face"].len() == mesh.get_nb_faces());
+            for (ind_vertex, vertex) in ply.payload["vertex"].iter().enumerate() {
+                assert!(vertex["y"] == Property::Double(mesh.get_vertex(ind_vertex)?[1]));
+                assert!(vertex["label"] == Property::Double(ind_vertex as f64 / 3.));
+            }
+            for (ind_face, face) in ply.payload["face"].iter().enumerate() {
+                let indices = mesh.get_face(ind_face)?.iter().map(|&i| i as u32).collect();
+                assert!(face["vertex_indices"] == Property::ListUInt(indices));
+            }
+        }
+
+        // loader reads binary files
+        let mut ply = Ply::<DefaultElement>::new();
+        ply.header.encoding = Encoding::BinaryBigEndian;
+        let mut vertex_element = ElementDef::new("vertex".to_string());
+        for name in ["x", "y", "z"] {
+            vertex_element.properties.add(PropertyDef::new(
+                name.to_string(),
+                PropertyType::Scalar(ScalarType::Float),
+            ));
+        }
+        let mut face_element = ElementDef::new("face".to_string());
+        face_element.properties.add(PropertyDef::new(
+            "vertex_index".to_string(),
+            PropertyType::List(ScalarType::UChar, ScalarType::Int),
+        ));
+        ply.header.elements.add(vertex_element);
+        ply.header.elements.add(face_element);
+        let mut vertices = Vec::new();
+        for ind_vertex in 0..mesh.get_nb_vertices() {
+            let coords = mesh.get_vertex(ind_vertex)?;
+            let mut vertex = DefaultElement::new();
+            vertex.insert("x".to_string(), Property::Float(coords[0] as f32));
+            vertex.insert("y".to_string(), Property::Float(coords[1] as f32));
+            vertex.insert("z".to_string(), Property::Float(coords[2] as f32));
+            vertices.push(vertex);
+        }
+        let mut faces = Vec::new();
+        for ind_face in 0..mesh.get_nb_faces() {
+            let mut face = DefaultElement::new();
+            face.insert(
+                "vertex_index".to_string(),
+                Property::ListInt(mesh.get_face(ind_face)?.iter().map(|&i| i as i32).collect()),
+            );
+            faces.push(face);
+        }
+        ply.payload.insert("vertex".to_string(), vertices);
+        ply.payload.insert("face".to_string(), faces);
+        write_ply(&filename, &mut ply)?;
+
+        let loaded = load_mesh_ply(&filename)?;
+        assert!(loaded.vertices == mesh.vertices);
+        for ind_face in 0..mesh.get_nb_faces() {
+            assert!(loaded.get_face(ind_face)? == mesh.get_face(ind_face)?);
+        }
+        std::fs::remove_file(&filename)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn ply_round_trip_test() -> Result<()> {
+        let mut mesh = build_cube()?;
+        mesh.add_vertex_property_f32("quality".to_string(), 0.);
+        mesh.add_face_property(
+            "group".to_string(),
+            PropertyType::Scalar(ScalarType::Short),
+            Property::Short(0),
+        );
+        mesh.add_face_property(
+            "weights".to_string(),
+            PropertyType::List(ScalarType::UChar, ScalarType::Double),
+            Property::ListDouble(Vec::new()),
+        );
+        for ind_vertex in 0..mesh.get_nb_vertices() {
+            mesh.set_vertex_property_f32(
+                ind_vertex,
+                "quality".to_string(),
+                0.1 * ind_vertex as f32,
+            )?;
+        }
+        for ind_face in 0..mesh.get_nb_faces() {
+            mesh.set_face_property_value(
+                ind_face,
+                "group".to_string(),
+                Property::Short(-(ind_face as i16)),
+            )?;
+            mesh.set_face_property_value(
+                ind_face,
+                "weights".to_string(),
+                Property::ListDouble(vec![0.5; ind_face % 3]),
+            )?;
+        }
+
+        let filename = std::env::temp_dir()
+            .join("skeletal_structures_round_trip_test.ply")
+            .to_string_lossy()
+            .to_string();
+        for encoding in [
+            Encoding::Ascii,
+            Encoding::BinaryLittleEndian,
+            Encoding::BinaryBigEndian,
+        ] {
+            save_mesh_ply_with_encoding(&filename, &mesh, None, encoding)?;
+            let loaded = load_mesh_ply(&filename)?;
+            assert!(loaded.vertices == mesh.vertices);
+            assert!(loaded.faces == mesh.faces);
+            for (properties, loaded_properties) in [
+                (mesh.get_vertex_properties(), loaded.get_vertex_properties()),
+                (mesh.get_face_properties(), loaded.get_face_properties()),
+            ] {
+                assert!(loaded_properties.get_property_names() == properties.get_property_names());
+                assert!(loaded_properties.get_nb_elements() == properties.get_nb_elements());
+                for name in properties.get_property_names() {
+                    assert!(
+                        loaded_properties.get_property_type(&name)?
+                            == properties.get_property_type(&name)?
+                    );
+                    for ind in 0..properties.get_nb_elements() {
+                        assert!(
+                            loaded_properties.get_property_value(ind, name.clone())?
+                                == properties.get_property_value(ind, name.clone())?
+                        );
+                    }
+                }
+            }
+        }
+
+        // other common schema, and point cloud without faces
+        std::fs::write(
+            &filename,
+            "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\n\
+             property float z\nelement face 1\nproperty list uchar int vertex_index\n\
+             end_header\n0 0 0\n1 0 0\n0 1 0.5\n3 0 1 2\n",
+        )?;
+        let loaded = load_mesh_ply(&filename)?;
+        assert!(loaded.get_vertex(2)? == Vector3::new(0., 1., 0.5));
+        assert!(*loaded.get_face(0)? == vec![0, 1, 2]);
+        std::fs::write(
+            &filename,
+            "ply\nformat ascii 1.0\nelement vertex 2\nproperty double x\nproperty double y\n\
+             property double z\nend_header\n0 0 0\n1 0 0\n",
+        )?;
+        let loaded = load_mesh_ply(&filename)?;
+        assert!(loaded.get_nb_vertices() == 2 && loaded.get_nb_faces() == 0);
+        std::fs::write(
+            &filename,
+            "ply\nformat ascii 1.0\nelement vertex 1\nproperty double x\nproperty double y\n\
+             property double z\nelement face 1\nproperty list uchar int vertex_index\n\
+             end_header\n0 0 0\n3 0 1 2\n",
+        )?;
+        assert!(load_mesh_ply(&filename).is_err());
+        std::fs::remove_file(&filename)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn stl_test() -> Result<()> {
+        let mesh = build_cube()?;
+        let filename = std::env::temp_dir()
+            .join("skeletal_structures_stl_test.stl")
+            .to_string_lossy()
+            .to_string();
+        for binary in [false, true] {
+            save_mesh_stl(&filename, &mesh, binary)?;
+            let loaded = load_mesh_stl(&filename)?;
+
+            // shared corners are merged back
+            assert!(loaded.get_nb_vertices() == 8);
+            assert!(loaded.get_nb_faces() == 12);
+            assert!((loaded.enclosed_volume()? - 8.).abs() < 1e-9);
+            for ind_face in 0..mesh.get_nb_faces() {
+                for i in 0..3 {
+                    assert!(
+                        loaded.get_vertex(loaded.get_face(ind_face)?[i])?
+                            == mesh.get_vertex(mesh.get_face(ind_face)?[i])?
+                    );
+                }
+            }
+            let simpl = loaded.to_simplicial2(false)?;
+            assert!(simplicial2_is_valid(&simpl)?);
+        }
+
+        // ascii file with -0 merged with 0, and a facet degenerated by merging
+        std::fs::write(
+            &filename,
+            "solid test\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\n\
+             vertex 0 1 0\nendloop\nendfacet\nfacet normal 0 0 1\nouter loop\n\
+             vertex 0 0 -0\nvertex 0 0 0\nvertex 5 5 5\nendloop\nendfacet\n\
+             facet normal 0 0 1\nouter loop\n\
+             vertex 1 0 -0\nvertex 1 1 0\nvertex 0 1 0\nendloop\nendfacet\nendsolid test\n",
+        )?;
+        let loaded = load_mesh_stl(&filename)?;
+        assert!(loaded.get_nb_vertices() == 4);
+        assert!(loaded.get_nb_faces() == 2);
+        assert!(*loaded.get_face(1)? == vec![1, 3, 2]);
+        assert!(simplicial2_is_valid(&loaded.to_simplicial2(false)?)?);
+        std::fs::write(&filename, "solid test\nouter loop\nvertex 0 0 0\nendloop\n")?;
+        assert!(load_mesh_stl(&filename).is_err());
+        std::fs::remove_file(&filename)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn obj_test() -> Result<()> {
+        let filename = std::env::temp_dir()
+            .join("skeletal_structures_obj_test.obj")
+            .to_string_lossy()
+            .to_string();
+        // quad and triangle, relative indices, normals, texture coordinates, groups and materials
+        std::fs::write(
+            &filename,
+            "# comment\nmtllib test.mtl\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0 # corner\n\
+             vt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\nvn 0 0 1\ng top side\nusemtl red\n\
+             f 1/1/1 2/2/1 3/3/1 4/4/1\nv 0.5 0.5 1\ng apex\nf -4//-1 -3//-1 \\\n-1//-1\n",
+        )?;
+        let mesh = load_mesh_obj(&filename)?;
+        assert!(mesh.get_nb_vertices() == 5);
+        assert!(*mesh.get_face(0)? == vec![0, 1, 2, 3]);
+        assert!(*mesh.get_face(1)? == vec![1, 2, 4]);
+        assert!(
+            mesh.get_face_properties()
+                .get_property_value(0, OBJ_CORNER_TEXTURE_PROPERTY.to_string())?
+                == Property::ListDouble(vec![0., 0., 1., 0., 1., 1., 0., 1.])
+        );
+        assert!(
+            mesh.get_face_properties()
+                .get_property_value(1, OBJ_CORNER_TEXTURE_PROPERTY.to_string())?
+                == Property::ListDouble(Vec::new())
+        );
+        assert!(
+            mesh.get_face_properties()
+                .get_property_value(1, OBJ_CORNER_NORMAL_PROPERTY.to_string())?
+                == Property::ListDouble([0., 0., 1.].repeat(3))
+        );
+        assert!(!mesh.get_vertex_properties().has_property("nz"));
+        assert!(
+            mesh.get_face_properties()
+                .get_property_value(0, OBJ_GROUP_PROPERTY.to_string())?
+                == Property::ListUChar(b"top side".to_vec())
+        );
+        assert!(
+            mesh.get_face_properties()
+                .get_property_value(1, OBJ_GROUP_PROPERTY.to_string())?
+                == Property::ListUChar(b"apex".to_vec())
+        );
+        assert!(
+            mesh.get_face_properties()
+                .get_property_value(1, OBJ_MATERIAL_PROPERTY.to_string())?
+                == Property::ListUChar(b"red".to_vec())
+        );
+
+        // round trip keeps geometry and properties
+        save_mesh_obj(&filename, &mesh)?;
+        let loaded = load_mesh_obj(&filename)?;
+        assert!(loaded.vertices == mesh.vertices);
+        assert!(loaded.faces == mesh.faces);
+        for (properties, loaded_properties) in [
+            (mesh.get_vertex_properties(), loaded.get_vertex_properties()),
+            (mesh.get_face_properties(), loaded.get_face_properties()),
+        ] {
+            assert!(loaded_properties.get_property_names() == properties.get_property_names());
+            for name in properties.get_property_names() {
+                for ind in 0..properties.get_nb_elements() {
+                    assert!(
+                        loaded_properties.get_property_value(ind, name.clone())?
+                            == properties.get_property_value(ind, name.clone())?
+                    );
+                }
+            }
+        }
+
+        // plain triangle mesh
+        let cube = build_cube()?;
+        save_mesh_obj(&filename, &cube)?;
+        let loaded = load_mesh_obj(&filename)?;
+        assert!(loaded.vertices == cube.vertices && loaded.faces == cube.faces);
+        assert!(loaded.get_face_properties().get_property_names() == vec!["vertex_indices"]);
+
+        // cube with per face normals: corners of a vertex have different normals
+        std::fs::write(
+            &filename,
+            "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 0 0 1\nv 1 0 1\nv 1 1 1\nv 0 1 1\n\
+             vn 0 0 -1\nvn 0 0 1\nvn 0 -1 0\nvn 1 0 0\nvn 0 1 0\nvn -1 0 0\n\
+             f 1//1 4//1 3//1 2//1\nf 5//2 6//2 7//2 8//2\nf 1//3 2//3 6//3 5//3\n\
+             f 2//4 3//4 7//4 6//4\nf 3//5 4//5 8//5 7//5\nf 4//6 1//6 5//6 8//6\n",
+        )?;
+        let mesh = load_mesh_obj(&filename)?;
+        assert!(mesh.get_nb_vertices() == 8 && mesh.get_nb_faces() == 6);
+        assert!(
+            mesh.get_face_properties()
+                .get_property_value(0, OBJ_CORNER_NORMAL_PROPERTY.to_string())?
+                == Property::ListDouble([0., 0., -1.].repeat(4))
+        );
+        assert!(
+            mesh.get_face_properties()
+                .get_property_value(3, OBJ_CORNER_NORMAL_PROPERTY.to_string())?
+                == Property::ListDouble([1., 0., 0.].repeat(4))
+        );
+        assert!(!mesh
+            .get_face_properties()
+            .has_property(OBJ_CORNER_TEXTURE_PROPERTY));
+        save_mesh_obj(&filename, &mesh)?;
+        let content = std::fs::read_to_string(&filename)?;
+        assert!(
+            content
+                .lines()
+                .filter(|line| line.starts_with("vn "))
+                .count()
+                == 6
+        );
+        let loaded = load_mesh_obj(&filename)?;
+        assert!(loaded.vertices == mesh.vertices && loaded.faces == mesh.faces);
+        for ind_face in 0..mesh.get_nb_faces() {
+            assert!(
+                loaded
+                    .get_face_properties()
+                    .get_property_value(ind_face, OBJ_CORNER_NORMAL_PROPERTY.to_string())?
+                    == mesh
+                        .get_face_properties()
+                        .get_property_value(ind_face, OBJ_CORNER_NORMAL_PROPERTY.to_string())?
+            );
+        }
+
+        std::fs::write(&filename, "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 0\n")?;
+        assert!(load_mesh_obj(&filename).is_err());
+        std::fs::write(
+            &filename,
+            "v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 1\nf 1//1 2 3\n",
+        )?;
+        assert!(load_mesh_obj(&filename).is_err());
+        std::fs::write(&filename, "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 -4\n")?;
+        assert!(load_mesh_obj(&filename).is_err());
+        std::fs::remove_file(&filename)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn off_test() -> Result<()> {
+        let filename = std::env::temp_dir()
+            .join("skeletal_structures_off_test.off")
+            .to_string_lossy()
+            .to_string();
+        let color = |mesh: &Mesh3D, is_face: bool, ind: usize| -> Result<Vec<f64>> {
+            let properties = if is_face {
+                mesh.get_face_properties()
+            } else {
+                mesh.get_vertex_properties()
+            };
+            ["red", "green", "blue", "alpha"]
+                .iter()
+                .map(|name| properties.get_property_value_f64(ind, name))
+                .collect()
+        };
+
+        // fewer faces than vertices, comments, polygon and per-face colors
+        std::fs::write(
+            &filename,
+            "OFF # square pyramid\n\n# counts\n5 4 8\n0 0 0\n1 0 0\n1 1 0\n0 1 0\n\
+             0.5 0.5 1\n4 3 2 1 0 255 0 0\n3 0 1 4 0 0.5 1 0.5\n3 1 2 4\n3 2 3 4\n",
+        )?;
+        let mesh = load_mesh_off(&filename)?;
+        assert!(mesh.get_nb_vertices() == 5 && mesh.get_nb_faces() == 4);
+        assert!(*mesh.get_face(0)? == vec![3, 2, 1, 0]);
+        assert!(!mesh.get_vertex_properties().has_property("red"));
+        assert!(color(&mesh, true, 0)? == vec![255., 0., 0., 255.]);
+        assert!(color(&mesh, true, 1)? == vec![0., 128., 255., 128.]);
+        assert!(color(&mesh, true, 2)? == vec![0., 0., 0., 255.]);
+
+        // variants, with counts on the header line
+        std::fs::write(
+            &filename,
+            "NOFF 3 1 3\n0 0 0 0 0 1\n1 0 0 0 0 1\n0 1 0 0 0 -1\n3 0 1 2\n",
+        )?;
+        let mesh = load_mesh_off(&filename)?;
+        assert!(mesh.get_vertex_property_value_f64(2, "nz".to_string())? == -1.);
+        std::fs::write(
+            &filename,
+            "COFF\n3 1 3\n0 0 0 255 0 0 255\n1 0 0 0 255 0\n0 1 0 0 0 1 0.5\n3 0 1 2\n",
+        )?;
+        let mesh = load_mesh_off(&filename)?;
+        assert!(color(&mesh, false, 1)? == vec![0., 255., 0., 255.]);
+        assert!(color(&mesh, false, 2)? == vec![0., 0., 255., 128.]);
+        // colormap indices give no color
+        std::fs::write(
+            &filename,
+            "COFF\n3 1 3\n0 0 0 7\n1 0 0 0 255 0\n0 1 0 2\n3 0 1 2 4\n",
+        )?;
+        let mesh = load_mesh_off(&filename)?;
+        assert!(color(&mesh, false, 0)? == vec![0., 0., 0., 255.]);
+        assert!(color(&mesh, false, 1)? == vec![0., 255., 0., 255.]);
+        assert!(!mesh.get_face_properties().has_property("red"));
+        std::fs::write(
+            &filename,
+            "STCNOFF\n3 1 3\n0 0 0 0 0 1 10 20 30 0 0\n1 0 0 0 0 1 40 50 60 70 1 0\n\
+             0 1 0 0 0 1 1 1 1 0 1\n3 0 1 2 0 0 255\n",
+        )?;
+        let mesh = load_mesh_off(&filename)?;
+        assert!(mesh.get_vertex_property_value_f64(1, "nz".to_string())? == 1.);
+        assert!(mesh.get_vertex_property_value_f64(1, "u".to_string())? == 1.);
+        assert!(mesh.get_vertex_property_value_f64(2, "v".to_string())? == 1.);
+        assert!(color(&mesh, false, 0)? == vec![10., 20., 30., 255.]);
+        assert!(color(&mesh, false, 1)? == vec![40., 50., 60., 70.]);
+        assert!(color(&mesh, true, 0)? == vec![0., 0., 255., 255.]);
+
+        // round trip keeps geometry and properties
+        save_mesh_off(&filename, &mesh)?;
+        assert!(std::fs::read_to_string(&filename)?.starts_with("STCNOFF\n3 1 3\n"));
+        let loaded = load_mesh_off(&filename)?;
+        assert!(loaded.vertices == mesh.vertices);
+        assert!(loaded.faces == mesh.faces);
+        for (properties, loaded_properties) in [
+            (mesh.get_vertex_properties(), loaded.get_vertex_properties()),
+            (mesh.get_face_properties(), loaded.get_face_properties()),
+        ] {
+            assert!(loaded_properties.get_property_names() == properties.get_property_names());
+            for name in properties.get_property_names() {
+                for ind in 0..properties.get_nb_elements() {
+                    assert!(
+                        loaded_properties.get_property_value(ind, name.clone())?
+                            == properties.get_property_value(ind, name.clone())?
+                    );
+                }
+            }
+        }
+        let cube = build_cube()?;
+        save_mesh_off(&filename, &cube)?;
+        assert!(std::fs::read_to_string(&filename)?.starts_with("OFF\n8 12 18\n"));
+        let loaded = load_mesh_off(&filename)?;
+        assert!(loaded.vertices == cube.vertices && loaded.faces == cube.faces);
+
+        std::fs::write(&filename, "4OFF\n1 0 0\n0 0 0 0\n")?;
+        assert!(load_mesh_off(&filename).is_err());
+        std::fs::write(&filename, "OFF\n3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 3\n")?;
+        assert!(load_mesh_off(&filename).is_err());
+        std::fs::write(&filename, "OFF\n3 2 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n")?;
+        assert!(load_mesh_off(&filename).is_err());
+        std::fs::write(
+            &filename,
+            "OFF\n3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2 255 0\n",
+        )?;
+        assert!(load_mesh_off(&filename).is_err());
+        std::fs::remove_file(&filename)?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,198 @@
+use anyhow::Result;
+use nalgebra::base::*;
+use std::f64::consts::PI;
+
+use super::mesh3d::Mesh3D;
+
+/// Maximal number of triangles in a leaf of winding number tree
+const LEAF_SIZE: usize = 8;
+
+/// Gets mesh triangles (polygons are split as fans)
+fn mesh_triangles(mesh: &Mesh3D) -> Result<Vec<[Vector3<f64>; 3]>> {
+    let mut triangles = Vec::with_capacity(mesh.get_nb_faces());
+    for ind_face in 0..mesh.get_nb_faces() {
+        let face = mesh.get_face(ind_face)?;
+        let v0 = mesh.get_vertex(face[0])?;
+        for i in 1..face.len().saturating_sub(1) {
+            triangles.push([v0, mesh.get_vertex(face[i])?, mesh.get_vertex(face[i + 1])?]);
+        }
+    }
+    Ok(triangles)
+}
+
+/// Area weighted normal of a triangle
+fn area_vector(triangle: &[Vector3<f64>; 3]) -> Vector3<f64> {
+    0.5 * (triangle[1] - triangle[0]).cross(&(triangle[2] - triangle[0]))
+}
+
+/// Barycenter of a triangle
+fn barycenter(triangle: &[Vector3<f64>; 3]) -> Vector3<f64> {
+    (triangle[0] + triangle[1] + triangle[2]) / 3.
+}
+
+/// Signed solid angle of a triangle seen from a point (Van Oosterom and Strackee formula)
+fn solid_angle(triangle: &[Vector3<f64>; 3], point: &Vector3<f64>) -> f64 {
+    let a = triangle[0] - point;
+    let b = triangle[1] - point;
+    let c = triangle[2] - point;
+    let (la, lb, lc) = (a.norm(), b.norm(), c.norm());
+    let numerator = a.dot(&b.cross(&c));
+    let denominator = la * lb * lc + a.dot(&b) * lc + b.dot(&c) * la + c.dot(&a) * lb;
+    2. * numerator.atan2(denominator)
+}
+
+/// Computes generalized winding number of a point with respect to mesh faces
+///
+/// Winding number is 1 inside a closed mesh with faces oriented counterclockwise from
+/// outside, 0 outside, and varies smoothly for meshes with holes or self-intersections.
+/// Complexity is linear in the number of faces, see [`WindingNumberTree`] for many queries.
+pub fn winding_number(mesh: &Mesh3D, point: &Vector3<f64>) -> Result<f64> {
+    Ok(mesh_triangles(mesh)?
+        .iter()
+        .map(|triangle| solid_angle(triangle, point))
+        .sum::<f64>()
+        / (4. * PI))
+}
+
+/// Node of winding number tree
+struct WindingNumberNode {
+    /// Area weighted centroid of node triangles
+    center: Vector3<f64>,
+    /// Distance from center to farthest triangle vertex
+    radius: f64,
+    /// Sum of area weighted normals of node triangles
+    dipole: Vector3<f64>,
+    /// Second order term, sum of area weighted (triangle centroid - center) * normal^T
+    quadrupole: Matrix3<f64>,
+    /// Range of node triangles
+    first_triangle: usize,
+    last_triangle: usize,
+    /// Child nodes (None for leaves)
+    children: Option<[usize; 2]>,
+}
+
+/// Hierarchical approximation of generalized winding number
+///
+/// Triangles are stored in a binary tree. Far from a node (compared to its radius), the
+/// contribution of its triangles is approximated by a second order expansion at their centroid.
+pub struct WindingNumberTree {
+    triangles: Vec<[Vector3<f64>; 3]>,
+    nodes: Vec<WindingNumberNode>,
+    accuracy: f64,
+}
+
+impl WindingNumberTree {
+    /// Builds tree from mesh faces
+    ///
+    /// Node approximation is used when query point is farther than accuracy times the node
+    /// radius (accuracy has to be greater than 1, 2 is a good tradeoff).
+    pub fn new(mesh: &Mesh3D, accuracy: f64) -> Result<WindingNumberTree> {
+        if accuracy <= 1. {
+            return Err(anyhow::Error::msg("Accuracy has to be greater than 1"));
+        }
+        let mut tree = WindingNumberTree {
+            triangles: mesh_triangles(mesh)?,
+            nodes: Vec::new(),
+            accuracy,
+        };
+        if !tree.triangles.is_empty() {
+            tree.build_node(0, tree.triangles.len());
+        }
+        Ok(tree)
+    }
+
+    /// Builds node from range of triangles, returns node index
+    fn build_node(&mut self, first_triangle: usize, last_triangle: usize) -> usize {
+        let triangles = &mut self.triangles[first_triangle..last_triangle];
+
+        let mut dipole = Vector3::zeros();
+        let mut center = Vector3::zeros();
+        let mut total_area = 0.;
+        for triangle in triangles.iter() {
+            let area_vector = area_vector(triangle);
+            let area = area_vector.norm();
+            dipole += area_vector;
+            center += area * barycenter(triangle);
+            total_area += area;
+        }
+        center = if total_area > 0. {
+            center / total_area
+        } else {
+            triangles.iter().map(barycenter).sum::<Vector3<f64>>() / triangles.len() as f64
+        };
+        let mut quadrupole = Matrix3::zeros();
+        for triangle in triangles.iter() {
+            quadrupole += (barycenter(triangle) - center) * area_vector(triangle).transpose();
+        }
+        let radius = triangles
+            .iter()
+            .flat_map(|triangle| triangle.iter())
+            .map(|vertex| (vertex - center).norm())
+            .fold(0., f64::max);
+
+        let ind_node = self.nodes.len();
+        self.nodes.push(WindingNumberNode {
+            center,
+            radius,
+            dipole,
+            quadrupole,
+            first_triangle,
+            last_triangle,
+            children: None,
+        });
+
+        if triangles.len() > LEAF_SIZE {
+            // split along largest extent of triangle barycenters
+            let (min, max) = triangles.iter().map(barycenter).fold(
+                (Vector3::repeat(f64::MAX), Vector3::repeat(f64::MIN)),
+                |(min, max), bary| (min.inf(&bary), max.sup(&bary)),
+            );
+            let axis = (max - min).imax();
+            let middle = triangles.len() / 2;
+            triangles.select_nth_unstable_by(middle, |t0, t1| {
+                barycenter(t0)[axis].total_cmp(&barycenter(t1)[axis])
+            });
+
+            let child0 = self.build_node(first_triangle, first_triangle + middle);
+            let child1 = self.build_node(first_triangle + middle, last_triangle);
+            self.nodes[ind_node].children = Some([child0, child1]);
+        }
+
+        ind_node
+    }
+
+    /// Gets approximated winding number of a point
+    pub fn winding_number(&self, point: &Vector3<f64>) -> f64 {
+        if self.nodes.is_empty() {
+            return 0.;
+        }
+        let mut total = 0.;
+        let mut to_visit = vec![0];
+        while let Some(ind_node) = to_visit.pop() {
+            let node = &self.nodes[ind_node];
+            let direction = node.center - point;
+            let distance = direction.norm();
+            if distance > self.accuracy * node.radius {
+                // Taylor expansion of solid angles around node center
+                let distance3 = distance * distance * distance;
+                total += node.dipole.dot(&direction) / distance3;
+                total += node.quadrupole.trace() / distance3
+                    - 3. * direction.dot(&(node.quadrupole * direction))
+                        / (distance3 * distance * distance);
+            } else if let Some(children) = node.children {
+                to_visit.extend(children);
+            } else {
+                total += self.triangles[node.first_triangle..node.last_triangle]
+                    .iter()
+                    .map(|triangle| solid_angle(triangle, point))
+                    .sum::<f64>();
+            }
+        }
+        total / (4. * PI)
+    }
+
+    /// Checks if a point is inside mesh (winding number greater than 1/2)
+    pub fn is_inside(&self, point: &Vector3<f64>) -> bool {
+        self.winding_number(point) > 0.5
+    }
+}
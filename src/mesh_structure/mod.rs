@@ -1,8 +1,17 @@
 /// Mesh 3D
 pub mod mesh3d;
 
+/// Tetrahedral mesh 3D
+pub mod tetmesh3d;
+
 /// Skeleton 2D
 pub mod skeleton2d;
 
 /// Property set describing vertices, faces, edges, ...
 pub mod property_set;
+
+/// Gmsh file parser, shared by meshes
+mod gmsh;
+
+/// Ply file writer, shared by meshes
+mod ply_writer;
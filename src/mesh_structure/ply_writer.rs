@@ -0,0 +1,136 @@
+use anyhow::Result;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use ply_rs::ply::{DefaultElement, Encoding, Ply, Property, PropertyType, ScalarType};
+use ply_rs::writer::Writer;
+
+/// Gets scalar type of a property (list item type for list properties)
+fn scalar_type(property: &Property) -> ScalarType {
+    match property {
+        Property::Char(_) | Property::ListChar(_) => ScalarType::Char,
+        Property::UChar(_) | Property::ListUChar(_) => ScalarType::UChar,
+        Property::Short(_) | Property::ListShort(_) => ScalarType::Short,
+        Property::UShort(_) | Property::ListUShort(_) => ScalarType::UShort,
+        Property::Int(_) | Property::ListInt(_) => ScalarType::Int,
+        Property::UInt(_) | Property::ListUInt(_) => ScalarType::UInt,
+        Property::Float(_) | Property::ListFloat(_) => ScalarType::Float,
+        Property::Double(_) | Property::ListDouble(_) => ScalarType::Double,
+    }
+}
+
+/// Writes a property in binary, checking it against its definition
+fn write_binary_property<W: Write>(
+    out: &mut W,
+    property: &Property,
+    property_type: &PropertyType,
+    big_endian: bool,
+) -> Result<()> {
+    macro_rules! write_value {
+        ($value:expr) => {
+            if big_endian {
+                out.write_all(&$value.to_be_bytes())?
+            } else {
+                out.write_all(&$value.to_le_bytes())?
+            }
+        };
+    }
+
+    let (is_list, item_type) = match property_type {
+        PropertyType::Scalar(item_type) => (false, item_type),
+        PropertyType::List(_, item_type) => (true, item_type),
+    };
+    let value_is_list = !matches!(
+        property,
+        Property::Char(_)
+            | Property::UChar(_)
+            | Property::Short(_)
+            | Property::UShort(_)
+            | Property::Int(_)
+            | Property::UInt(_)
+            | Property::Float(_)
+            | Property::Double(_)
+    );
+    if is_list != value_is_list || *item_type != scalar_type(property) {
+        return Err(anyhow::Error::msg(
+            "Property value does not match its definition",
+        ));
+    }
+
+    macro_rules! write_list {
+        ($list:expr) => {{
+            let length = $list.len();
+            match property_type {
+                PropertyType::List(ScalarType::Char, _) => write_value!(i8::try_from(length)?),
+                PropertyType::List(ScalarType::UChar, _) => write_value!(u8::try_from(length)?),
+                PropertyType::List(ScalarType::Short, _) => write_value!(i16::try_from(length)?),
+                PropertyType::List(ScalarType::UShort, _) => {
+                    write_value!(u16::try_from(length)?)
+                }
+                PropertyType::List(ScalarType::Int, _) => write_value!(i32::try_from(length)?),
+                PropertyType::List(ScalarType::UInt, _) => write_value!(u32::try_from(length)?),
+                _ => return Err(anyhow::Error::msg("List index type has to be an integer")),
+            }
+            for value in $list.iter() {
+                write_value!(value);
+            }
+        }};
+    }
+
+    match property {
+        Property::Char(value) => write_value!(value),
+        Property::UChar(value) => write_value!(value),
+        Property::Short(value) => write_value!(value),
+        Property::UShort(value) => write_value!(value),
+        Property::Int(value) => write_value!(value),
+        Property::UInt(value) => write_value!(value),
+        Property::Float(value) => write_value!(value),
+        Property::Double(value) => write_value!(value),
+        Property::ListChar(list) => write_list!(list),
+        Property::ListUChar(list) => write_list!(list),
+        Property::ListShort(list) => write_list!(list),
+        Property::ListUShort(list) => write_list!(list),
+        Property::ListInt(list) => write_list!(list),
+        Property::ListUInt(list) => write_list!(list),
+        Property::ListFloat(list) => write_list!(list),
+        Property::ListDouble(list) => write_list!(list),
+    }
+    Ok(())
+}
+
+/// Writes a ply file with the encoding of its header
+///
+/// Binary payloads are not written by ply-rs, whose binary writer puts the number of
+/// elements instead of the list length in front of list properties.
+pub(crate) fn write_ply(filename: &str, ply: &mut Ply<DefaultElement>) -> Result<()> {
+    ply.make_consistent()?;
+
+    let mut file = BufWriter::new(File::create(filename)?);
+    let writer = Writer::<DefaultElement>::new();
+    let big_endian = match ply.header.encoding {
+        Encoding::Ascii => {
+            writer.write_ply(&mut file, ply)?;
+            file.flush()?;
+            return Ok(());
+        }
+        Encoding::BinaryBigEndian => true,
+        Encoding::BinaryLittleEndian => false,
+    };
+
+    writer.write_header(&mut file, &ply.header)?;
+    for (element_name, element_def) in ply.header.elements.iter() {
+        let Some(elements) = ply.payload.get(element_name) else {
+            continue;
+        };
+        for element in elements.iter() {
+            for (property_name, property_def) in element_def.properties.iter() {
+                let property = element.get(property_name).ok_or(anyhow::Error::msg(
+                    "No property ".to_owned() + property_name + " in " + element_name,
+                ))?;
+                write_binary_property(&mut file, property, &property_def.data_type, big_endian)?;
+            }
+        }
+    }
+    file.flush()?;
+    Ok(())
+}
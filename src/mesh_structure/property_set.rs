@@ -2,7 +2,62 @@ use anyhow::Result;
 
 use std::collections::HashMap;
 
-use ply_rs::ply::{Addable, DefaultElement, ElementDef, Property, PropertyDef, PropertyType};
+use ply_rs::ply::{
+    Addable, DefaultElement, ElementDef, Property, PropertyDef, PropertyType, ScalarType,
+};
+
+/// Converts a scalar property to f64 (None for list properties)
+pub fn property_to_f64(property: &Property) -> Option<f64> {
+    match *property {
+        Property::Char(v) => Some(v as f64),
+        Property::UChar(v) => Some(v as f64),
+        Property::Short(v) => Some(v as f64),
+        Property::UShort(v) => Some(v as f64),
+        Property::Int(v) => Some(v as f64),
+        Property::UInt(v) => Some(v as f64),
+        Property::Float(v) => Some(v as f64),
+        Property::Double(v) => Some(v),
+        _ => None,
+    }
+}
+
+/// Converts an integer list property to indices (None for other properties or negative values)
+pub fn property_to_indices(property: &Property) -> Option<Vec<usize>> {
+    fn convert<T: Copy + TryInto<usize>>(list: &[T]) -> Option<Vec<usize>> {
+        list.iter().map(|&v| v.try_into().ok()).collect()
+    }
+    match property {
+        Property::ListChar(list) => convert(list),
+        Property::ListUChar(list) => convert(list),
+        Property::ListShort(list) => convert(list),
+        Property::ListUShort(list) => convert(list),
+        Property::ListInt(list) => convert(list),
+        Property::ListUInt(list) => convert(list),
+        _ => None,
+    }
+}
+
+/// Gets default value of a property type (zero or empty list)
+pub fn default_property(property_type: &PropertyType) -> Property {
+    match property_type {
+        PropertyType::Scalar(ScalarType::Char) => Property::Char(0),
+        PropertyType::Scalar(ScalarType::UChar) => Property::UChar(0),
+        PropertyType::Scalar(ScalarType::Short) => Property::Short(0),
+        PropertyType::Scalar(ScalarType::UShort) => Property::UShort(0),
+        PropertyType::Scalar(ScalarType::Int) => Property::Int(0),
+        PropertyType::Scalar(ScalarType::UInt) => Property::UInt(0),
+        PropertyType::Scalar(ScalarType::Float) => Property::Float(0.),
+        PropertyType::Scalar(ScalarType::Double) => Property::Double(0.),
+        PropertyType::List(_, ScalarType::Char) => Property::ListChar(Vec::new()),
+        PropertyType::List(_, ScalarType::UChar) => Property::ListUChar(Vec::new()),
+        PropertyType::List(_, ScalarType::Short) => Property::ListShort(Vec::new()),
+        PropertyType::List(_, ScalarType::UShort) => Property::ListUShort(Vec::new()),
+        PropertyType::List(_, ScalarType::Int) => Property::ListInt(Vec::new()),
+        PropertyType::List(_, ScalarType::UInt) => Property::ListUInt(Vec::new()),
+        PropertyType::List(_, ScalarType::Float) => Property::ListFloat(Vec::new()),
+        PropertyType::List(_, ScalarType::Double) => Property::ListDouble(Vec::new()),
+    }
+}
 
 /// Property set: save a set of properties attributed to a vertex, face, edge, ...
 #[derive(Clone)]
@@ -47,6 +102,58 @@ impl PropertySet {
             .insert(property_name.clone(), default_properties);
     }
 
+    /// Adds properties of a ply header element (with default values), except excluded ones
+    /// and already existing ones
+    pub fn add_header_properties(&mut self, header_element: &ElementDef, excluded: &[&str]) {
+        for (name, property_def) in header_element.properties.iter() {
+            if excluded.contains(&name.as_str()) || self.has_property(name) {
+                continue;
+            }
+            self.add_property(
+                name.clone(),
+                property_def.data_type.clone(),
+                default_property(&property_def.data_type),
+            );
+        }
+    }
+
+    /// Gets number of elements
+    pub fn get_nb_elements(&self) -> usize {
+        self.nb_elements
+    }
+
+    /// Checks if a property exists
+    pub fn has_property(&self, property_name: &str) -> bool {
+        self.property_type.contains_key(property_name)
+    }
+
+    /// Gets property names (sorted)
+    pub fn get_property_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.property_type.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Gets property type
+    pub fn get_property_type(&self, property_name: &str) -> Result<PropertyType> {
+        Ok(self
+            .property_type
+            .get(property_name)
+            .ok_or(anyhow::Error::msg(
+                "No property ".to_owned() + property_name + " in properties",
+            ))?
+            .0
+            .clone())
+    }
+
+    /// Gets a scalar property, converted to f64
+    pub fn get_property_value_f64(&self, ind_element: usize, property_name: &str) -> Result<f64> {
+        let property = self.get_property_value(ind_element, property_name.to_string())?;
+        property_to_f64(&property).ok_or(anyhow::Error::msg(
+            "Property ".to_owned() + property_name + " is not a scalar",
+        ))
+    }
+
     /// Push new element
     pub fn push_element(&mut self) -> () {
         self.nb_elements += 1;
@@ -63,6 +170,18 @@ impl PropertySet {
         }
     }
 
+    /// Keeps given elements (in given order), removing all others
+    pub fn keep_elements(&mut self, kept_elements: &[usize]) -> Result<()> {
+        if kept_elements.iter().any(|&ind| ind >= self.nb_elements) {
+            return Err(anyhow::Error::msg("Index out of bounds"));
+        }
+        for (_, vec) in self.property_value.iter_mut() {
+            *vec = kept_elements.iter().map(|&ind| vec[ind].clone()).collect();
+        }
+        self.nb_elements = kept_elements.len();
+        Ok(())
+    }
+
     /// Sets a property
     pub fn set_property_value(
         &mut self,
@@ -1,21 +1,35 @@
 use anyhow::Result;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
 
 use nalgebra::base::*;
 use ply_rs::parser::Parser;
-use ply_rs::ply::{Addable, DefaultElement, Encoding, Ply, Property};
-use ply_rs::writer::Writer;
-use std::fs::File;
+use ply_rs::ply::{Addable, DefaultElement, Encoding, Ply};
 
 use super::skeleton2d::Skeleton2D;
+use crate::mesh_structure::ply_writer::write_ply;
+use crate::mesh_structure::property_set::property_to_f64;
 
-/// Save a skeleton to a PLY file
+/// Save a skeleton to a PLY file (ascii encoding)
 pub fn save_skeleton2d_ply(
     filename: &str,
     skeleton: &Skeleton2D,
     header: Option<String>,
+) -> Result<()> {
+    save_skeleton2d_ply_with_encoding(filename, skeleton, header, Encoding::Ascii)
+}
+
+/// Save a skeleton to a PLY file with given encoding (ascii, binary little endian or big
+/// endian)
+pub fn save_skeleton2d_ply_with_encoding(
+    filename: &str,
+    skeleton: &Skeleton2D,
+    header: Option<String>,
+    encoding: Encoding,
 ) -> Result<()> {
     let mut ply = Ply::<DefaultElement>::new();
-    ply.header.encoding = Encoding::Ascii;
+    ply.header.encoding = encoding;
     if let Some(h) = header {
         ply.header.comments.push(h);
     }
@@ -34,15 +48,32 @@ pub fn save_skeleton2d_ply(
     ply.payload
         .insert("edge".to_string(), edges_payload_element);
 
-    ply.make_consistent().unwrap();
+    write_ply(filename, &mut ply)
+}
 
-    let mut file = File::create(filename)?;
-    let w = Writer::new();
-    w.write_ply(&mut file, &mut ply).unwrap();
-    Ok(())
+/// Accepted names of the edge properties storing vertex indices
+const PLY_EDGE_VERTICES: [[&str; 2]; 2] = [["v1", "v2"], ["vertex1", "vertex2"]];
+
+/// Converts an edge vertex property to an index
+fn edge_vertex(edge: &DefaultElement, name: &str) -> Result<usize> {
+    let value = edge
+        .get(name)
+        .and_then(property_to_f64)
+        .ok_or(anyhow::Error::msg(format!("No {} property in edge", name)))?;
+    if value < 0. || value.fract() != 0. {
+        return Err(anyhow::Error::msg(format!(
+            "Wrong {} property in edge",
+            name
+        )));
+    }
+    Ok(value as usize)
 }
 
-/// Load a skeleton from a PLY file
+/// Load a skeleton from a PLY file (ascii or binary)
+///
+/// Coordinates and radii may have any scalar type, and edge vertices may be stored in
+/// "v1"/"v2" or "vertex1"/"vertex2" properties of any integer type. Other properties are
+/// kept with their type.
 pub fn load_skeleton2d_ply(file_path: &str) -> Result<Skeleton2D> {
     let mut f = std::fs::File::open(file_path)?;
 
@@ -51,77 +82,122 @@ pub fn load_skeleton2d_ply(file_path: &str) -> Result<Skeleton2D> {
     let mut skeleton = Skeleton2D::new();
 
     // Load vertices
-    if !ply.payload.contains_key("vertex") {
-        return Err(anyhow::Error::msg("No vertex element in file"));
-    }
-    for v in ply.payload["vertex"].iter() {
-        let x = if let Some(x_prop) = v.get("x") {
-            if let &Property::Float(x) = x_prop {
-                x
-            } else {
-                return Err(anyhow::Error::msg("No x property in vertex"));
-            }
-        } else {
-            return Err(anyhow::Error::msg("No x property in vertex"));
+    let vertex_header = ply
+        .header
+        .elements
+        .get("vertex")
+        .ok_or(anyhow::Error::msg("No vertex element in file"))?;
+    skeleton
+        .vertex_properties
+        .add_header_properties(vertex_header, &["x", "y", "radius"]);
+    for v in ply.payload.get("vertex").into_iter().flatten() {
+        let scalar = |name: &str| {
+            v.get(name)
+                .and_then(property_to_f64)
+                .ok_or(anyhow::Error::msg(format!(
+                    "No {} property in vertex",
+                    name
+                )))
         };
-        let y = if let Some(y_prop) = v.get("y") {
-            if let &Property::Float(y) = y_prop {
-                y
-            } else {
-                return Err(anyhow::Error::msg("No y property in vertex"));
-            }
-        } else {
-            return Err(anyhow::Error::msg("No y property in vertex"));
-        };
-        let radius = if let Some(radius_prop) = v.get("radius") {
-            if let &Property::Float(radius) = radius_prop {
-                radius
-            } else {
-                return Err(anyhow::Error::msg("No radius property in vertex"));
-            }
-        } else {
-            return Err(anyhow::Error::msg("No radius property in vertex"));
-        };
-        let ind_vertex = skeleton.insert_vertex(Vector2::new(x as f64, y as f64), radius as f64)?;
-
-        for (key, prop) in v.into_iter() {
-            match (key.as_ref(), prop) {
-                ("x", _) => (),
-                ("y", _) => (),
-                ("radius", _) => (),
-                (k, p) => {
-                    skeleton.set_vertex_property_value(ind_vertex, k.to_string(), p.clone())?;
-                    ()
-                }
+        let ind_vertex =
+            skeleton.insert_vertex(Vector2::new(scalar("x")?, scalar("y")?), scalar("radius")?)?;
+
+        for (key, prop) in v.iter() {
+            if !["x", "y", "radius"].contains(&key.as_str()) {
+                skeleton.set_vertex_property_value(ind_vertex, key.clone(), prop.clone())?;
             }
         }
     }
 
     // Load edges
-    if !ply.payload.contains_key("edge") {
-        return Err(anyhow::Error::msg("No edge element in file"));
+    let edge_header = ply
+        .header
+        .elements
+        .get("edge")
+        .ok_or(anyhow::Error::msg("No edge element in file"))?;
+    let [name1, name2] = PLY_EDGE_VERTICES
+        .into_iter()
+        .find(|names| {
+            names
+                .iter()
+                .all(|name| edge_header.properties.contains_key(*name))
+        })
+        .ok_or(anyhow::Error::msg("No vertex properties in edge"))?;
+    let excluded: Vec<&str> = PLY_EDGE_VERTICES.iter().flatten().copied().collect();
+    skeleton
+        .edge_properties
+        .add_header_properties(edge_header, &excluded);
+    for e in ply.payload.get("edge").into_iter().flatten() {
+        let nb_edges = skeleton.nb_edges;
+        skeleton.insert_edge(edge_vertex(e, name1)?, edge_vertex(e, name2)?)?;
+        if skeleton.nb_edges == nb_edges {
+            // duplicate edge
+            continue;
+        }
+
+        for (key, prop) in e.iter() {
+            if !excluded.contains(&key.as_str()) {
+                skeleton
+                    .edge_properties
+                    .set_property_value(nb_edges, key.clone(), prop.clone())?;
+            }
+        }
     }
-    for e in ply.payload["edge"].iter() {
-        let vertex1 = if let Some(vertex1_prop) = e.get("vertex1") {
-            if let &Property::Int(vertex1) = vertex1_prop {
-                vertex1
-            } else {
-                return Err(anyhow::Error::msg("No vertex1 property in edge"));
+
+    Ok(skeleton)
+}
+
+/// Chains skeleton edges into polylines
+///
+/// Polylines stop at vertices whose degree is not 2, cycles are closed by repeating their
+/// first vertex.
+fn skeleton_polylines(skeleton: &Skeleton2D) -> Vec<Vec<usize>> {
+    let edges = &skeleton.edges;
+    let edge_key = |v1: usize, v2: usize| if v1 < v2 { [v1, v2] } else { [v2, v1] };
+    let mut visited = HashSet::new();
+    let mut polylines = Vec::new();
+
+    // branches and extremities first, so that only cycles remain for degree 2 vertices
+    let starts = (0..edges.len())
+        .filter(|&v| edges[v].len() != 2)
+        .chain((0..edges.len()).filter(|&v| edges[v].len() == 2));
+    for start in starts {
+        for &first in edges[start].iter() {
+            if !visited.insert(edge_key(start, first)) {
+                continue;
             }
-        } else {
-            return Err(anyhow::Error::msg("No vertex1 property in edge"));
-        };
-        let vertex2 = if let Some(vertex2_prop) = e.get("vertex2") {
-            if let &Property::Int(vertex2) = vertex2_prop {
-                vertex2
-            } else {
-                return Err(anyhow::Error::msg("No vertex2 property in edge"));
+            let mut polyline = vec![start, first];
+            let (mut prev, mut cur) = (start, first);
+            while edges[cur].len() == 2 && cur != start {
+                let next = if edges[cur][0] == prev {
+                    edges[cur][1]
+                } else {
+                    edges[cur][0]
+                };
+                if !visited.insert(edge_key(cur, next)) {
+                    break;
+                }
+                polyline.push(next);
+                (prev, cur) = (cur, next);
             }
-        } else {
-            return Err(anyhow::Error::msg("No vertex2 property in edge"));
-        };
-        skeleton.insert_edge(vertex1 as usize, vertex2 as usize)?;
+            polylines.push(polyline);
+        }
     }
+    polylines
+}
 
-    Ok(skeleton)
+/// Save a skeleton to an OBJ file, with edges chained into polylines (l elements)
+///
+/// Vertices are written with a zero z coordinate, radii and other properties are not saved.
+pub fn save_skeleton2d_obj(filename: &str, skeleton: &Skeleton2D) -> Result<()> {
+    let mut file = BufWriter::new(File::create(filename)?);
+    for vertex in skeleton.vertices.iter() {
+        writeln!(file, "v {} {} 0", vertex[0], vertex[1])?;
+    }
+    for polyline in skeleton_polylines(skeleton) {
+        let indices: Vec<String> = polyline.iter().map(|ind| (ind + 1).to_string()).collect();
+        writeln!(file, "l {}", indices.join(" "))?;
+    }
+    file.flush()?;
+    Ok(())
 }
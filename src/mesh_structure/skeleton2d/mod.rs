@@ -4,3 +4,5 @@ pub use skeleton2d::Skeleton2D;
 
 /// IO functions
 pub mod io;
+
+mod unit_tests;
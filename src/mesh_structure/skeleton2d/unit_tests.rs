@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod skeleton_test {
+    use anyhow::Result;
+    use nalgebra::base::*;
+    use ply_rs::ply::{Encoding, Property};
+
+    use super::super::io::{
+        load_skeleton2d_ply, save_skeleton2d_obj, save_skeleton2d_ply_with_encoding,
+    };
+    use super::super::Skeleton2D;
+
+    #[test]
+    fn ply_round_trip_test() -> Result<()> {
+        let mut skeleton = Skeleton2D::new();
+        skeleton.add_vertex_property_f32("quality".to_string(), 0.);
+        for ind in 0..5 {
+            let angle = ind as f64;
+            let ind_vertex =
+                skeleton.insert_vertex(Vector2::new(angle.cos(), angle.sin()), 0.1 * angle)?;
+            skeleton.set_vertex_property_f32(
+                ind_vertex,
+                "quality".to_string(),
+                ind_vertex as f32 / 7.,
+            )?;
+        }
+        for (v1, v2) in [(0, 1), (1, 2), (2, 3), (3, 4), (4, 0), (1, 3)] {
+            skeleton.insert_edge(v1, v2)?;
+        }
+
+        let filename = std::env::temp_dir()
+            .join("skeletal_structures_skeleton_test.ply")
+            .to_string_lossy()
+            .to_string();
+        for encoding in [
+            Encoding::Ascii,
+            Encoding::BinaryLittleEndian,
+            Encoding::BinaryBigEndian,
+        ] {
+            save_skeleton2d_ply_with_encoding(&filename, &skeleton, None, encoding)?;
+            let loaded = load_skeleton2d_ply(&filename)?;
+            assert!(loaded.get_nb_vertex() == skeleton.get_nb_vertex());
+            for ind_vertex in 0..skeleton.get_nb_vertex() {
+                assert!(
+                    loaded.get_vertex_coords(ind_vertex)?
+                        == skeleton.get_vertex_coords(ind_vertex)?
+                );
+                assert!(
+                    loaded.get_vertex_radius(ind_vertex)?
+                        == skeleton.get_vertex_radius(ind_vertex)?
+                );
+                assert!(
+                    loaded.get_vertex_neighbors(ind_vertex)?
+                        == skeleton.get_vertex_neighbors(ind_vertex)?
+                );
+                assert!(
+                    loaded.get_vertex_property_value_f32(ind_vertex, "quality".to_string())?
+                        == ind_vertex as f32 / 7.
+                );
+            }
+            assert!(
+                loaded.get_edge_properties().get_nb_elements()
+                    == skeleton.get_edge_properties().get_nb_elements()
+            );
+        }
+
+        // other common schema
+        std::fs::write(
+            &filename,
+            "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\n\
+             property float radius\nproperty uchar label\nelement edge 2\n\
+             property uint vertex1\nproperty uint vertex2\nend_header\n\
+             0 0 0.5 3\n1 0 0.25 4\n0 2 1 5\n0 1\n2 1\n",
+        )?;
+        let loaded = load_skeleton2d_ply(&filename)?;
+        assert!(loaded.get_nb_vertex() == 3);
+        assert!(loaded.get_vertex_coords(2)? == Vector2::new(0., 2.));
+        assert!(loaded.get_vertex_radius(1)? == 0.25);
+        assert!(loaded.get_vertex_neighbors(1)? == vec![0, 2]);
+        assert!(
+            loaded
+                .get_vertex_properties()
+                .get_property_value(2, "label".to_string())?
+                == Property::UChar(5)
+        );
+        std::fs::remove_file(&filename)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn obj_polylines_test() -> Result<()> {
+        // branch 0-1-2-3 and 1-4, separate cycle 5-6-7
+        let mut skeleton = Skeleton2D::new();
+        for ind in 0..8 {
+            skeleton.insert_vertex(Vector2::new(ind as f64, 0.5 * ind as f64), 1.)?;
+        }
+        let edges = [(0, 1), (1, 2), (2, 3), (1, 4), (5, 6), (6, 7), (7, 5)];
+        for (v1, v2) in edges {
+            skeleton.insert_edge(v1, v2)?;
+        }
+
+        let filename = std::env::temp_dir()
+            .join("skeletal_structures_skeleton_test.obj")
+            .to_string_lossy()
+            .to_string();
+        save_skeleton2d_obj(&filename, &skeleton)?;
+        let content = std::fs::read_to_string(&filename)?;
+        std::fs::remove_file(&filename)?;
+
+        assert!(
+            content
+                .lines()
+                .filter(|line| line.starts_with("v "))
+                .count()
+                == 8
+        );
+        assert!(content.contains("v 3 1.5 0\n"));
+        let polylines: Vec<Vec<usize>> = content
+            .lines()
+            .filter_map(|line| line.strip_prefix("l "))
+            .map(|line| {
+                line.split_whitespace()
+                    .map(|word| word.parse::<usize>().unwrap() - 1)
+                    .collect()
+            })
+            .collect();
+        assert!(polylines.len() == 4);
+        let mut written_edges: Vec<(usize, usize)> = polylines
+            .iter()
+            .flat_map(|polyline| polyline.windows(2).map(|pair| (pair[0], pair[1])))
+            .map(|(v1, v2)| (v1.min(v2), v1.max(v2)))
+            .collect();
+        written_edges.sort();
+        let mut expected_edges: Vec<(usize, usize)> = edges
+            .iter()
+            .map(|&(v1, v2)| (v1.min(v2), v1.max(v2)))
+            .collect();
+        expected_edges.sort();
+        assert!(written_edges == expected_edges);
+        assert!(polylines
+            .iter()
+            .any(|polyline| polyline.len() == 4 && polyline.first() == polyline.last()));
+
+        Ok(())
+    }
+}
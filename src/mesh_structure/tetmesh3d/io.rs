@@ -0,0 +1,448 @@
+use anyhow::Result;
+use nalgebra::base::*;
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::Path;
+
+use ply_rs::ply::{Property, PropertyType, ScalarType};
+
+use super::tetmesh3d::TetMesh3D;
+use crate::graph_structure::simplicial3::Simplicial3;
+use crate::mesh_structure::gmsh::{
+    read_gmsh, write_gmsh, GmshElement, GMSH_TETRAHEDRON, GMSH_TRIANGLE,
+};
+use crate::mesh_structure::property_set::PropertySet;
+
+/// Reads a TetGen file as lists of tokens (comments and empty lines removed)
+fn read_tetgen_lines(filename: &str) -> Result<Vec<Vec<String>>> {
+    let file = File::open(filename)?;
+    let mut lines = Vec::new();
+    for line in io::BufReader::new(file).lines() {
+        let line = line?;
+        let content = line.split('#').next().unwrap_or("");
+        let tokens: Vec<String> = content.split_whitespace().map(|s| s.to_string()).collect();
+        if !tokens.is_empty() {
+            lines.push(tokens);
+        }
+    }
+    Ok(lines)
+}
+
+fn parse_token<T: std::str::FromStr>(tokens: &[String], ind: usize) -> Result<T> {
+    tokens
+        .get(ind)
+        .ok_or(anyhow::Error::msg("Expected value"))?
+        .parse::<T>()
+        .map_err(|_| anyhow::Error::msg("Could not parse value ".to_owned() + &tokens[ind]))
+}
+
+/// Converts a TetGen index to vertex index
+fn vertex_index(index: i64, first_index: i64, nb_vertices: usize) -> Result<usize> {
+    let ind = index - first_index;
+    if ind < 0 || ind as usize >= nb_vertices {
+        return Err(anyhow::Error::msg("Vertex index out of bounds"));
+    }
+    Ok(ind as usize)
+}
+
+/// Loads TetGen files (basename.node, basename.ele, and basename.face if it exists)
+///
+/// Node attributes are stored as vertex properties attribute_0, attribute_1, ...,
+/// boundary markers as boundary_marker vertex and face properties, and region attributes as
+/// region tetrahedron property (region_0, region_1, ... if there are several).
+pub fn load_tetmesh_tetgen(basename: &str) -> Result<TetMesh3D> {
+    let mut mesh = TetMesh3D::new();
+
+    // load nodes
+    let lines = read_tetgen_lines(&(basename.to_owned() + ".node"))?;
+    let header = lines.first().ok_or(anyhow::Error::msg("Empty node file"))?;
+    let nb_nodes = parse_token::<usize>(header, 0)?;
+    let dimension = parse_token::<usize>(header, 1)?;
+    let nb_attributes = parse_token::<usize>(header, 2)?;
+    let has_marker = parse_token::<usize>(header, 3)? != 0;
+    if dimension != 3 {
+        return Err(anyhow::Error::msg("Only 3D node files are handled"));
+    }
+    if lines.len() < nb_nodes + 1 {
+        return Err(anyhow::Error::msg("Missing nodes in node file"));
+    }
+    for ind_attr in 0..nb_attributes {
+        mesh.add_vertex_property(
+            format!("attribute_{}", ind_attr),
+            PropertyType::Scalar(ScalarType::Double),
+            Property::Double(0.),
+        );
+    }
+    if has_marker {
+        mesh.add_vertex_property(
+            "boundary_marker".to_string(),
+            PropertyType::Scalar(ScalarType::Int),
+            Property::Int(0),
+        );
+    }
+    let first_index = if nb_nodes > 0 {
+        parse_token::<i64>(&lines[1], 0)?
+    } else {
+        0
+    };
+    for (ind_node, tokens) in lines[1..nb_nodes + 1].iter().enumerate() {
+        if parse_token::<i64>(tokens, 0)? != first_index + ind_node as i64 {
+            return Err(anyhow::Error::msg("Nodes are not numbered consecutively"));
+        }
+        let vertex = Vector3::new(
+            parse_token::<f64>(tokens, 1)?,
+            parse_token::<f64>(tokens, 2)?,
+            parse_token::<f64>(tokens, 3)?,
+        );
+        let ind_vertex = mesh.insert_vertex(vertex);
+        for ind_attr in 0..nb_attributes {
+            mesh.set_vertex_property_value(
+                ind_vertex,
+                format!("attribute_{}", ind_attr),
+                Property::Double(parse_token::<f64>(tokens, 4 + ind_attr)?),
+            )?;
+        }
+        if has_marker {
+            mesh.set_vertex_property_value(
+                ind_vertex,
+                "boundary_marker".to_string(),
+                Property::Int(parse_token::<i32>(tokens, 4 + nb_attributes)?),
+            )?;
+        }
+    }
+
+    // load tetrahedra
+    let lines = read_tetgen_lines(&(basename.to_owned() + ".ele"))?;
+    let header = lines
+        .first()
+        .ok_or(anyhow::Error::msg("Empty element file"))?;
+    let nb_tetrahedra = parse_token::<usize>(header, 0)?;
+    let nb_nodes_per_tetra = parse_token::<usize>(header, 1)?;
+    let nb_regions = parse_token::<usize>(header, 2)?;
+    if nb_nodes_per_tetra != 4 && nb_nodes_per_tetra != 10 {
+        return Err(anyhow::Error::msg("Wrong number of nodes per tetrahedron"));
+    }
+    if lines.len() < nb_tetrahedra + 1 {
+        return Err(anyhow::Error::msg("Missing tetrahedra in element file"));
+    }
+    let regions = region_names(nb_regions);
+    for name in regions.iter() {
+        mesh.add_tetrahedron_property(
+            name.clone(),
+            PropertyType::Scalar(ScalarType::Double),
+            Property::Double(0.),
+        );
+    }
+    let nb_vertices = mesh.get_nb_vertices();
+    for tokens in lines[1..nb_tetrahedra + 1].iter() {
+        // only corner nodes are kept for second order tetrahedra
+        let mut tetra = [0; 4];
+        for (i, ind) in tetra.iter_mut().enumerate() {
+            *ind = vertex_index(parse_token::<i64>(tokens, 1 + i)?, first_index, nb_vertices)?;
+        }
+        let ind_tetra = mesh.insert_tetrahedron(tetra)?;
+        for (ind_region, name) in regions.iter().enumerate() {
+            mesh.set_tetrahedron_property_value(
+                ind_tetra,
+                name.clone(),
+                Property::Double(parse_token::<f64>(
+                    tokens,
+                    1 + nb_nodes_per_tetra + ind_region,
+                )?),
+            )?;
+        }
+    }
+
+    // load faces
+    let face_filename = basename.to_owned() + ".face";
+    if Path::new(&face_filename).exists() {
+        let lines = read_tetgen_lines(&face_filename)?;
+        let header = lines.first().ok_or(anyhow::Error::msg("Empty face file"))?;
+        let nb_faces = parse_token::<usize>(header, 0)?;
+        let has_marker = parse_token::<usize>(header, 1)? != 0;
+        if lines.len() < nb_faces + 1 {
+            return Err(anyhow::Error::msg("Missing faces in face file"));
+        }
+        if has_marker {
+            mesh.add_face_property(
+                "boundary_marker".to_string(),
+                PropertyType::Scalar(ScalarType::Int),
+                Property::Int(0),
+            );
+        }
+        for tokens in lines[1..nb_faces + 1].iter() {
+            let mut face = [0; 3];
+            for (i, ind) in face.iter_mut().enumerate() {
+                *ind = vertex_index(parse_token::<i64>(tokens, 1 + i)?, first_index, nb_vertices)?;
+            }
+            let ind_face = mesh.insert_face(face)?;
+            if has_marker {
+                mesh.set_face_property_value(
+                    ind_face,
+                    "boundary_marker".to_string(),
+                    Property::Int(parse_token::<i32>(tokens, 4)?),
+                )?;
+            }
+        }
+    }
+
+    Ok(mesh)
+}
+
+/// Gets names of indexed properties (prefix_0, prefix_1, ...), in order
+fn indexed_names(properties: &PropertySet, prefix: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    while properties.has_property(&format!("{}_{}", prefix, names.len())) {
+        names.push(format!("{}_{}", prefix, names.len()));
+    }
+    names
+}
+
+/// Gets names of region properties (region if single, region_0, region_1, ... otherwise)
+fn region_names(nb_regions: usize) -> Vec<String> {
+    if nb_regions == 1 {
+        vec!["region".to_string()]
+    } else {
+        (0..nb_regions)
+            .map(|ind_region| format!("region_{}", ind_region))
+            .collect()
+    }
+}
+
+/// Saves TetGen files (basename.node, basename.ele, and basename.face if mesh has faces)
+///
+/// Properties are written following the mapping of [`load_tetmesh_tetgen`], indices start at 0.
+pub fn save_tetmesh_tetgen(basename: &str, mesh: &TetMesh3D) -> Result<()> {
+    // save nodes
+    let vertex_properties = mesh.get_vertex_properties();
+    let attributes = indexed_names(vertex_properties, "attribute");
+    let has_marker = vertex_properties.has_property("boundary_marker");
+    let mut file = BufWriter::new(File::create(basename.to_owned() + ".node")?);
+    writeln!(
+        file,
+        "{} 3 {} {}",
+        mesh.get_nb_vertices(),
+        attributes.len(),
+        has_marker as usize
+    )?;
+    for (ind_vertex, vertex) in mesh.get_vertices().iter().enumerate() {
+        write!(
+            file,
+            "{} {} {} {}",
+            ind_vertex, vertex[0], vertex[1], vertex[2]
+        )?;
+        for name in attributes.iter() {
+            write!(
+                file,
+                " {}",
+                vertex_properties.get_property_value_f64(ind_vertex, name)?
+            )?;
+        }
+        if has_marker {
+            write!(
+                file,
+                " {}",
+                vertex_properties.get_property_value_f64(ind_vertex, "boundary_marker")? as i64
+            )?;
+        }
+        writeln!(file)?;
+    }
+    file.flush()?;
+
+    // save tetrahedra
+    let tetrahedron_properties = mesh.get_tetrahedron_properties();
+    let regions = if tetrahedron_properties.has_property("region") {
+        region_names(1)
+    } else {
+        indexed_names(tetrahedron_properties, "region")
+    };
+    let mut file = BufWriter::new(File::create(basename.to_owned() + ".ele")?);
+    writeln!(file, "{} 4 {}", mesh.get_nb_tetrahedra(), regions.len())?;
+    for (ind_tetra, [i0, i1, i2, i3]) in mesh.get_tetrahedra().iter().enumerate() {
+        write!(file, "{} {} {} {} {}", ind_tetra, i0, i1, i2, i3)?;
+        for name in regions.iter() {
+            write!(
+                file,
+                " {}",
+                tetrahedron_properties.get_property_value_f64(ind_tetra, name)?
+            )?;
+        }
+        writeln!(file)?;
+    }
+    file.flush()?;
+
+    // save faces
+    if mesh.get_nb_faces() != 0 {
+        let face_properties = mesh.get_face_properties();
+        let has_marker = face_properties.has_property("boundary_marker");
+        let mut file = BufWriter::new(File::create(basename.to_owned() + ".face")?);
+        writeln!(file, "{} {}", mesh.get_nb_faces(), has_marker as usize)?;
+        for ind_face in 0..mesh.get_nb_faces() {
+            let [i0, i1, i2] = mesh.get_face(ind_face)?;
+            write!(file, "{} {} {} {}", ind_face, i0, i1, i2)?;
+            if has_marker {
+                write!(
+                    file,
+                    " {}",
+                    face_properties.get_property_value_f64(ind_face, "boundary_marker")? as i64
+                )?;
+            }
+            writeln!(file)?;
+        }
+        file.flush()?;
+    }
+
+    Ok(())
+}
+
+/// Gets physical tag from physical_tag property (0 means no physical group)
+fn physical_tag(properties: &PropertySet, ind_element: usize) -> Result<Option<i32>> {
+    if !properties.has_property("physical_tag") {
+        return Ok(None);
+    }
+    let tag = properties.get_property_value_f64(ind_element, "physical_tag")? as i32;
+    Ok(if tag != 0 { Some(tag) } else { None })
+}
+
+/// Loads tetrahedra and triangles of a Gmsh file (MSH 4.1 ASCII) as tetrahedral mesh
+///
+/// Triangles are stored as faces. Physical tags are stored in the physical_tag
+/// tetrahedron and face properties (0 if no physical group).
+pub fn load_tetmesh_gmsh(filename: &str) -> Result<TetMesh3D> {
+    let content = read_gmsh(filename)?;
+    let mut mesh = TetMesh3D::new();
+    for vertex in content.vertices.iter() {
+        mesh.insert_vertex(*vertex);
+    }
+
+    let has_physical = |element_type: usize| {
+        content
+            .elements
+            .iter()
+            .any(|element| element.element_type == element_type && element.physical_tag.is_some())
+    };
+    if has_physical(GMSH_TETRAHEDRON) {
+        mesh.add_tetrahedron_property(
+            "physical_tag".to_string(),
+            PropertyType::Scalar(ScalarType::Int),
+            Property::Int(0),
+        );
+    }
+    if has_physical(GMSH_TRIANGLE) {
+        mesh.add_face_property(
+            "physical_tag".to_string(),
+            PropertyType::Scalar(ScalarType::Int),
+            Property::Int(0),
+        );
+    }
+
+    for element in content.elements.iter() {
+        let v = &element.vertices;
+        if element.element_type == GMSH_TETRAHEDRON {
+            let ind_tetra = mesh.insert_tetrahedron([v[0], v[1], v[2], v[3]])?;
+            if let Some(tag) = element.physical_tag {
+                mesh.set_tetrahedron_property_value(
+                    ind_tetra,
+                    "physical_tag".to_string(),
+                    Property::Int(tag),
+                )?;
+            }
+        } else {
+            let ind_face = mesh.insert_face([v[0], v[1], v[2]])?;
+            if let Some(tag) = element.physical_tag {
+                mesh.set_face_property_value(
+                    ind_face,
+                    "physical_tag".to_string(),
+                    Property::Int(tag),
+                )?;
+            }
+        }
+    }
+
+    Ok(mesh)
+}
+
+/// Saves tetrahedral mesh (tetrahedra and faces) as Gmsh file (MSH 4.1 ASCII)
+///
+/// The physical_tag tetrahedron and face properties, if any, give physical groups
+/// (0 if no physical group).
+pub fn save_tetmesh_gmsh(filename: &str, mesh: &TetMesh3D) -> Result<()> {
+    let mut elements = Vec::new();
+    for (ind_tetra, tetra) in mesh.get_tetrahedra().iter().enumerate() {
+        elements.push(GmshElement {
+            element_type: GMSH_TETRAHEDRON,
+            vertices: tetra.to_vec(),
+            physical_tag: physical_tag(mesh.get_tetrahedron_properties(), ind_tetra)?,
+        });
+    }
+    for ind_face in 0..mesh.get_nb_faces() {
+        elements.push(GmshElement {
+            element_type: GMSH_TRIANGLE,
+            vertices: mesh.get_face(ind_face)?.to_vec(),
+            physical_tag: physical_tag(mesh.get_face_properties(), ind_face)?,
+        });
+    }
+
+    write_gmsh(filename, mesh.get_vertices(), &elements)
+}
+
+/// Loads tetrahedra of a Gmsh file (MSH 4.1 ASCII) as closed 3D simplicial, with node coordinates
+///
+/// Node value is vertex index, the infinite node closing the boundary is returned if any
+/// (see [`TetMesh3D::to_simplicial3`]). Physical tags are stored in the physical_tag
+/// tetrahedron attribute (i32, 0 if no physical group or closing tetrahedron).
+pub fn load_simplicial3_gmsh(
+    filename: &str,
+    register_node_halfedges: bool,
+) -> Result<(Simplicial3, Vec<Vector3<f64>>, Option<usize>)> {
+    let mesh = load_tetmesh_gmsh(filename)?;
+    let (mut simpl, infinite_node) = mesh.to_simplicial3(register_node_halfedges)?;
+
+    let tetrahedron_properties = mesh.get_tetrahedron_properties();
+    if tetrahedron_properties.has_property("physical_tag") {
+        let attributes = simpl.get_tetrahedron_attributes_mut();
+        attributes.add_attribute("physical_tag".to_string(), 0i32);
+        // closing tetrahedra are appended after mesh tetrahedra
+        for ind_tetra in 0..mesh.get_nb_tetrahedra() {
+            let tag = physical_tag(tetrahedron_properties, ind_tetra)?.unwrap_or(0);
+            attributes.set_attribute_value(ind_tetra, "physical_tag", tag)?;
+        }
+    }
+
+    Ok((simpl, mesh.get_vertices().clone(), infinite_node))
+}
+
+/// Saves 3D simplicial as Gmsh file (MSH 4.1 ASCII), node values are indices of node coordinates
+///
+/// Tetrahedra touching the infinite node, if given, are skipped.
+/// The physical_tag tetrahedron attribute (i32), if any, gives physical groups
+/// (0 if no physical group).
+pub fn save_simplicial3_gmsh(
+    filename: &str,
+    simpl: &Simplicial3,
+    node_coords: &[Vector3<f64>],
+    infinite_node: Option<usize>,
+) -> Result<()> {
+    let physical_tags = simpl
+        .get_tetrahedron_attributes()
+        .get_attribute_values::<i32>("physical_tag")
+        .ok();
+
+    let mut elements = Vec::new();
+    for tetra in simpl.get_all_tetrahedra() {
+        let nodes = tetra.node_values();
+        if infinite_node.is_some_and(|inf| nodes.contains(&inf)) {
+            continue;
+        }
+        let physical_tag = physical_tags
+            .map(|tags| tags[tetra.ind()])
+            .filter(|&tag| tag != 0);
+        elements.push(GmshElement {
+            element_type: GMSH_TETRAHEDRON,
+            vertices: nodes.to_vec(),
+            physical_tag,
+        });
+    }
+
+    write_gmsh(filename, node_coords, &elements)
+}
@@ -0,0 +1,8 @@
+// Tetrahedral mesh 3D structure
+mod tetmesh3d;
+pub use tetmesh3d::TetMesh3D;
+
+/// Input/Ouput functions
+pub mod io;
+
+mod unit_tests;
@@ -0,0 +1,284 @@
+use anyhow::Result;
+use nalgebra::base::*;
+use ply_rs::ply::{Property, PropertyType};
+
+use crate::graph_structure::simplicial3::{
+    build_from_tetrahedron_list, close_tetrahedron_list, Simplicial3,
+};
+use crate::mesh_structure::property_set::PropertySet;
+
+#[derive(Clone)]
+/// TetMesh3D is a struct that represents a tetrahedral mesh in three-dimensional space.
+pub struct TetMesh3D {
+    /// A vector of vertices, where each vertex is a 3D vector,
+    /// which is a point with x, y, z coordinates.
+    pub(super) vertices: Vec<Vector3<f64>>,
+
+    /// Set of tetrahedra (4 vertices)
+    pub(super) tetrahedra: Vec<[usize; 4]>,
+
+    /// Set of faces (boundary or constrained triangles)
+    pub(super) faces: Vec<[usize; 3]>,
+
+    /// Set of vertices properties (coordinates excluded)
+    pub(super) vertex_properties: PropertySet,
+
+    /// Set of tetrahedra properties (vertex indices excluded)
+    pub(super) tetrahedron_properties: PropertySet,
+
+    /// Set of faces properties (vertex indices excluded)
+    pub(super) face_properties: PropertySet,
+}
+
+impl TetMesh3D {
+    /// Empty tetrahedral mesh constructor
+    pub fn new() -> TetMesh3D {
+        TetMesh3D {
+            vertices: Vec::new(),
+            tetrahedra: Vec::new(),
+            faces: Vec::new(),
+            vertex_properties: PropertySet::new("vertex", 0),
+            tetrahedron_properties: PropertySet::new("tetrahedron", 0),
+            face_properties: PropertySet::new("face", 0),
+        }
+    }
+
+    /// Create tetrahedral mesh from vertices, tetrahedra and faces
+    pub fn create(
+        vertices: Vec<Vector3<f64>>,
+        tetrahedra: Vec<[usize; 4]>,
+        faces: Vec<[usize; 3]>,
+    ) -> TetMesh3D {
+        TetMesh3D {
+            vertex_properties: PropertySet::new("vertex", vertices.len()),
+            tetrahedron_properties: PropertySet::new("tetrahedron", tetrahedra.len()),
+            face_properties: PropertySet::new("face", faces.len()),
+            vertices,
+            tetrahedra,
+            faces,
+        }
+    }
+
+    /// Gets number of vertices
+    pub fn get_nb_vertices(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Gets vertex coordinates
+    pub fn get_vertex(&self, ind_vertex: usize) -> Result<Vector3<f64>> {
+        if ind_vertex >= self.vertices.len() {
+            return Err(anyhow::Error::msg("Vertex index out of bounds"));
+        }
+        Ok(self.vertices[ind_vertex])
+    }
+
+    /// Gets all vertices
+    pub fn get_vertices(&self) -> &Vec<Vector3<f64>> {
+        &self.vertices
+    }
+
+    /// Gets number of tetrahedra
+    pub fn get_nb_tetrahedra(&self) -> usize {
+        self.tetrahedra.len()
+    }
+
+    /// Gets tetrahedron vertex indices
+    pub fn get_tetrahedron(&self, ind_tetra: usize) -> Result<[usize; 4]> {
+        if ind_tetra >= self.tetrahedra.len() {
+            return Err(anyhow::Error::msg("Tetrahedron index out of bounds"));
+        }
+        Ok(self.tetrahedra[ind_tetra])
+    }
+
+    /// Gets all tetrahedra
+    pub fn get_tetrahedra(&self) -> &Vec<[usize; 4]> {
+        &self.tetrahedra
+    }
+
+    /// Gets number of faces
+    pub fn get_nb_faces(&self) -> usize {
+        self.faces.len()
+    }
+
+    /// Gets face vertex indices
+    pub fn get_face(&self, ind_face: usize) -> Result<[usize; 3]> {
+        if ind_face >= self.faces.len() {
+            return Err(anyhow::Error::msg("Face index out of bounds"));
+        }
+        Ok(self.faces[ind_face])
+    }
+
+    /// Inserts new vertex
+    pub fn insert_vertex(&mut self, vertex: Vector3<f64>) -> usize {
+        self.vertices.push(vertex);
+        self.vertex_properties.push_element();
+        self.vertices.len() - 1
+    }
+
+    /// Inserts new tetrahedron
+    pub fn insert_tetrahedron(&mut self, tetrahedron: [usize; 4]) -> Result<usize> {
+        if tetrahedron.iter().any(|&ind| ind >= self.vertices.len()) {
+            return Err(anyhow::Error::msg("Vertex index out of bounds"));
+        }
+        self.tetrahedra.push(tetrahedron);
+        self.tetrahedron_properties.push_element();
+        Ok(self.tetrahedra.len() - 1)
+    }
+
+    /// Inserts new face
+    pub fn insert_face(&mut self, face: [usize; 3]) -> Result<usize> {
+        if face.iter().any(|&ind| ind >= self.vertices.len()) {
+            return Err(anyhow::Error::msg("Vertex index out of bounds"));
+        }
+        self.faces.push(face);
+        self.face_properties.push_element();
+        Ok(self.faces.len() - 1)
+    }
+
+    /// Signed volume of a tetrahedron (positive if fourth vertex is on the side
+    /// of the counterclockwise normal of the first three)
+    pub fn tetrahedron_signed_volume(&self, ind_tetra: usize) -> Result<f64> {
+        let [i0, i1, i2, i3] = self.get_tetrahedron(ind_tetra)?;
+        let v0 = self.vertices[i0];
+        let e1 = self.vertices[i1] - v0;
+        let e2 = self.vertices[i2] - v0;
+        let e3 = self.vertices[i3] - v0;
+        Ok(e1.cross(&e2).dot(&e3) / 6.0)
+    }
+
+    /// Orients all tetrahedra with positive signed volume
+    pub fn orient_tetrahedra(&mut self) -> Result<()> {
+        for ind_tetra in 0..self.tetrahedra.len() {
+            if self.tetrahedron_signed_volume(ind_tetra)? < 0.0 {
+                self.tetrahedra[ind_tetra].swap(2, 3);
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds closed 3D simplicial from tetrahedra (node value is vertex index)
+    ///
+    /// Tetrahedra are oriented using vertex coordinates.
+    /// If the mesh has a boundary, it is closed with an infinite node
+    /// (value equal to number of vertices), which is returned.
+    pub fn to_simplicial3(
+        &self,
+        register_node_halfedges: bool,
+    ) -> Result<(Simplicial3, Option<usize>)> {
+        let mut oriented = self.clone();
+        oriented.orient_tetrahedra()?;
+
+        let infinite_node = self.vertices.len();
+        let closed = close_tetrahedron_list(&oriented.tetrahedra, infinite_node)?;
+        let infinite_opt = if closed.len() != oriented.tetrahedra.len() {
+            Some(infinite_node)
+        } else {
+            None
+        };
+
+        let simplicial = build_from_tetrahedron_list(closed, register_node_halfedges)?;
+        Ok((simplicial, infinite_opt))
+    }
+
+    /// Adds a vertex property with a default value
+    pub fn add_vertex_property(
+        &mut self,
+        property_name: String,
+        property_type: PropertyType,
+        property_default: Property,
+    ) {
+        self.vertex_properties
+            .add_property(property_name, property_type, property_default);
+    }
+
+    /// Sets a vertex property
+    pub fn set_vertex_property_value(
+        &mut self,
+        ind_vertex: usize,
+        property_name: String,
+        property_value: Property,
+    ) -> Result<()> {
+        if ind_vertex >= self.vertices.len() {
+            return Err(anyhow::Error::msg(
+                "set_vertex_property_value(): Index out of bounds",
+            ));
+        }
+        self.vertex_properties
+            .set_property_value(ind_vertex, property_name, property_value)
+    }
+
+    /// Get vertex properties
+    pub fn get_vertex_properties(&self) -> &PropertySet {
+        &self.vertex_properties
+    }
+
+    /// Adds a tetrahedron property with a default value
+    pub fn add_tetrahedron_property(
+        &mut self,
+        property_name: String,
+        property_type: PropertyType,
+        property_default: Property,
+    ) {
+        self.tetrahedron_properties
+            .add_property(property_name, property_type, property_default);
+    }
+
+    /// Sets a tetrahedron property
+    pub fn set_tetrahedron_property_value(
+        &mut self,
+        ind_tetra: usize,
+        property_name: String,
+        property_value: Property,
+    ) -> Result<()> {
+        if ind_tetra >= self.tetrahedra.len() {
+            return Err(anyhow::Error::msg(
+                "set_tetrahedron_property_value(): Index out of bounds",
+            ));
+        }
+        self.tetrahedron_properties
+            .set_property_value(ind_tetra, property_name, property_value)
+    }
+
+    /// Get tetrahedron properties
+    pub fn get_tetrahedron_properties(&self) -> &PropertySet {
+        &self.tetrahedron_properties
+    }
+
+    /// Adds a face property with a default value
+    pub fn add_face_property(
+        &mut self,
+        property_name: String,
+        property_type: PropertyType,
+        property_default: Property,
+    ) {
+        self.face_properties
+            .add_property(property_name, property_type, property_default);
+    }
+
+    /// Sets a face property
+    pub fn set_face_property_value(
+        &mut self,
+        ind_face: usize,
+        property_name: String,
+        property_value: Property,
+    ) -> Result<()> {
+        if ind_face >= self.faces.len() {
+            return Err(anyhow::Error::msg(
+                "set_face_property_value(): Index out of bounds",
+            ));
+        }
+        self.face_properties
+            .set_property_value(ind_face, property_name, property_value)
+    }
+
+    /// Get face properties
+    pub fn get_face_properties(&self) -> &PropertySet {
+        &self.face_properties
+    }
+}
+
+impl Default for TetMesh3D {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,212 @@
+#[cfg(test)]
+mod tetmesh_test {
+    use anyhow::Result;
+    use nalgebra::base::*;
+
+    use super::super::io::{
+        load_simplicial3_gmsh, load_tetmesh_gmsh, load_tetmesh_tetgen, save_simplicial3_gmsh,
+        save_tetmesh_gmsh, save_tetmesh_tetgen,
+    };
+    use super::super::tetmesh3d::TetMesh3D;
+    use crate::graph_structure::simplicial3::simplicial3_is_valid;
+
+    use ply_rs::ply::{Property, PropertyType, ScalarType};
+
+    fn build_bipyramid() -> Result<TetMesh3D> {
+        let vertex_coordinates = vec![
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, -1.0),
+        ];
+        // second tetrahedron is negatively oriented
+        let tetrahedra = vec![[0, 1, 2, 3], [0, 1, 2, 4]];
+        let faces = vec![[0, 2, 1], [1, 2, 3]];
+        Ok(TetMesh3D::create(vertex_coordinates, tetrahedra, faces))
+    }
+
+    #[test]
+    fn tetgen_io_test() -> Result<()> {
+        let mut mesh = build_bipyramid()?;
+        mesh.add_vertex_property(
+            "attribute_0".to_string(),
+            PropertyType::Scalar(ScalarType::Double),
+            Property::Double(0.),
+        );
+        mesh.add_vertex_property(
+            "boundary_marker".to_string(),
+            PropertyType::Scalar(ScalarType::Int),
+            Property::Int(0),
+        );
+        mesh.add_tetrahedron_property(
+            "region".to_string(),
+            PropertyType::Scalar(ScalarType::Double),
+            Property::Double(0.),
+        );
+        mesh.add_face_property(
+            "boundary_marker".to_string(),
+            PropertyType::Scalar(ScalarType::Int),
+            Property::Int(0),
+        );
+        mesh.set_vertex_property_value(3, "attribute_0".to_string(), Property::Double(2.5))?;
+        mesh.set_vertex_property_value(4, "boundary_marker".to_string(), Property::Int(7))?;
+        mesh.set_tetrahedron_property_value(1, "region".to_string(), Property::Double(3.))?;
+        mesh.set_face_property_value(1, "boundary_marker".to_string(), Property::Int(-2))?;
+
+        let basename = std::env::temp_dir()
+            .join("skeletal_structures_tetgen_test")
+            .to_string_lossy()
+            .to_string();
+        save_tetmesh_tetgen(&basename, &mesh)?;
+        let mesh_loaded = load_tetmesh_tetgen(&basename)?;
+        for ext in [".node", ".ele", ".face"] {
+            std::fs::remove_file(basename.clone() + ext)?;
+        }
+
+        assert_eq!(mesh_loaded.get_vertices(), mesh.get_vertices());
+        assert_eq!(mesh_loaded.get_tetrahedra(), mesh.get_tetrahedra());
+        assert_eq!(mesh_loaded.get_face(1)?, [1, 2, 3]);
+        let vertex_properties = mesh_loaded.get_vertex_properties();
+        assert_eq!(
+            vertex_properties.get_property_value_f64(3, "attribute_0")?,
+            2.5
+        );
+        assert_eq!(
+            vertex_properties.get_property_value_f64(4, "boundary_marker")?,
+            7.
+        );
+        assert_eq!(
+            mesh_loaded
+                .get_tetrahedron_properties()
+                .get_property_value_f64(1, "region")?,
+            3.
+        );
+        assert_eq!(
+            mesh_loaded
+                .get_face_properties()
+                .get_property_value_f64(1, "boundary_marker")?,
+            -2.
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn tetgen_load_test() -> Result<()> {
+        let basename = std::env::temp_dir()
+            .join("skeletal_structures_tetgen_load_test")
+            .to_string_lossy()
+            .to_string();
+        std::fs::write(
+            basename.clone() + ".node",
+            "# one based nodes\n4 3 0 0\n\n1 0 0 0\n2 1 0 0 # comment\n3 0 1 0\n4 0 0 1\n",
+        )?;
+        std::fs::write(basename.clone() + ".ele", "1 4 0\n1 1 2 3 4\n")?;
+        let mesh = load_tetmesh_tetgen(&basename)?;
+        std::fs::write(basename.clone() + ".ele", "1 4 0\n1 1 2 3 5\n")?;
+        let err = load_tetmesh_tetgen(&basename);
+        std::fs::remove_file(basename.clone() + ".node")?;
+        std::fs::remove_file(basename.clone() + ".ele")?;
+
+        assert_eq!(mesh.get_nb_vertices(), 4);
+        assert_eq!(mesh.get_tetrahedron(0)?, [0, 1, 2, 3]);
+        assert_eq!(mesh.get_nb_faces(), 0);
+        assert!(err.is_err());
+
+        // several region attributes, kept when saving
+        std::fs::write(
+            basename.clone() + ".node",
+            "4 3 0 0\n0 0 0 0\n1 1 0 0\n2 0 1 0\n3 0 0 1\n",
+        )?;
+        std::fs::write(basename.clone() + ".ele", "1 4 2\n0 0 1 2 3 5 -1.5\n")?;
+        let mesh = load_tetmesh_tetgen(&basename)?;
+        save_tetmesh_tetgen(&basename, &mesh)?;
+        let mesh_loaded = load_tetmesh_tetgen(&basename)?;
+        std::fs::remove_file(basename.clone() + ".node")?;
+        std::fs::remove_file(basename.clone() + ".ele")?;
+
+        for mesh in [mesh, mesh_loaded] {
+            let properties = mesh.get_tetrahedron_properties();
+            assert!(!properties.has_property("region"));
+            assert_eq!(properties.get_property_value_f64(0, "region_0")?, 5.);
+            assert_eq!(properties.get_property_value_f64(0, "region_1")?, -1.5);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn to_simplicial3_test() -> Result<()> {
+        let mesh = build_bipyramid()?;
+        let (simplicial, infinite_node) = mesh.to_simplicial3(true)?;
+
+        assert_eq!(infinite_node, Some(5));
+        // 2 tetrahedra, and 6 closing tetrahedra
+        assert_eq!(simplicial.get_nb_tetrahedra(), 8);
+        assert!(simplicial3_is_valid(&simplicial)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn gmsh_io_test() -> Result<()> {
+        let mut mesh = build_bipyramid()?;
+        mesh.add_tetrahedron_property(
+            "physical_tag".to_string(),
+            PropertyType::Scalar(ScalarType::Int),
+            Property::Int(0),
+        );
+        mesh.set_tetrahedron_property_value(1, "physical_tag".to_string(), Property::Int(4))?;
+
+        let filename = std::env::temp_dir()
+            .join("skeletal_structures_tetmesh_gmsh_test.msh")
+            .to_string_lossy()
+            .to_string();
+        save_tetmesh_gmsh(&filename, &mesh)?;
+        let mesh_loaded = load_tetmesh_gmsh(&filename)?;
+        let (simpl, coords, infinite_node) = load_simplicial3_gmsh(&filename, false)?;
+        std::fs::remove_file(&filename)?;
+
+        assert_eq!(mesh_loaded.get_vertices(), mesh.get_vertices());
+        assert_eq!(mesh_loaded.get_tetrahedra(), mesh.get_tetrahedra());
+        assert_eq!(mesh_loaded.get_nb_faces(), 2);
+        assert_eq!(mesh_loaded.get_face(1)?, [1, 2, 3]);
+        let tetrahedron_properties = mesh_loaded.get_tetrahedron_properties();
+        assert_eq!(
+            tetrahedron_properties.get_property_value_f64(0, "physical_tag")?,
+            0.
+        );
+        assert_eq!(
+            tetrahedron_properties.get_property_value_f64(1, "physical_tag")?,
+            4.
+        );
+        assert!(!mesh_loaded
+            .get_face_properties()
+            .has_property("physical_tag"));
+
+        assert_eq!(coords.len(), 5);
+        assert_eq!(infinite_node, Some(5));
+        assert_eq!(simpl.get_nb_tetrahedra(), 8);
+        let tags = simpl
+            .get_tetrahedron_attributes()
+            .get_attribute_values::<i32>("physical_tag")?;
+        assert_eq!(tags[1], 4);
+        assert_eq!(tags[7], 0);
+
+        // closing tetrahedra are not saved
+        save_simplicial3_gmsh(&filename, &simpl, &coords, infinite_node)?;
+        let mesh_simpl = load_tetmesh_gmsh(&filename)?;
+        std::fs::remove_file(&filename)?;
+        assert_eq!(mesh_simpl.get_nb_tetrahedra(), 2);
+        assert_eq!(mesh_simpl.get_nb_faces(), 0);
+        assert_eq!(
+            mesh_simpl
+                .get_tetrahedron_properties()
+                .get_property_value_f64(1, "physical_tag")?,
+            4.
+        );
+
+        Ok(())
+    }
+}